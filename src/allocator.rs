@@ -0,0 +1,49 @@
+//! A stand-in for the unstable allocator API, used without the `allocator-api` feature.
+
+use std::{
+    alloc::{alloc, dealloc, Layout},
+    ptr::NonNull,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+/// The global memory allocator. A stand-in for `std::alloc::Global`, which is unstable.
+pub struct Global;
+
+/// A memory allocator. A stand-in for `std::alloc::Allocator`, which is unstable.
+///
+/// This trait is sealed and only implemented for [Global]. Enable the `allocator-api` feature on nightly to use other allocators.
+pub trait Allocator: private::Sealed {}
+
+impl Allocator for Global {}
+
+pub(crate) mod private {
+    use super::*;
+
+    #[derive(Debug)]
+    /// The error [Sealed::allocate] returns.
+    pub struct AllocError;
+
+    /// The subset of `std::alloc::Allocator` this crate uses.
+    ///
+    /// `Default` is required because `Vec<T>` doesn't carry its allocator on stable.
+    pub trait Sealed: Default {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
+
+        /// # Safety
+        ///
+        /// See `std::alloc::Allocator::deallocate`.
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+    }
+
+    impl Sealed for Global {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            debug_assert_ne!(layout.size(), 0);
+            let ptr = NonNull::new(unsafe { alloc(layout) }).ok_or(AllocError)?;
+            Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            dealloc(ptr.as_ptr(), layout)
+        }
+    }
+}
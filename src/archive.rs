@@ -0,0 +1,174 @@
+//! [rkyv] support.
+
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    mem::{align_of, size_of},
+    slice,
+};
+
+use rkyv::{
+    bytecheck::{CheckBytes, Verify},
+    munge::munge,
+    primitive::ArchivedUsize,
+    rancor::{Fallible, Source},
+    ser::{Writer, WriterExt},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Portable, Serialize,
+};
+
+use crate::{Allocator, ElementBytes, TypeErasedVec};
+
+/// The archived form of a [TypeErasedVec], which can be read in place without copying.
+///
+/// Only vecs constructed with [TypeErasedVec::from_pod_vec] can be archived. The element bytes are stored as is,
+/// so an archive can only be read on a machine with the same endianness as the one that wrote it.
+///
+/// Checked access fails if the length doesn't match the number of bytes.
+#[derive(Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
+#[rkyv(crate = rkyv)]
+#[repr(C)]
+pub struct ArchivedTypeErasedVec {
+    len: ArchivedUsize,
+    element_size: ArchivedUsize,
+    bytes: ArchivedVec<u8>,
+}
+
+impl ArchivedTypeErasedVec {
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the size of each element in bytes.
+    pub fn element_size(&self) -> usize {
+        self.element_size.to_native() as usize
+    }
+
+    /// Gets the elements as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Iterates over the byte representation of each element. See [TypeErasedVec::iter_element_bytes].
+    pub fn iter_element_bytes(&self) -> ElementBytes<'_> {
+        ElementBytes::new(self.as_bytes(), self.element_size(), self.len())
+    }
+
+    /// Views the elements as `T`, or returns `None` if `T` has a different size or the bytes are not aligned for `T`.
+    pub fn get<T: bytemuck::Pod>(&self) -> Option<&[T]> {
+        if size_of::<T>() != self.element_size() {
+            return None;
+        }
+        let bytes = self.as_bytes();
+        if bytes.as_ptr().align_offset(align_of::<T>()) != 0 {
+            return None;
+        }
+        // Any bit pattern is a valid `T`, so only size and alignment need checking.
+        Some(unsafe { slice::from_raw_parts(bytes.as_ptr().cast(), self.len()) })
+    }
+}
+
+// `get` and `iter_element_bytes` rely on the length matching the bytes.
+unsafe impl<C: Fallible + ?Sized> Verify<C> for ArchivedTypeErasedVec
+where
+    C::Error: Source,
+{
+    fn verify(&self, _context: &mut C) -> Result<(), C::Error> {
+        if self.len().checked_mul(self.element_size()) == Some(self.bytes.len()) {
+            Ok(())
+        } else {
+            Err(C::Error::new(LengthMismatchError))
+        }
+    }
+}
+
+impl Debug for ArchivedTypeErasedVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedTypeErasedVec")
+            .field("len", &self.len())
+            .field("element_size", &self.element_size())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The resolver for [ArchivedTypeErasedVec].
+pub struct TypeErasedVecResolver {
+    bytes: VecResolver,
+}
+
+impl Debug for TypeErasedVecResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeErasedVecResolver")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: Allocator> Archive for TypeErasedVec<A> {
+    type Archived = ArchivedTypeErasedVec;
+    type Resolver = TypeErasedVecResolver;
+
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    fn resolve(&self, resolver: TypeErasedVecResolver, out: Place<ArchivedTypeErasedVec>) {
+        munge!(let ArchivedTypeErasedVec { len, element_size, bytes } = out);
+        let size = self.vtable.layout.size();
+        self.len().resolve((), len);
+        size.resolve((), element_size);
+        ArchivedVec::<u8>::resolve_from_len(self.len() * size, resolver.bytes, bytes);
+    }
+}
+
+impl<A: Allocator, S: Fallible + Writer + ?Sized> Serialize<S> for TypeErasedVec<A>
+where
+    S::Error: Source,
+{
+    /// Fails if `self` was not constructed with [TypeErasedVec::from_pod_vec].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    fn serialize(&self, serializer: &mut S) -> Result<TypeErasedVecResolver, S::Error> {
+        let bytes = self.as_bytes().ok_or_else(|| S::Error::new(NotPodError))?;
+        serializer.align(self.vtable.layout.align())?;
+        let pos = serializer.pos();
+        serializer.write(bytes)?;
+        Ok(TypeErasedVecResolver {
+            bytes: VecResolver::from_pos(pos),
+        })
+    }
+}
+
+/// Archiving a vec that was not constructed with [TypeErasedVec::from_pod_vec].
+#[derive(Debug)]
+struct NotPodError;
+
+impl Display for NotPodError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TypeErasedVec was not constructed with from_pod_vec")
+    }
+}
+
+impl Error for NotPodError {}
+
+/// Accessing an archived vec whose length doesn't match its number of bytes.
+#[derive(Debug)]
+struct LengthMismatchError;
+
+impl Display for LengthMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archived TypeErasedVec length doesn't match its number of bytes"
+        )
+    }
+}
+
+impl Error for LengthMismatchError {}
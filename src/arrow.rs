@@ -0,0 +1,244 @@
+//! Conversion to and from Apache Arrow buffers and arrays.
+
+use arrow_buffer::Buffer;
+use arrow_data::ArrayData;
+use arrow_schema::DataType;
+
+use crate::{
+    dispatch, missing_capability, Error, Global, NullableTypeErasedVec, ScalarType, TypeErasedVec,
+};
+
+impl ScalarType {
+    /// Returns the Arrow data type of primitive arrays of the scalar type.
+    ///
+    /// # Panics
+    ///
+    /// Panics for `BF16`, which Arrow has no data type for.
+    #[track_caller]
+    pub fn to_arrow_data_type(self) -> DataType {
+        match self {
+            ScalarType::I8 => DataType::Int8,
+            ScalarType::I16 => DataType::Int16,
+            ScalarType::I32 => DataType::Int32,
+            ScalarType::I64 => DataType::Int64,
+            ScalarType::U8 => DataType::UInt8,
+            ScalarType::U16 => DataType::UInt16,
+            ScalarType::U32 => DataType::UInt32,
+            ScalarType::U64 => DataType::UInt64,
+            ScalarType::F32 => DataType::Float32,
+            ScalarType::F64 => DataType::Float64,
+            #[cfg(feature = "half")]
+            ScalarType::F16 => DataType::Float16,
+            #[cfg(feature = "half")]
+            ScalarType::BF16 => panic!("Arrow has no data type for bf16"),
+        }
+    }
+
+    /// Returns the scalar type of primitive arrays of `data_type`, or `None` if there's none.
+    pub fn from_arrow_data_type(data_type: &DataType) -> Option<Self> {
+        Some(match data_type {
+            DataType::Int8 => ScalarType::I8,
+            DataType::Int16 => ScalarType::I16,
+            DataType::Int32 => ScalarType::I32,
+            DataType::Int64 => ScalarType::I64,
+            DataType::UInt8 => ScalarType::U8,
+            DataType::UInt16 => ScalarType::U16,
+            DataType::UInt32 => ScalarType::U32,
+            DataType::UInt64 => ScalarType::U64,
+            DataType::Float32 => ScalarType::F32,
+            DataType::Float64 => ScalarType::F64,
+            #[cfg(feature = "half")]
+            DataType::Float16 => ScalarType::F16,
+            _ => return None,
+        })
+    }
+}
+
+impl TypeErasedVec<Global> {
+    /// Converts to an Arrow buffer without copying.
+    ///
+    /// ```
+    /// use type_erased_vec::{ScalarType, TypeErasedVec};
+    ///
+    /// let buffer = TypeErasedVec::from_scalar_vec(vec![1u32, 2, 3]).into_arrow_buffer();
+    /// assert_eq!(buffer.typed_data::<u32>(), [1, 2, 3]);
+    /// let vec = TypeErasedVec::from_arrow_buffer(buffer, ScalarType::U32).unwrap();
+    /// assert_eq!(unsafe { vec.get::<u32>() }, [1, 2, 3]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not a [ScalarType], or if `self` is leaked.
+    #[track_caller]
+    pub fn into_arrow_buffer(self) -> Buffer {
+        let Some(scalar_type) = self.scalar_type() else {
+            missing_capability(self.vtable, "from_scalar_vec or new_scalar");
+        };
+        match scalar_type {
+            #[cfg(feature = "half")]
+            ScalarType::F16 => Buffer::from_vec(unsafe { self.into_vec::<half::f16>() }),
+            // Arrow buffers can't hold `bf16`, but can hold its bits. Collecting in place keeps the allocation.
+            #[cfg(feature = "half")]
+            ScalarType::BF16 => Buffer::from_vec(
+                unsafe { self.into_vec::<half::bf16>() }
+                    .into_iter()
+                    .map(half::bf16::to_bits)
+                    .collect::<Vec<_>>(),
+            ),
+            // `dispatch!` only calls this with the element type.
+            scalar_type => dispatch!(@primitive scalar_type, |T| Buffer::from_vec(unsafe {
+                self.into_vec::<T>()
+            })),
+        }
+    }
+
+    /// Converts an Arrow buffer to a vec of `scalar_type` elements, without copying if the buffer was allocated
+    /// as a `Vec` of them and is not shared or sliced.
+    ///
+    /// Fails if the length of `buffer` is not a multiple of the size of `scalar_type`.
+    pub fn from_arrow_buffer(buffer: Buffer, scalar_type: ScalarType) -> Result<Self, Error> {
+        let element_size = scalar_type.size();
+        if !buffer.len().is_multiple_of(element_size) {
+            // `Buffer::into_vec` would drop the trailing bytes.
+            return Err(Error::LengthNotMultipleOfElement {
+                len: buffer.len(),
+                element_size,
+            });
+        }
+        let buffer = match scalar_type {
+            #[cfg(feature = "half")]
+            ScalarType::F16 => buffer
+                .into_vec::<half::f16>()
+                .map(TypeErasedVec::from_scalar_vec),
+            #[cfg(feature = "half")]
+            ScalarType::BF16 => buffer.into_vec::<u16>().map(|vec| {
+                TypeErasedVec::from_scalar_vec(
+                    vec.into_iter()
+                        .map(half::bf16::from_bits)
+                        .collect::<Vec<_>>(),
+                )
+            }),
+            scalar_type => dispatch!(@primitive scalar_type, |T| buffer
+                .into_vec::<T>()
+                .map(TypeErasedVec::from_scalar_vec)),
+        };
+        buffer.or_else(|buffer| {
+            let mut vec = TypeErasedVec::new_scalar(scalar_type);
+            vec.try_extend_from_byte_slice(buffer.as_slice())?;
+            Ok(vec)
+        })
+    }
+
+    /// Converts to the data of a primitive Arrow array without nulls, without copying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not a [ScalarType], or if `self` is leaked.
+    #[track_caller]
+    pub fn into_arrow_array_data(self) -> ArrayData {
+        let Some(scalar_type) = self.scalar_type() else {
+            missing_capability(self.vtable, "from_scalar_vec or new_scalar");
+        };
+        let len = self.len();
+        let buffer = self.into_arrow_buffer();
+        // The buffer holds `len` aligned elements of the data type.
+        unsafe {
+            ArrayData::new_unchecked(
+                scalar_type.to_arrow_data_type(),
+                len,
+                Some(0),
+                None,
+                0,
+                vec![buffer],
+                vec![],
+            )
+        }
+    }
+
+    /// Converts the data of a primitive Arrow array to a vec of its elements, without copying if
+    /// [TypeErasedVec::from_arrow_buffer] can.
+    ///
+    /// Fails if the data type is not a [ScalarType], or if the array has nulls, which
+    /// [NullableTypeErasedVec::from_arrow_array_data] accepts.
+    pub fn from_arrow_array_data(data: ArrayData) -> Result<Self, Error> {
+        let Some(scalar_type) = ScalarType::from_arrow_data_type(data.data_type()) else {
+            return Err(Error::Unsupported("data type is not a scalar type"));
+        };
+        if data.null_count() != 0 {
+            return Err(Error::Unsupported("array has nulls"));
+        }
+        let size = scalar_type.size();
+        let buffer = data.buffers()[0].slice_with_length(data.offset() * size, data.len() * size);
+        drop(data);
+        Self::from_arrow_buffer(buffer, scalar_type)
+    }
+}
+
+impl NullableTypeErasedVec {
+    /// Converts to the data of a nullable primitive Arrow array, without copying the elements.
+    ///
+    /// ```
+    /// use type_erased_vec::{NullableTypeErasedVec, TypeErasedVec};
+    ///
+    /// let vec = TypeErasedVec::from_scalar_vec(vec![1i32, 0, 3]);
+    /// let vec = NullableTypeErasedVec::from_mask(vec, &[true, false, true]);
+    /// let data = vec.into_arrow_array_data();
+    /// assert_eq!(data.null_count(), 1);
+    /// let vec = NullableTypeErasedVec::from_arrow_array_data(data).unwrap();
+    /// assert!(!vec.is_valid(1));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not a [ScalarType], or if it is `BF16`, which Arrow has no data type for.
+    #[track_caller]
+    pub fn into_arrow_array_data(self) -> ArrayData {
+        let null_count = self.null_count();
+        let (vec, validity) = self.into_inner();
+        let Some(scalar_type) = vec.scalar_type() else {
+            missing_capability(vec.vtable, "from_scalar_vec or new_scalar");
+        };
+        let len = vec.len();
+        let buffer = vec.into_arrow_buffer();
+        // The buffer holds `len` aligned elements of the data type, and the bitmap has `null_count` clear bits for them.
+        unsafe {
+            ArrayData::new_unchecked(
+                scalar_type.to_arrow_data_type(),
+                len,
+                Some(null_count),
+                (null_count != 0).then(|| Buffer::from_vec(validity)),
+                0,
+                vec![buffer],
+                vec![],
+            )
+        }
+    }
+
+    /// Converts the data of a primitive Arrow array, which may have nulls, to a vec of its elements and their validity,
+    /// without copying the elements if [TypeErasedVec::from_arrow_buffer] can.
+    ///
+    /// Fails if the data type is not a [ScalarType].
+    pub fn from_arrow_array_data(data: ArrayData) -> Result<Self, Error> {
+        let Some(scalar_type) = ScalarType::from_arrow_data_type(data.data_type()) else {
+            return Err(Error::Unsupported("data type is not a scalar type"));
+        };
+        let (len, null_count) = (data.len(), data.null_count());
+        let validity = match data.nulls() {
+            Some(nulls) => {
+                let mut validity = vec![0; len.div_ceil(8)];
+                for index in nulls.valid_indices() {
+                    validity[index / 8] |= 1 << (index % 8);
+                }
+                validity
+            }
+            None => crate::nullable::all_valid(len),
+        };
+        let size = scalar_type.size();
+        let buffer = data.buffers()[0].slice_with_length(data.offset() * size, len * size);
+        drop(data);
+        let vec = TypeErasedVec::from_arrow_buffer(buffer, scalar_type)?;
+        Ok(NullableTypeErasedVec::from_validity_bitmap(
+            vec, validity, null_count,
+        ))
+    }
+}
@@ -0,0 +1,33 @@
+//! Export through the Arrow C Data Interface.
+
+use arrow_data::ffi::FFI_ArrowArray;
+use arrow_schema::ffi::FFI_ArrowSchema;
+
+use crate::{missing_capability, Global, TypeErasedVec};
+
+impl TypeErasedVec<Global> {
+    /// Hands the buffer over as the `ArrowArray` and `ArrowSchema` C structs of a primitive array without nulls,
+    /// without copying, e.g. to DuckDB, polars or pyarrow. The array's release callback drops `self`.
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let (array, schema) = TypeErasedVec::from_scalar_vec(vec![1i64, 2, 3]).export_to_c();
+    /// assert_eq!((array.len(), schema.format()), (3, "l"));
+    /// // Usually moved to the consumer, which calls `array.release` once done with it.
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not a [ScalarType](crate::ScalarType), or if `self` is leaked.
+    #[track_caller]
+    pub fn export_to_c(self) -> (FFI_ArrowArray, FFI_ArrowSchema) {
+        let Some(scalar_type) = self.scalar_type() else {
+            missing_capability(self.vtable, "from_scalar_vec or new_scalar");
+        };
+        let schema = FFI_ArrowSchema::try_from(&scalar_type.to_arrow_data_type())
+            .expect("primitive data types have a format");
+        let array = FFI_ArrowArray::new(&self.into_arrow_array_data());
+        (array, schema)
+    }
+}
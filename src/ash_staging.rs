@@ -0,0 +1,129 @@
+//! Copying into Vulkan staging memory with `ash`.
+
+use ash::vk;
+
+use crate::{missing_capability, Allocator, TypeErasedVec};
+
+/// Where [TypeErasedVec::copy_to_vk_staging] put the elements in the staging memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VkStagingRegion {
+    offset: vk::DeviceSize,
+    size: vk::DeviceSize,
+    padded_size: vk::DeviceSize,
+}
+
+impl VkStagingRegion {
+    /// Returns the byte offset of the elements, a multiple of both `optimalBufferCopyOffsetAlignment` and `nonCoherentAtomSize`.
+    pub fn offset(&self) -> vk::DeviceSize {
+        self.offset
+    }
+
+    /// Returns the byte length of the elements.
+    pub fn size(&self) -> vk::DeviceSize {
+        self.size
+    }
+
+    /// Returns the byte length of the elements rounded up to `nonCoherentAtomSize`. The padding is zeroed.
+    pub fn padded_size(&self) -> vk::DeviceSize {
+        self.padded_size
+    }
+
+    /// Returns where the next region may start.
+    pub fn end(&self) -> vk::DeviceSize {
+        self.offset + self.padded_size
+    }
+
+    /// Describes copying the elements from the staging buffer to `dst_offset` in another buffer with `vkCmdCopyBuffer`.
+    pub fn buffer_copy(&self, dst_offset: vk::DeviceSize) -> vk::BufferCopy {
+        vk::BufferCopy {
+            src_offset: self.offset,
+            dst_offset,
+            size: self.size,
+        }
+    }
+
+    /// Describes the range to pass to `vkFlushMappedMemoryRanges` if the staging memory is not host coherent.
+    ///
+    /// `memory_offset` is where the staging buffer is bound in `memory`, which must be a multiple of `nonCoherentAtomSize`.
+    pub fn mapped_memory_range(
+        &self,
+        memory: vk::DeviceMemory,
+        memory_offset: vk::DeviceSize,
+    ) -> vk::MappedMemoryRange<'static> {
+        vk::MappedMemoryRange::default()
+            .memory(memory)
+            .offset(memory_offset + self.offset)
+            .size(self.padded_size)
+    }
+}
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Returns the byte length of the elements rounded up to `nonCoherentAtomSize`, which is what
+    /// [TypeErasedVec::copy_to_vk_staging] takes up in the staging memory, not counting the alignment of its offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn vk_staging_size(&self, limits: &vk::PhysicalDeviceLimits) -> vk::DeviceSize {
+        let len = raw!(self).len() * self.vtable.layout.size();
+        align_up(len as vk::DeviceSize, limits.non_coherent_atom_size)
+    }
+
+    /// Copies the elements into mapped staging memory at the first offset from `offset` that satisfies `limits`,
+    /// zeroing the padding up to `nonCoherentAtomSize`, or returns `None` if they don't fit.
+    ///
+    /// ```
+    /// use ash::vk;
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let limits = vk::PhysicalDeviceLimits {
+    ///     optimal_buffer_copy_offset_alignment: 16,
+    ///     non_coherent_atom_size: 64,
+    ///     ..Default::default()
+    /// };
+    /// let mut staging = vec![0xffu8; 256];
+    /// let indices = TypeErasedVec::from_pod_vec(vec![0u16, 1, 2]);
+    /// let first = indices.copy_to_vk_staging(&mut staging, 0, &limits).unwrap();
+    /// let second = indices.copy_to_vk_staging(&mut staging, first.end(), &limits).unwrap();
+    /// assert_eq!((second.offset(), second.size(), second.padded_size()), (64, 6, 64));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data, or if `self` is leaked.
+    #[track_caller]
+    pub fn copy_to_vk_staging(
+        &self,
+        staging: &mut [u8],
+        offset: vk::DeviceSize,
+        limits: &vk::PhysicalDeviceLimits,
+    ) -> Option<VkStagingRegion> {
+        let Some(bytes) = self.as_bytes() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        // Both limits are powers of two, so the larger one is a multiple of the other.
+        let alignment = limits
+            .optimal_buffer_copy_offset_alignment
+            .max(limits.non_coherent_atom_size);
+        let offset = align_up(offset, alignment);
+        let size = bytes.len() as vk::DeviceSize;
+        let padded_size = align_up(size, limits.non_coherent_atom_size);
+        let start = usize::try_from(offset).ok()?;
+        let end = start.checked_add(usize::try_from(padded_size).ok()?)?;
+        let region = staging.get_mut(start..end)?;
+        let (data, padding) = region.split_at_mut(bytes.len());
+        data.copy_from_slice(bytes);
+        padding.fill(0);
+        Some(VkStagingRegion {
+            offset,
+            size,
+            padded_size,
+        })
+    }
+}
+
+/// Rounds `value` up to a multiple of `alignment`, treating 0 as no alignment.
+fn align_up(value: vk::DeviceSize, alignment: vk::DeviceSize) -> vk::DeviceSize {
+    value.next_multiple_of(alignment.max(1))
+}
@@ -0,0 +1,116 @@
+//! A single type erased value.
+
+use std::{
+    fmt::{self, Debug},
+    mem::forget,
+};
+
+#[cfg(not(feature = "allocator-api"))]
+use crate::Global;
+use crate::{
+    raw::{DeallocGuard, RawVec},
+    ElementVTable, Erasable, ErasedMut, ErasedRef, TypedVTable,
+};
+#[cfg(feature = "allocator-api")]
+use std::alloc::Global;
+
+/// A type erased [Box], holding a single value.
+///
+/// It shares the [ElementVTable] machinery with [TypeErasedVec](crate::TypeErasedVec), so values can be moved in and out of one with
+/// [TypeErasedVec::push_erased](crate::TypeErasedVec::push_erased) and [TypeErasedVec::pop_erased](crate::TypeErasedVec::pop_erased).
+pub struct TypeErasedBox {
+    /// A buffer holding exactly one element.
+    raw: RawVec<Global>,
+    vtable: &'static ElementVTable,
+}
+
+impl TypeErasedBox {
+    /// Erases the type of `value`.
+    pub fn new<T: Erasable>(value: T) -> Self {
+        unsafe { Self::from_value(value, &TypedVTable::<T>::NEW.vtable) }
+    }
+
+    /// Erases the type of `value`, using `vtable` for the operations that don't know the element type.
+    pub fn new_with_vtable<T>(value: T, vtable: &'static TypedVTable<T>) -> Self {
+        unsafe { Self::from_value(value, vtable.erased()) }
+    }
+
+    /// # Safety
+    ///
+    /// `vtable` must describe `T`.
+    unsafe fn from_value<T>(value: T, vtable: &'static ElementVTable) -> Self {
+        let mut raw = RawVec::with_capacity_in(vtable.layout, 1, Global);
+        raw.ptr().cast::<T>().write(value);
+        raw.set_len(1);
+        TypeErasedBox { raw, vtable }
+    }
+
+    /// # Safety
+    ///
+    /// `raw` must hold exactly one element described by `vtable`, with a capacity of 1 for sized elements.
+    pub(crate) unsafe fn from_raw(raw: RawVec<Global>, vtable: &'static ElementVTable) -> Self {
+        TypeErasedBox { raw, vtable }
+    }
+
+    /// Gives up ownership of the buffer without dropping the value.
+    pub(crate) fn into_raw(self) -> RawVec<Global> {
+        let raw = unsafe { std::ptr::read(&self.raw) };
+        forget(self);
+        raw
+    }
+
+    /// Returns the vtable of the value type.
+    pub fn vtable(&self) -> &'static ElementVTable {
+        self.vtable
+    }
+
+    /// Gets a handle to the value.
+    pub fn as_erased_ref(&self) -> ErasedRef<'_> {
+        unsafe { ErasedRef::new(self.raw.ptr(), self.vtable) }
+    }
+
+    /// Gets a mutable handle to the value.
+    pub fn as_erased_mut(&mut self) -> ErasedMut<'_> {
+        unsafe { ErasedMut::new(self.raw.ptr(), self.vtable) }
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Converts back to `T`, or returns `self` if `T` is not the value type.
+    pub fn downcast<T: 'static>(self) -> Result<T, Self> {
+        if self.as_erased_ref().is::<T>() {
+            Ok(unsafe { self.into_inner() })
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Converts back to `T`.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the value type.
+    pub unsafe fn into_inner<T>(self) -> T {
+        let layout = self.vtable.layout;
+        let raw = DeallocGuard::new(self.into_raw(), layout);
+        raw.raw().ptr().cast::<T>().read()
+    }
+}
+
+impl Drop for TypeErasedBox {
+    fn drop(&mut self) {
+        let raw = unsafe { std::ptr::read(&self.raw) };
+        // Deallocates even if the destructor panics.
+        let raw = DeallocGuard::new(raw, self.vtable.layout);
+        unsafe {
+            (self.vtable.drop)(raw.raw().ptr(), 1);
+        }
+    }
+}
+
+impl Debug for TypeErasedBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypeErasedBox")
+            .field(&self.as_erased_ref())
+            .finish()
+    }
+}
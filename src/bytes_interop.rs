@@ -0,0 +1,106 @@
+//! Conversion to and from `bytes` buffers.
+
+use std::mem::ManuallyDrop;
+
+use bytes::{Bytes, BytesMut};
+
+use crate::{
+    missing_capability, ElementVTable, Error, Global, SendSyncTypeErasedVec, TypeErasedVec,
+};
+
+/// Owns the vec behind a [Bytes].
+struct BytesOwner(SendSyncTypeErasedVec);
+
+impl AsRef<[u8]> for BytesOwner {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes().unwrap()
+    }
+}
+
+impl TypeErasedVec<Global> {
+    /// Converts to [Bytes] without copying, e.g. to send the buffer with `tokio` or `hyper`.
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let bytes = TypeErasedVec::from_scalar_vec(vec![1u16, 2]).into_bytes();
+    /// assert_eq!(bytes.len(), 4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], or if `self` is leaked.
+    #[track_caller]
+    pub fn into_bytes(self) -> Bytes {
+        if self.as_bytes().is_none() {
+            missing_capability(self.vtable, "from_pod_vec");
+        }
+        // Plain-old-data elements are only bytes, so they can be sent and shared between threads.
+        Bytes::from_owner(BytesOwner(unsafe {
+            SendSyncTypeErasedVec::from_erased_unchecked(self)
+        }))
+    }
+
+    /// Converts to [BytesMut], without copying if the element alignment and the buffer alignment are 1, as `BytesMut`
+    /// deallocates with them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], or if `self` is leaked.
+    #[track_caller]
+    pub fn into_bytes_mut(self) -> BytesMut {
+        let Some(bytes) = self.as_bytes() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        if self.buffer_align() != 1 {
+            return BytesMut::from(bytes);
+        }
+        let (ptr, len, cap, layout, _, _) = self.into_raw_parts();
+        // The buffer was allocated with an array layout of `cap` elements of `layout`, which is the same as `cap * size` bytes.
+        // Zero sized elements have no buffer.
+        let (len, cap) = match layout.size() {
+            0 => (0, 0),
+            size => (len * size, cap * size),
+        };
+        BytesMut::from(Bytes::from(unsafe { Vec::from_raw_parts(ptr, len, cap) }))
+    }
+
+    /// Copies `bytes` into a vec of the elements `vtable` describes.
+    ///
+    /// Fails if the element type is not known to be plain-old-data, or if the length of `bytes` is not a multiple of the element size.
+    /// To read the elements without copying, see [TypeErasedSlice::try_from_bytes](crate::TypeErasedSlice::try_from_bytes).
+    pub fn from_bytes(bytes: &[u8], vtable: &'static ElementVTable) -> Result<Self, Error> {
+        let mut vec = Self::new_from_vtable(vtable);
+        vec.try_extend_from_byte_slice(bytes)?;
+        Ok(vec)
+    }
+
+    /// Converts [BytesMut] to a vec of the elements `vtable` describes, without copying if the element alignment is 1
+    /// and `bytes` owns its whole buffer.
+    ///
+    /// Fails if the element type is not known to be plain-old-data, or if the length of `bytes` is not a multiple of the element size.
+    pub fn from_bytes_mut(bytes: BytesMut, vtable: &'static ElementVTable) -> Result<Self, Error> {
+        let layout = vtable.layout;
+        if !vtable.pod || layout.align() != 1 || layout.size() == 0 {
+            return Self::from_bytes(&bytes, vtable);
+        }
+        if !bytes.len().is_multiple_of(layout.size()) {
+            return Err(Error::LengthNotMultipleOfElement {
+                len: bytes.len(),
+                element_size: layout.size(),
+            });
+        }
+        let mut vec = Vec::from(bytes);
+        if !vec.capacity().is_multiple_of(layout.size()) {
+            // The buffer has to be an array of whole elements to be deallocated with their layout.
+            vec.shrink_to_fit();
+            if vec.capacity() != vec.len() {
+                return Self::from_bytes(&vec, vtable);
+            }
+        }
+        let (len, cap) = (vec.len() / layout.size(), vec.capacity() / layout.size());
+        let mut vec = ManuallyDrop::new(vec);
+        // An array of `cap` elements of `layout` has the same layout as the `cap * size` bytes.
+        Ok(unsafe { Self::from_raw_parts(vec.as_mut_ptr(), len, cap, layout, vtable, Global) })
+    }
+}
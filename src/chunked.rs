@@ -0,0 +1,162 @@
+//! Vecs growing by fixed-size chunks.
+
+use crate::{missing_capability, Allocator, ElementVTable, ErasedRef, Global, TypeErasedVec};
+
+/// A vec of plain-old-data elements stored in chunks of a fixed number of elements, each a [TypeErasedVec] allocated
+/// once at full capacity.
+///
+/// Appending never moves existing elements, so their addresses stay stable, e.g. for persistently mapped GPU memory,
+/// and never copies the whole buffer into a bigger allocation, which matters for streams of several gigabytes.
+///
+/// ```
+/// use type_erased_vec::{ChunkedTypeErasedVec, ScalarType};
+///
+/// let mut samples = ChunkedTypeErasedVec::new(ScalarType::U16.vtable(), 4);
+/// samples.push_bytes(&1u16.to_ne_bytes());
+/// let first = samples.element(0).unwrap().as_ptr();
+/// samples.extend_from_byte_slice(&[0; 2 * 8]);
+/// assert_eq!(samples.chunks().len(), 3);
+/// assert_eq!(samples.element(0).unwrap().as_ptr(), first);
+/// ```
+#[derive(Debug)]
+pub struct ChunkedTypeErasedVec<A: Allocator + Clone = Global> {
+    chunks: Vec<TypeErasedVec<A>>,
+    vtable: &'static ElementVTable,
+    chunk_len: usize,
+    len: usize,
+    alloc: A,
+}
+
+impl ChunkedTypeErasedVec<Global> {
+    /// Constructs a new, empty `ChunkedTypeErasedVec` of the elements `vtable` describes, in chunks of `chunk_len`
+    /// elements. See [ChunkedTypeErasedVec::new_in].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data or is zero-sized, or if `chunk_len` is 0.
+    #[track_caller]
+    pub fn new(vtable: &'static ElementVTable, chunk_len: usize) -> Self {
+        Self::new_in(vtable, chunk_len, Global)
+    }
+}
+
+impl<A: Allocator + Clone> ChunkedTypeErasedVec<A> {
+    /// Constructs a new, empty `ChunkedTypeErasedVec` of the elements `vtable` describes, in chunks of `chunk_len`
+    /// elements allocated with `alloc`. No chunk is allocated until an element is appended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data or is zero-sized, or if `chunk_len` is 0.
+    #[track_caller]
+    pub fn new_in(vtable: &'static ElementVTable, chunk_len: usize, alloc: A) -> Self {
+        if !vtable.pod {
+            missing_capability(vtable, "from_pod_vec");
+        }
+        assert_ne!(vtable.layout.size(), 0, "element type is zero-sized");
+        assert_ne!(chunk_len, 0, "chunk length is 0");
+        ChunkedTypeErasedVec {
+            chunks: Vec::new(),
+            vtable,
+            chunk_len,
+            len: 0,
+            alloc,
+        }
+    }
+
+    /// Returns the vtable of the element type.
+    pub fn vtable(&self) -> &'static ElementVTable {
+        self.vtable
+    }
+
+    /// Returns the number of elements in each full chunk.
+    pub fn chunk_len(&self) -> usize {
+        self.chunk_len
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the chunks, all full but the last one. They can't be borrowed mutably, as growing one would move its elements.
+    pub fn chunks(&self) -> &[TypeErasedVec<A>] {
+        &self.chunks
+    }
+
+    /// Gets the element at `index`, or `None` if it's out of bounds.
+    pub fn element(&self, index: usize) -> Option<ErasedRef<'_>> {
+        self.chunks
+            .get(index / self.chunk_len)?
+            .element(index % self.chunk_len)
+    }
+
+    /// Appends one element from its byte representation. See [TypeErasedVec::push_bytes].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is not exactly one element long.
+    #[track_caller]
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        assert_eq!(
+            bytes.len(),
+            self.vtable.layout.size(),
+            "byte length is not element size"
+        );
+        self.extend_from_byte_slice(bytes);
+    }
+
+    /// Appends elements from their byte representation, filling the last chunk before allocating new ones.
+    /// See [TypeErasedVec::extend_from_byte_slice].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` is not a multiple of the element size.
+    #[track_caller]
+    pub fn extend_from_byte_slice(&mut self, bytes: &[u8]) {
+        let size = self.vtable.layout.size();
+        assert!(
+            bytes.len().is_multiple_of(size),
+            "byte length is not a multiple of element size"
+        );
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            let chunk = match self.chunks.last_mut() {
+                Some(chunk) if chunk.len() < self.chunk_len => chunk,
+                _ => {
+                    let mut chunk =
+                        TypeErasedVec::new_from_vtable_in(self.vtable, self.alloc.clone());
+                    chunk.reserve_exact(self.chunk_len);
+                    self.chunks.push(chunk);
+                    self.chunks.last_mut().unwrap()
+                }
+            };
+            let (head, tail) =
+                rest.split_at(((self.chunk_len - chunk.len()) * size).min(rest.len()));
+            // Within the reserved capacity, so the chunk is not reallocated.
+            chunk.extend_from_byte_slice(head);
+            rest = tail;
+        }
+        self.len += bytes.len() / size;
+    }
+
+    /// Copies the elements into one contiguous vec, e.g. for APIs that need a single buffer.
+    pub fn to_contiguous(&self) -> TypeErasedVec<A> {
+        let mut vec = TypeErasedVec::new_from_vtable_in(self.vtable, self.alloc.clone());
+        vec.reserve_exact(self.len);
+        for chunk in &self.chunks {
+            vec.extend_from_byte_slice(chunk.as_bytes().unwrap());
+        }
+        vec
+    }
+
+    /// Removes all elements and frees the chunks.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+    }
+}
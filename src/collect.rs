@@ -0,0 +1,37 @@
+//! Collecting iterators into a [TypeErasedVec].
+
+#[cfg(not(feature = "allocator-api"))]
+use crate::Global;
+use crate::{Allocator, Erasable, TypeErasedVec};
+#[cfg(feature = "allocator-api")]
+use std::alloc::Global;
+
+/// Extends [Iterator] with collecting into a [TypeErasedVec].
+///
+/// ```
+/// use type_erased_vec::CollectErased;
+///
+/// let vec = (0..3).map(|i| i * 2).collect_erased();
+/// assert_eq!(unsafe { vec.get::<i32>() }, [0, 2, 4]);
+/// ```
+pub trait CollectErased: Iterator + Sized {
+    /// Collects the items into a [TypeErasedVec]. See [Iterator::collect].
+    fn collect_erased(self) -> TypeErasedVec<Global>
+    where
+        Self::Item: Erasable,
+    {
+        self.collect_erased_in(Global)
+    }
+
+    /// Collects the items into a [TypeErasedVec] in `alloc`.
+    fn collect_erased_in<A: Allocator>(self, alloc: A) -> TypeErasedVec<A>
+    where
+        Self::Item: Erasable,
+    {
+        let mut vec = TypeErasedVec::with_capacity_in::<Self::Item>(self.size_hint().0, alloc);
+        unsafe { vec.with_mut::<Self::Item, _>(|vec| vec.extend(self)) };
+        vec
+    }
+}
+
+impl<I: Iterator> CollectErased for I {}
@@ -0,0 +1,234 @@
+//! Converting between scalar element types.
+
+use std::mem::MaybeUninit;
+#[cfg(feature = "simd")]
+use std::simd::num::{SimdFloat, SimdInt, SimdUint};
+
+use crate::{dispatch, missing_capability, Allocator, Scalar, ScalarType, TypeErasedVec};
+
+/// Converts a scalar of type `S` to `Self` with `as`.
+pub(crate) trait CastFrom<S: Copy>: Scalar {
+    fn cast_from(value: S) -> Self;
+
+    /// Converts `src` into `dst`, which has the same length.
+    fn cast_slice(src: &[S], dst: &mut [MaybeUninit<Self>]) {
+        for (dst, &value) in dst.iter_mut().zip(src) {
+            dst.write(Self::cast_from(value));
+        }
+    }
+}
+
+macro_rules! impl_cast_from {
+    ($($t:ty),*) => {
+        impl_cast_from!(@each [$($t),*] $($t),*);
+    };
+    (@each $from:tt $($to:ty),*) => {
+        $(impl_cast_from!(@pairs $to, $from);)*
+    };
+    (@pairs $to:ty, [$($from:ty),*]) => {
+        $(
+            impl CastFrom<$from> for $to {
+                #[allow(trivial_numeric_casts, clippy::unnecessary_cast)]
+                fn cast_from(value: $from) -> Self {
+                    value as $to
+                }
+
+                #[cfg(feature = "simd")]
+                fn cast_slice(src: &[$from], dst: &mut [MaybeUninit<Self>]) {
+                    let (src, dst) = crate::kernels::simd::map(src, dst, |block| block.cast::<$to>());
+                    for (dst, &value) in dst.iter_mut().zip(src) {
+                        dst.write(Self::cast_from(value));
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_cast_from!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+/// Converts between the `half` types and the primitive types through `f64`, and between the `half` types through `f32`,
+/// which represents both exactly.
+#[cfg(feature = "half")]
+macro_rules! impl_cast_half {
+    ($($half:ty),*) => {
+        impl_cast_half!(@each [$($half),*] $($half),*);
+    };
+    (@each $halves:tt $($half:ty),*) => {
+        $(
+            impl_cast_half!(@primitives $half, [i8, i16, i32, i64, u8, u16, u32, u64, f32, f64]);
+            impl_cast_half!(@halves $half, $halves);
+        )*
+    };
+    (@primitives $half:ty, [$($t:ty),*]) => {
+        $(
+            impl CastFrom<$t> for $half {
+                #[allow(trivial_numeric_casts, clippy::unnecessary_cast)]
+                fn cast_from(value: $t) -> Self {
+                    <$half>::from_f64(value as f64)
+                }
+            }
+
+            impl CastFrom<$half> for $t {
+                #[allow(trivial_numeric_casts, clippy::unnecessary_cast)]
+                fn cast_from(value: $half) -> Self {
+                    value.to_f64() as $t
+                }
+            }
+        )*
+    };
+    (@halves $to:ty, [$($from:ty),*]) => {
+        $(
+            impl CastFrom<$from> for $to {
+                fn cast_from(value: $from) -> Self {
+                    <$to>::from_f32(value.to_f32())
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "half")]
+impl_cast_half!(half::f16, half::bf16);
+
+/// Maps a scalar to and from the range GPUs normalize it to.
+trait Normalize: Scalar {
+    /// Maps integers to `[0, 1]` if unsigned or `[-1, 1]` if signed. Floats are unchanged.
+    fn to_normalized(self) -> f64;
+
+    /// The inverse of [Normalize::to_normalized], clamping to the range and rounding to the nearest integer.
+    fn from_normalized(value: f64) -> Self;
+}
+
+macro_rules! impl_normalize {
+    (float $($t:ty),*) => {
+        $(
+            impl Normalize for $t {
+                #[allow(trivial_numeric_casts, clippy::unnecessary_cast)]
+                fn to_normalized(self) -> f64 {
+                    self as f64
+                }
+
+                #[allow(trivial_numeric_casts, clippy::unnecessary_cast)]
+                fn from_normalized(value: f64) -> Self {
+                    value as $t
+                }
+            }
+        )*
+    };
+    (half $($t:ty),*) => {
+        $(
+            impl Normalize for $t {
+                fn to_normalized(self) -> f64 {
+                    self.to_f64()
+                }
+
+                fn from_normalized(value: f64) -> Self {
+                    <$t>::from_f64(value)
+                }
+            }
+        )*
+    };
+    (int $($t:ty),*) => {
+        $(
+            impl Normalize for $t {
+                fn to_normalized(self) -> f64 {
+                    // The most negative value of signed integers is below -1 and clamped, like GPUs do.
+                    (self as f64 / <$t>::MAX as f64).max(-1.0)
+                }
+
+                fn from_normalized(value: f64) -> Self {
+                    let min = if <$t>::MIN == 0 { 0.0 } else { -1.0 };
+                    (value.clamp(min, 1.0) * <$t>::MAX as f64).round() as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_normalize!(int i8, i16, i32, i64, u8, u16, u32, u64);
+impl_normalize!(float f32, f64);
+
+#[cfg(feature = "half")]
+impl_normalize!(half half::f16, half::bf16);
+
+impl<A: Allocator + Clone> TypeErasedVec<A> {
+    /// Converts the elements to `scalar_type` into a new vec, e.g. to turn `f64` columns read from a file into `f32`,
+    /// or `u16` indices into `u32`.
+    ///
+    /// Each element is converted like with `as`: integers wrap or extend, floats round to the nearest representable value,
+    /// and floats converted to integers truncate toward zero and saturate, with `NaN` becoming 0.
+    ///
+    /// ```
+    /// use type_erased_vec::{ScalarType, TypeErasedVec};
+    ///
+    /// let indices = TypeErasedVec::from_scalar_vec(vec![1u16, 2, 65535]);
+    /// let converted = indices.convert_to(ScalarType::U32);
+    /// assert_eq!(unsafe { converted.get::<u32>() }, [1, 2, 65535]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_scalar_vec] or [TypeErasedVec::new_scalar],
+    /// or if `self` is leaked.
+    #[track_caller]
+    pub fn convert_to(&self, scalar_type: ScalarType) -> Self {
+        let Some(from) = self.scalar_type() else {
+            missing_capability(self.vtable, "from_scalar_vec or new_scalar");
+        };
+        let mut converted = Self::new_scalar_in(scalar_type, raw!(self).allocator().clone());
+        // `dispatch!` only calls this with the element types of `self` and `converted`.
+        dispatch!(from, |S| dispatch!(scalar_type, |D| unsafe {
+            let elements = self.get::<S>();
+            converted.with_mut::<D, _>(|vec| {
+                vec.reserve_exact(elements.len());
+                D::cast_slice(elements, &mut vec.spare_capacity_mut()[..elements.len()]);
+                vec.set_len(elements.len());
+            })
+        }));
+        converted
+    }
+
+    /// Converts the elements to `scalar_type` into a new vec with normalized integers, the way GPUs read and write
+    /// color and weight attributes: unsigned integers map to `[0, 1]` and signed integers to `[-1, 1]`, scaled by
+    /// their maximum value.
+    ///
+    /// Converting to integers clamps to the range and rounds to the nearest integer, and `NaN` becomes 0.
+    /// Integers convert to integers through the normalized range, so `255u8` becomes `65535u16`.
+    /// Floats convert to floats like with [TypeErasedVec::convert_to].
+    /// Values pass through `f64`, so 64 bit integers may lose precision.
+    ///
+    /// ```
+    /// use type_erased_vec::{ScalarType, TypeErasedVec};
+    ///
+    /// let colors = TypeErasedVec::from_scalar_vec(vec![0u8, 51, 255]);
+    /// let floats = colors.convert_normalized_to(ScalarType::F32);
+    /// assert_eq!(unsafe { floats.get::<f32>() }, [0.0, 0.2, 1.0]);
+    /// let colors = floats.convert_normalized_to(ScalarType::U8);
+    /// assert_eq!(unsafe { colors.get::<u8>() }, [0, 51, 255]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_scalar_vec] or [TypeErasedVec::new_scalar],
+    /// or if `self` is leaked.
+    #[track_caller]
+    pub fn convert_normalized_to(&self, scalar_type: ScalarType) -> Self {
+        let Some(from) = self.scalar_type() else {
+            missing_capability(self.vtable, "from_scalar_vec or new_scalar");
+        };
+        let mut converted = Self::new_scalar_in(scalar_type, raw!(self).allocator().clone());
+        // `dispatch!` only calls this with the element types of `self` and `converted`.
+        dispatch!(from, |S| dispatch!(scalar_type, |D| unsafe {
+            let elements = self.get::<S>();
+            converted.with_mut::<D, _>(|vec| {
+                vec.extend(
+                    elements
+                        .iter()
+                        .map(|&value| D::from_normalized(value.to_normalized())),
+                )
+            })
+        }));
+        converted
+    }
+}
@@ -0,0 +1,140 @@
+//! Pinned host memory and device copies with `cudarc`.
+
+use std::sync::Arc;
+#[cfg(feature = "allocator-api")]
+use std::{
+    alloc::{AllocError, Layout},
+    ptr::{self, NonNull},
+};
+
+#[cfg(feature = "allocator-api")]
+use cudarc::driver::CudaContext;
+use cudarc::driver::{result, CudaStream, DevicePtr, DevicePtrMut, DriverError};
+
+use crate::{missing_capability, Allocator, TypeErasedVec};
+
+/// The alignment of the memory `cuMemHostAlloc` returns.
+#[cfg(feature = "allocator-api")]
+const PAGE_SIZE: usize = 4096;
+
+/// An [Allocator] of page-locked host memory, which the device can copy from and to asynchronously.
+///
+/// Requires the `allocator-api` feature.
+///
+/// ```
+/// #![feature(allocator_api)]
+/// use std::sync::Arc;
+///
+/// use cudarc::driver::{CudaContext, DriverError};
+/// use type_erased_vec::{CudaPinnedAllocator, ScalarType, TypeErasedVec};
+///
+/// fn preprocess(ctx: Arc<CudaContext>, dtype: ScalarType) -> Result<(), DriverError> {
+///     let stream = ctx.default_stream();
+///     let mut host = TypeErasedVec::new_scalar_in(dtype, CudaPinnedAllocator::new(ctx));
+///     host.resize_zeroed(1024);
+///     let mut device = stream.alloc_zeros::<u8>(host.as_bytes().unwrap().len())?;
+///     unsafe { host.memcpy_htod_async(&stream, &mut device)? };
+///     stream.synchronize()
+/// }
+/// ```
+#[cfg(feature = "allocator-api")]
+#[derive(Debug, Clone)]
+pub struct CudaPinnedAllocator {
+    ctx: Arc<CudaContext>,
+}
+
+#[cfg(feature = "allocator-api")]
+impl CudaPinnedAllocator {
+    /// Constructs an allocator of memory pinned for `ctx`.
+    pub fn new(ctx: Arc<CudaContext>) -> Self {
+        CudaPinnedAllocator { ctx }
+    }
+
+    /// Gets the context the memory is pinned for.
+    pub fn context(&self) -> &Arc<CudaContext> {
+        &self.ctx
+    }
+}
+
+#[cfg(feature = "allocator-api")]
+unsafe impl Allocator for CudaPinnedAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.size() == 0 {
+            let dangling = NonNull::new(ptr::without_provenance_mut(layout.align())).unwrap();
+            return Ok(NonNull::slice_from_raw_parts(dangling, 0));
+        }
+        if layout.align() > PAGE_SIZE {
+            return Err(AllocError);
+        }
+        self.ctx.bind_to_thread().map_err(|_| AllocError)?;
+        let ptr = unsafe { result::malloc_host(layout.size(), 0) }.map_err(|_| AllocError)?;
+        let ptr = NonNull::new(ptr.cast::<u8>()).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            // Errors can't be reported from here, and the memory is gone either way.
+            let _ = self.ctx.bind_to_thread();
+            let _ = result::free_host(ptr.as_ptr().cast());
+        }
+    }
+}
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Schedules copying the elements to the start of `dst` on `stream`, without waiting for it to finish.
+    ///
+    /// The copy only overlaps with host work if the buffer is page-locked, e.g. allocated by `CudaPinnedAllocator`.
+    ///
+    /// # Safety
+    ///
+    /// `self` must not be mutated, moved from or dropped until `stream` is synchronized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data, if `dst` is shorter than the elements' bytes,
+    /// or if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn memcpy_htod_async(
+        &self,
+        stream: &Arc<CudaStream>,
+        dst: &mut impl DevicePtrMut<u8>,
+    ) -> Result<(), DriverError> {
+        let Some(bytes) = self.as_bytes() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        assert!(dst.len() >= bytes.len(), "device buffer is too small");
+        stream.context().bind_to_thread()?;
+        let (dst, _record_dst) = dst.device_ptr_mut(stream);
+        result::memcpy_htod_async(dst, bytes, stream.cu_stream())
+    }
+
+    /// Schedules copying the start of `src` over the elements on `stream`, without waiting for it to finish.
+    /// The length of `self` decides how many bytes are copied, so resize it first.
+    ///
+    /// The copy only overlaps with host work if the buffer is page-locked, e.g. allocated by `CudaPinnedAllocator`.
+    ///
+    /// # Safety
+    ///
+    /// `self` must not be accessed, moved from or dropped until `stream` is synchronized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data, if `src` is shorter than the elements' bytes,
+    /// or if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn memcpy_dtoh_async(
+        &mut self,
+        stream: &Arc<CudaStream>,
+        src: &impl DevicePtr<u8>,
+    ) -> Result<(), DriverError> {
+        let vtable = self.vtable;
+        let Some(bytes) = self.as_bytes_mut() else {
+            missing_capability(vtable, "from_pod_vec");
+        };
+        assert!(src.len() >= bytes.len(), "device buffer is too small");
+        stream.context().bind_to_thread()?;
+        let (src, _record_src) = src.device_ptr(stream);
+        result::memcpy_dtoh_async(bytes, src, stream.cu_stream())
+    }
+}
@@ -0,0 +1,332 @@
+//! Exchanging buffers as DLPack tensors.
+
+use std::{
+    ffi::c_void,
+    fmt::{self, Debug},
+    ptr::{self, NonNull},
+    slice,
+};
+
+use crate::{missing_capability, Allocator, Error, ScalarType, TypeErasedSlice, TypeErasedVec};
+
+/// `kDLCPU`, the only device type [DlpackTensor] accepts.
+const DL_CPU: i32 = 1;
+/// `kDLInt`.
+const DL_INT: u8 = 0;
+/// `kDLUInt`.
+const DL_UINT: u8 = 1;
+/// `kDLFloat`.
+const DL_FLOAT: u8 = 2;
+#[cfg(feature = "half")]
+/// `kDLBfloat`.
+const DL_BFLOAT: u8 = 4;
+
+/// The `DLDevice` C struct.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DLDevice {
+    /// The `DLDeviceType`, e.g. 1 for `kDLCPU`.
+    pub device_type: i32,
+    /// The device index.
+    pub device_id: i32,
+}
+
+/// The `DLDataType` C struct.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DLDataType {
+    /// The `DLDataTypeCode`, e.g. 2 for `kDLFloat`.
+    pub code: u8,
+    /// The number of bits of a lane.
+    pub bits: u8,
+    /// The number of lanes, 1 for scalars.
+    pub lanes: u16,
+}
+
+/// The `DLTensor` C struct.
+#[repr(C)]
+#[derive(Debug)]
+pub struct DLTensor {
+    /// The start of the allocation.
+    pub data: *mut c_void,
+    /// Where the allocation is.
+    pub device: DLDevice,
+    /// The number of dimensions.
+    pub ndim: i32,
+    /// The element type.
+    pub dtype: DLDataType,
+    /// `ndim` dimension lengths.
+    pub shape: *mut i64,
+    /// `ndim` strides in elements, or null for a compact row-major tensor.
+    pub strides: *mut i64,
+    /// The offset of the first element from `data`, in bytes.
+    pub byte_offset: u64,
+}
+
+/// The `DLManagedTensor` C struct, a [DLTensor] and the means to release it.
+#[repr(C)]
+#[derive(Debug)]
+pub struct DLManagedTensor {
+    /// The tensor.
+    pub dl_tensor: DLTensor,
+    /// The state of the producer, for `deleter`.
+    pub manager_ctx: *mut c_void,
+    /// Releases the tensor. Called by the consumer once it's done with it.
+    pub deleter: Option<unsafe extern "C" fn(*mut DLManagedTensor)>,
+}
+
+impl DLDataType {
+    /// Returns the data type of `scalar_type`.
+    pub fn from_scalar_type(scalar_type: ScalarType) -> Self {
+        #[cfg(feature = "half")]
+        if scalar_type == ScalarType::BF16 {
+            return DLDataType {
+                code: DL_BFLOAT,
+                bits: 16,
+                lanes: 1,
+            };
+        }
+        let code = if scalar_type.is_float() {
+            DL_FLOAT
+        } else if scalar_type.is_signed() {
+            DL_INT
+        } else {
+            DL_UINT
+        };
+        DLDataType {
+            code,
+            bits: (scalar_type.size() * 8) as u8,
+            lanes: 1,
+        }
+    }
+
+    /// Returns the scalar type of the data type, or `None` if there's none, like for `bfloat16` without the `half` feature
+    /// or vectors.
+    pub fn scalar_type(self) -> Option<ScalarType> {
+        if self.lanes != 1 {
+            return None;
+        }
+        ScalarType::ALL
+            .into_iter()
+            .find(|&scalar_type| DLDataType::from_scalar_type(scalar_type) == self)
+    }
+}
+
+/// What the deleter of an exported tensor frees.
+struct ExportContext<A: Allocator> {
+    _vec: TypeErasedVec<A>,
+    shape: Vec<i64>,
+}
+
+unsafe extern "C" fn delete_exported<A: Allocator>(tensor: *mut DLManagedTensor) {
+    let tensor = Box::from_raw(tensor);
+    drop(Box::from_raw(tensor.manager_ctx.cast::<ExportContext<A>>()));
+}
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Hands the buffer over as a compact row-major tensor of `shape` on the CPU, without copying, e.g. to a DLPack bridge
+    /// of PyTorch, TensorFlow or JAX. The consumer drops `self` by calling the tensor's deleter.
+    ///
+    /// ```
+    /// use type_erased_vec::{DlpackTensor, TypeErasedVec};
+    ///
+    /// let vec = TypeErasedVec::from_scalar_vec(vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// let tensor = vec.into_dlpack(&[2, 3]);
+    /// // Usually passed to another library here.
+    /// let tensor = unsafe { DlpackTensor::from_raw(tensor) }.unwrap();
+    /// assert_eq!(tensor.shape(), [2, 3]);
+    /// assert_eq!(unsafe { tensor.as_erased_slice().get::<f32>() }[5], 6.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not a [ScalarType], if `shape` has a negative length or its product is not the length,
+    /// or if `self` is leaked.
+    #[track_caller]
+    pub fn into_dlpack(self, shape: &[i64]) -> NonNull<DLManagedTensor> {
+        let Some(scalar_type) = self.vtable.scalar_type() else {
+            missing_capability(self.vtable, "from_scalar_vec or new_scalar");
+        };
+        let data = raw!(self).ptr();
+        assert_eq!(
+            element_count(shape),
+            Some(self.len()),
+            "shape {shape:?} doesn't match length {}",
+            self.len()
+        );
+        let ndim = i32::try_from(shape.len()).expect("too many dimensions");
+        let mut context = Box::new(ExportContext {
+            _vec: self,
+            shape: shape.to_vec(),
+        });
+        let tensor = DLManagedTensor {
+            dl_tensor: DLTensor {
+                data: data.cast(),
+                device: DLDevice {
+                    device_type: DL_CPU,
+                    device_id: 0,
+                },
+                ndim,
+                dtype: DLDataType::from_scalar_type(scalar_type),
+                shape: context.shape.as_mut_ptr(),
+                strides: ptr::null_mut(),
+                byte_offset: 0,
+            },
+            manager_ctx: Box::into_raw(context).cast(),
+            deleter: Some(delete_exported::<A>),
+        };
+        NonNull::from(Box::leak(Box::new(tensor)))
+    }
+}
+
+/// A compact row-major CPU tensor received through DLPack, read as a [TypeErasedSlice]. Calls the tensor's deleter when dropped.
+pub struct DlpackTensor {
+    tensor: NonNull<DLManagedTensor>,
+}
+
+impl DlpackTensor {
+    /// Takes ownership of `tensor`, e.g. one from a DLPack bridge of PyTorch, TensorFlow or JAX.
+    ///
+    /// Fails if the tensor is not on the CPU, if its data type is not a [ScalarType], if it has a negative number
+    /// of dimensions, a negative dimension length or more bytes than fit in `isize`, if it's not compact and row-major,
+    /// or if its data is misaligned. `tensor` is released then.
+    ///
+    /// # Safety
+    ///
+    /// `tensor` must point to a valid `DLManagedTensor`, not used by anyone else until its deleter is called.
+    pub unsafe fn from_raw(tensor: NonNull<DLManagedTensor>) -> Result<Self, Error> {
+        let this = DlpackTensor { tensor };
+        let dl_tensor = &this.tensor.as_ref().dl_tensor;
+        if dl_tensor.device.device_type != DL_CPU {
+            return Err(Error::Unsupported("tensor is not on the CPU"));
+        }
+        let Some(scalar_type) = dl_tensor.dtype.scalar_type() else {
+            return Err(Error::Unsupported("data type is not a scalar type"));
+        };
+        if dl_tensor.ndim < 0 {
+            return Err(Error::Unsupported(
+                "tensor has a negative number of dimensions",
+            ));
+        }
+        if this.shape().iter().any(|&len| len < 0) {
+            return Err(Error::Unsupported("tensor has a negative dimension length"));
+        }
+        if element_count(this.shape())
+            .and_then(|len| len.checked_mul(scalar_type.layout().size()))
+            .is_none_or(|size| size > isize::MAX as usize)
+        {
+            return Err(Error::Unsupported("tensor is too large"));
+        }
+        if !dl_tensor.strides.is_null() {
+            let shape = this.shape();
+            let strides = slice::from_raw_parts(dl_tensor.strides, shape.len());
+            let mut expected = 1;
+            for (&len, &stride) in shape.iter().zip(strides).rev() {
+                // The stride of a dimension of length 1 doesn't matter.
+                if len != 1 && stride != expected {
+                    return Err(Error::Unsupported("tensor is not compact and row-major"));
+                }
+                // Saturates only if a later dimension has length 0, making the tensor empty.
+                expected = expected.saturating_mul(len);
+            }
+        }
+        if !this.is_empty()
+            && !this
+                .data()
+                .addr()
+                .is_multiple_of(scalar_type.layout().align())
+        {
+            return Err(Error::Misaligned {
+                align: scalar_type.layout().align(),
+            });
+        }
+        Ok(this)
+    }
+
+    /// Releases the tensor without calling its deleter.
+    pub fn into_raw(self) -> NonNull<DLManagedTensor> {
+        let tensor = self.tensor;
+        std::mem::forget(self);
+        tensor
+    }
+
+    /// Returns the dimension lengths.
+    pub fn shape(&self) -> &[i64] {
+        let dl_tensor = self.dl_tensor();
+        if dl_tensor.ndim == 0 {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(dl_tensor.shape, dl_tensor.ndim as usize) }
+    }
+
+    /// Returns the element type.
+    pub fn scalar_type(&self) -> ScalarType {
+        self.dl_tensor().dtype.scalar_type().unwrap()
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        // Checked in `from_raw`.
+        element_count(self.shape()).unwrap()
+    }
+
+    /// Returns if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the elements in row-major order.
+    pub fn as_erased_slice(&self) -> TypeErasedSlice<'_> {
+        let vtable = self.scalar_type().vtable();
+        let data = if self.is_empty() {
+            ptr::without_provenance(vtable.layout.align())
+        } else {
+            self.data()
+        };
+        unsafe { TypeErasedSlice::from_raw_parts(data, self.len(), vtable) }
+    }
+
+    /// Copies the elements into a new vec.
+    pub fn to_vec(&self) -> TypeErasedVec {
+        let mut vec = TypeErasedVec::new_scalar(self.scalar_type());
+        vec.extend_from_byte_slice(self.as_erased_slice().as_bytes().unwrap());
+        vec
+    }
+
+    fn dl_tensor(&self) -> &DLTensor {
+        unsafe { &self.tensor.as_ref().dl_tensor }
+    }
+
+    /// The first element.
+    fn data(&self) -> *const u8 {
+        let dl_tensor = self.dl_tensor();
+        dl_tensor
+            .data
+            .cast::<u8>()
+            .wrapping_add(dl_tensor.byte_offset as usize)
+    }
+}
+
+impl Drop for DlpackTensor {
+    fn drop(&mut self) {
+        if let Some(deleter) = unsafe { self.tensor.as_ref().deleter } {
+            unsafe { deleter(self.tensor.as_ptr()) };
+        }
+    }
+}
+
+impl Debug for DlpackTensor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DlpackTensor")
+            .field("shape", &self.shape())
+            .field("elements", &self.as_erased_slice())
+            .finish()
+    }
+}
+
+/// Returns the number of elements of a tensor of `shape`, or `None` if a length is negative or the number overflows.
+fn element_count(shape: &[i64]) -> Option<usize> {
+    shape.iter().try_fold(1usize, |count, &len| {
+        count.checked_mul(usize::try_from(len).ok()?)
+    })
+}
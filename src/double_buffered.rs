@@ -0,0 +1,79 @@
+//! Front and back buffers.
+
+use std::mem::swap;
+
+use crate::{Allocator, TypeErasedVec};
+
+/// A pair of buffers, one read from while the other is written to, e.g. a simulation writing
+/// the next frame while the previous one is uploaded.
+///
+/// ```
+/// use type_erased_vec::{DoubleBuffered, TypeErasedVec};
+///
+/// let mut positions = DoubleBuffered::from_front(TypeErasedVec::from_vec(vec![0.0f32; 4]));
+/// for _ in 0..2 {
+///     let (front, back) = positions.split_mut();
+///     let next = unsafe { front.get::<f32>() }.iter().map(|x| x + 1.0);
+///     let mut back = unsafe { back.get_mut::<f32>() };
+///     back.clear();
+///     back.extend(next);
+///     drop(back);
+///     positions.swap();
+/// }
+/// assert_eq!(unsafe { positions.front().get::<f32>() }, [2.0; 4]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DoubleBuffered<T> {
+    front: T,
+    back: T,
+}
+
+impl<T> DoubleBuffered<T> {
+    /// Constructs from the two buffers.
+    pub fn new(front: T, back: T) -> Self {
+        DoubleBuffered { front, back }
+    }
+
+    /// Gets the buffer to read from.
+    pub fn front(&self) -> &T {
+        &self.front
+    }
+
+    /// Gets the buffer to read from mutably.
+    pub fn front_mut(&mut self) -> &mut T {
+        &mut self.front
+    }
+
+    /// Gets the buffer to write to.
+    pub fn back(&self) -> &T {
+        &self.back
+    }
+
+    /// Gets the buffer to write to mutably.
+    pub fn back_mut(&mut self) -> &mut T {
+        &mut self.back
+    }
+
+    /// Gets the front buffer to read from while writing the back buffer.
+    pub fn split_mut(&mut self) -> (&T, &mut T) {
+        (&self.front, &mut self.back)
+    }
+
+    /// Makes the back buffer the front one, and the other way around.
+    pub fn swap(&mut self) {
+        swap(&mut self.front, &mut self.back);
+    }
+
+    /// Unwraps the front and back buffers.
+    pub fn into_inner(self) -> (T, T) {
+        (self.front, self.back)
+    }
+}
+
+impl<A: Allocator + Clone> DoubleBuffered<TypeErasedVec<A>> {
+    /// Uses `front` as the front buffer, with an empty back buffer of the same element type and allocator.
+    pub fn from_front(front: TypeErasedVec<A>) -> Self {
+        let back = TypeErasedVec::new_from_vtable_in(front.vtable(), front.allocator().clone());
+        DoubleBuffered { front, back }
+    }
+}
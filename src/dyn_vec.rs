@@ -0,0 +1,251 @@
+//! A type erased [Vec] with an entirely safe API.
+
+use std::{any::type_name, iter::FusedIterator, ops::Range};
+
+use crate::{ElementVTable, ErasedRef, Global, TypeErasedBox, TypeErasedVec, VecMut};
+
+#[derive(Debug, Clone)]
+/// A [TypeErasedVec] whose typed accesses are checked at runtime, so none of them is `unsafe`.
+///
+/// `DynVec` never hands out a [VecMut], so it can't be leaked either.
+///
+/// ```
+/// use type_erased_vec::DynVec;
+///
+/// let mut vec = DynVec::new::<i32>();
+/// vec.push(1);
+/// vec.push(2);
+/// assert_eq!(vec.downcast_ref::<i32>(), Some(&[1, 2][..]));
+/// assert_eq!(vec.downcast_ref::<u32>(), None);
+/// assert_eq!(vec.pop::<i32>(), Some(2));
+/// ```
+pub struct DynVec {
+    vec: TypeErasedVec,
+}
+
+impl DynVec {
+    /// Constructs a new, empty `DynVec`. See [Vec::new].
+    pub fn new<T: 'static>() -> Self {
+        DynVec {
+            vec: TypeErasedVec::new::<T>(),
+        }
+    }
+
+    /// Erases the type of `vec`.
+    pub fn from_vec<T: 'static>(vec: Vec<T>) -> Self {
+        DynVec {
+            vec: TypeErasedVec::from_vec(vec),
+        }
+    }
+
+    /// Wraps `vec`, or gives it back if it's leaked.
+    pub fn from_erased(vec: TypeErasedVec) -> Result<Self, TypeErasedVec> {
+        if vec.is_leaked() {
+            Err(vec)
+        } else {
+            Ok(DynVec { vec })
+        }
+    }
+
+    /// Unwraps the underlying [TypeErasedVec].
+    pub fn into_erased(self) -> TypeErasedVec {
+        self.vec
+    }
+
+    /// Gets the underlying [TypeErasedVec].
+    pub fn as_erased(&self) -> &TypeErasedVec {
+        &self.vec
+    }
+
+    /// Returns the vtable of the element type.
+    pub fn vtable(&self) -> &'static ElementVTable {
+        self.vec.vtable()
+    }
+
+    /// Returns if `T` is the element type.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.vec.is::<T>()
+    }
+
+    /// Returns the number of elements. See [Vec::len].
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns if there are no elements. See [Vec::is_empty].
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Gets the elements, or `None` if `T` is not the element type.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&[T]> {
+        self.vec.try_get().unwrap()
+    }
+
+    /// Gets the elements mutably, or `None` if `T` is not the element type.
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut [T]> {
+        if self.is::<T>() {
+            let raw = raw!(mut self.vec);
+            Some(unsafe { std::slice::from_raw_parts_mut(raw.ptr().cast(), raw.len()) })
+        } else {
+            None
+        }
+    }
+
+    /// Calls `f` with the elements as a [Vec], or returns `None` if `T` is not the element type.
+    pub fn with_vec_mut<T: 'static, R>(&mut self, f: impl FnOnce(&mut Vec<T>) -> R) -> Option<R> {
+        if self.is::<T>() {
+            Some(f(&mut self.vec_mut()))
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over the elements, or returns `None` if `T` is not the element type.
+    pub fn iter<T: 'static>(&self) -> Option<std::slice::Iter<'_, T>> {
+        self.downcast_ref().map(<[T]>::iter)
+    }
+
+    /// Iterates over handles to the elements, whatever their type.
+    pub fn iter_erased(&self) -> IterErased<'_> {
+        IterErased {
+            vec: &self.vec,
+            range: 0..self.len(),
+        }
+    }
+
+    /// Appends an element. See [Vec::push].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is not the element type.
+    #[track_caller]
+    pub fn push<T: 'static>(&mut self, value: T) {
+        self.vec_mut().push(value);
+    }
+
+    /// Inserts an element at `index`. See [Vec::insert].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is not the element type, or if `index > len`.
+    #[track_caller]
+    pub fn insert<T: 'static>(&mut self, index: usize, value: T) {
+        self.vec_mut().insert(index, value);
+    }
+
+    /// Removes the last element and returns it, or `None` if there's none. See [Vec::pop].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is not the element type.
+    #[track_caller]
+    pub fn pop<T: 'static>(&mut self) -> Option<T> {
+        self.vec_mut().pop()
+    }
+
+    /// Removes the element at `index` and returns it. See [Vec::remove].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is not the element type, or if `index >= len`.
+    #[track_caller]
+    pub fn remove<T: 'static>(&mut self, index: usize) -> T {
+        self.vec_mut().remove(index)
+    }
+
+    /// Removes the element at `index` and returns it, moving the last element into its place. See [Vec::swap_remove].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is not the element type, or if `index >= len`.
+    #[track_caller]
+    pub fn swap_remove<T: 'static>(&mut self, index: usize) -> T {
+        self.vec_mut().swap_remove(index)
+    }
+
+    /// Appends a type erased value. See [TypeErasedVec::push_erased].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type of `value` is not the element type.
+    #[track_caller]
+    pub fn push_erased(&mut self, value: TypeErasedBox) {
+        self.vec.push_erased(value);
+    }
+
+    /// Removes the last element and returns it as a [TypeErasedBox]. See [TypeErasedVec::pop_erased].
+    pub fn pop_erased(&mut self) -> Option<TypeErasedBox> {
+        self.vec.pop_erased()
+    }
+
+    /// Drops the element at `index`, moving the last element into its place. See [TypeErasedVec::swap_remove_erased].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    #[track_caller]
+    pub fn swap_remove_erased(&mut self, index: usize) {
+        self.vec.swap_remove_erased(index);
+    }
+
+    /// Shortens to `len` elements, dropping the rest. See [Vec::truncate].
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len);
+    }
+
+    /// Drops all elements. See [Vec::clear].
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `T` is not the element type.
+    #[track_caller]
+    fn vec_mut<T: 'static>(&mut self) -> VecMut<'_, T, Global> {
+        assert!(
+            self.is::<T>(),
+            "element type is {}, not {}",
+            self.vtable().type_name(),
+            type_name::<T>()
+        );
+        // `VecMut` is dropped before the borrow ends, so `self.vec` is never left leaked.
+        unsafe { self.vec.get_mut() }
+    }
+}
+
+impl<T: 'static> From<Vec<T>> for DynVec {
+    fn from(vec: Vec<T>) -> Self {
+        DynVec::from_vec(vec)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An iterator over handles to the elements of a [DynVec]. See [DynVec::iter_erased].
+pub struct IterErased<'a> {
+    vec: &'a TypeErasedVec,
+    range: Range<usize>,
+}
+
+impl<'a> Iterator for IterErased<'a> {
+    type Item = ErasedRef<'a>;
+
+    fn next(&mut self) -> Option<ErasedRef<'a>> {
+        self.range.next().map(|i| self.vec.element(i).unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IterErased<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|i| self.vec.element(i).unwrap())
+    }
+}
+
+impl ExactSizeIterator for IterErased<'_> {}
+
+impl FusedIterator for IterErased<'_> {}
@@ -0,0 +1,241 @@
+//! Handles to single elements of a [TypeErasedVec](crate::TypeErasedVec).
+
+#[cfg(feature = "type-id")]
+use std::any::TypeId;
+use std::{
+    fmt::{self, Debug},
+    iter::FusedIterator,
+    marker::PhantomData,
+    slice,
+};
+
+use crate::ElementVTable;
+
+/// A reference to an element of unknown type. See [TypeErasedVec::element](crate::TypeErasedVec::element).
+#[derive(Clone, Copy)]
+pub struct ErasedRef<'a> {
+    ptr: *const u8,
+    vtable: &'static ElementVTable,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> ErasedRef<'a> {
+    /// # Safety
+    ///
+    /// `ptr` must point to an element described by `vtable`, valid for reads during `'a`.
+    pub(crate) unsafe fn new(ptr: *const u8, vtable: &'static ElementVTable) -> Self {
+        ErasedRef {
+            ptr,
+            vtable,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the vtable of the element type.
+    pub fn vtable(&self) -> &'static ElementVTable {
+        self.vtable
+    }
+
+    /// Returns a pointer to the element.
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Returns if the element is a `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.vtable.type_id() == TypeId::of::<T>()
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Gets a reference to the element, or `None` if it's not a `T`.
+    pub fn downcast_ref<T: 'static>(self) -> Option<&'a T> {
+        if self.is::<T>() {
+            Some(unsafe { self.downcast_ref_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a reference to the element.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the element type.
+    pub unsafe fn downcast_ref_unchecked<T>(self) -> &'a T {
+        &*self.ptr.cast()
+    }
+
+    /// Gets the element as bytes, or `None` if the element type is not known to be plain-old-data.
+    pub fn as_bytes(self) -> Option<&'a [u8]> {
+        if self.vtable.pod {
+            Some(unsafe { slice::from_raw_parts(self.ptr, self.vtable.layout.size()) })
+        } else {
+            None
+        }
+    }
+}
+
+impl Debug for ErasedRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_element(self.ptr, self.vtable, "ErasedRef", f)
+    }
+}
+
+/// A mutable reference to an element of unknown type. See [TypeErasedVec::element_mut](crate::TypeErasedVec::element_mut).
+pub struct ErasedMut<'a> {
+    ptr: *mut u8,
+    vtable: &'static ElementVTable,
+    _marker: PhantomData<&'a mut ()>,
+}
+
+impl<'a> ErasedMut<'a> {
+    /// # Safety
+    ///
+    /// `ptr` must point to an element described by `vtable`, valid for reads and writes and not aliased during `'a`.
+    pub(crate) unsafe fn new(ptr: *mut u8, vtable: &'static ElementVTable) -> Self {
+        ErasedMut {
+            ptr,
+            vtable,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the vtable of the element type.
+    pub fn vtable(&self) -> &'static ElementVTable {
+        self.vtable
+    }
+
+    /// Returns a pointer to the element.
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Reborrows as an [ErasedRef].
+    pub fn as_ref(&self) -> ErasedRef<'_> {
+        unsafe { ErasedRef::new(self.ptr, self.vtable) }
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Returns if the element is a `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.as_ref().is::<T>()
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Gets a reference to the element, or `None` if it's not a `T`.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.as_ref().downcast_ref()
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Gets a mutable reference to the element, or `None` if it's not a `T`.
+    pub fn downcast_mut<T: 'static>(self) -> Option<&'a mut T> {
+        if self.is::<T>() {
+            Some(unsafe { self.downcast_mut_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a mutable reference to the element.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the element type.
+    pub unsafe fn downcast_mut_unchecked<T>(self) -> &'a mut T {
+        &mut *self.ptr.cast()
+    }
+
+    /// Gets the element as mutable bytes, or `None` if the element type is not known to be plain-old-data.
+    pub fn as_bytes_mut(self) -> Option<&'a mut [u8]> {
+        if self.vtable.pod {
+            Some(unsafe { slice::from_raw_parts_mut(self.ptr, self.vtable.layout.size()) })
+        } else {
+            None
+        }
+    }
+}
+
+impl Debug for ErasedMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_element(self.ptr, self.vtable, "ErasedMut", f)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An iterator over the byte representation of each element. See [TypeErasedVec::iter_element_bytes](crate::TypeErasedVec::iter_element_bytes).
+///
+/// Unlike [slice::ChunksExact], it also works for zero sized elements, yielding one empty slice per element.
+pub struct ElementBytes<'a> {
+    bytes: &'a [u8],
+    size: usize,
+    len: usize,
+}
+
+impl<'a> ElementBytes<'a> {
+    /// `bytes` must hold `len` elements of `size` bytes each.
+    pub(crate) fn new(bytes: &'a [u8], size: usize, len: usize) -> Self {
+        debug_assert_eq!(bytes.len(), size * len);
+        ElementBytes { bytes, size, len }
+    }
+}
+
+impl<'a> Iterator for ElementBytes<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.len == 0 {
+            return None;
+        }
+        let (first, rest) = self.bytes.split_at(self.size);
+        self.bytes = rest;
+        self.len -= 1;
+        Some(first)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for ElementBytes<'a> {
+    fn next_back(&mut self) -> Option<&'a [u8]> {
+        if self.len == 0 {
+            return None;
+        }
+        let (rest, last) = self.bytes.split_at(self.bytes.len() - self.size);
+        self.bytes = rest;
+        self.len -= 1;
+        Some(last)
+    }
+}
+
+impl ExactSizeIterator for ElementBytes<'_> {}
+
+impl FusedIterator for ElementBytes<'_> {}
+
+/// Formats the element with the vtable's debug function if there's one, or only its type name otherwise.
+fn debug_element(
+    ptr: *const u8,
+    vtable: &'static ElementVTable,
+    name: &str,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    struct Element(*const u8, &'static ElementVTable);
+
+    impl Debug for Element {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let debug = self.1.debug.unwrap();
+            // The debug function formats a list, so this prints `[element]`.
+            unsafe { debug(self.0, 1, f) }
+        }
+    }
+
+    let mut s = f.debug_struct(name);
+    s.field("type_name", &vtable.type_name());
+    match vtable.debug {
+        Some(_) => s.field("element", &Element(ptr, vtable)).finish(),
+        None => s.finish_non_exhaustive(),
+    }
+}
@@ -0,0 +1,132 @@
+use std::{alloc::Layout, error, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The error returned when accessing a leaked [TypeErasedVec](crate::TypeErasedVec).
+///
+/// See [the crate level documentation](crate#leaking) for what leaked means.
+pub struct LeakedError;
+
+impl fmt::Display for LeakedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TypeErasedVec is leaked")
+    }
+}
+
+impl error::Error for LeakedError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The error returned by [TypeErasedVec::try_reserve](crate::TypeErasedVec::try_reserve) and [TypeErasedVec::try_reserve_exact](crate::TypeErasedVec::try_reserve_exact).
+///
+/// The same as [std::collections::TryReserveError], whose kind can't be constructed on stable.
+pub enum TryReserveError {
+    /// The required capacity exceeds the maximum for the element type.
+    CapacityOverflow,
+    /// The allocator failed to allocate the buffer.
+    AllocError {
+        /// The layout of the buffer that failed to allocate.
+        layout: Layout,
+    },
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")?;
+        match self {
+            TryReserveError::CapacityOverflow => {
+                f.write_str(" because the computed capacity exceeded the collection's maximum")
+            }
+            TryReserveError::AllocError { .. } => {
+                f.write_str(" because the memory allocator returned an error")
+            }
+        }
+    }
+}
+
+impl error::Error for TryReserveError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+/// The error returned by the checked conversion and byte APIs, like [TypeErasedVec::try_as_slice](crate::TypeErasedVec::try_as_slice)
+/// and [TypeErasedVec::try_extend_from_byte_slice](crate::TypeErasedVec::try_extend_from_byte_slice).
+pub enum Error {
+    /// The [TypeErasedVec](crate::TypeErasedVec) is leaked. See [LeakedError].
+    Leaked,
+    /// The requested type is not the element type.
+    TypeMismatch {
+        /// The name of the element type.
+        element: &'static str,
+        /// The name of the requested type.
+        requested: &'static str,
+    },
+    /// The element type is not known to be plain-old-data, so its bytes can't be accessed.
+    NotPod,
+    /// A byte length is not a whole number of elements.
+    LengthNotMultipleOfElement {
+        /// The byte length.
+        len: usize,
+        /// The element size.
+        element_size: usize,
+    },
+    /// The buffer is not aligned enough for the requested type.
+    Misaligned {
+        /// The required alignment.
+        align: usize,
+    },
+    /// Growing the buffer failed.
+    AllocError(TryReserveError),
+    /// Foreign data can't be read as elements, for the given reason.
+    Unsupported(&'static str),
+    #[cfg(feature = "opencl")]
+    /// An OpenCL call failed with the given error code.
+    OpenCl(i32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Leaked => fmt::Display::fmt(&LeakedError, f),
+            Error::TypeMismatch { element, requested } => {
+                write!(f, "element type is {element}, not {requested}")
+            }
+            Error::NotPod => f.write_str("element type is not known to be plain-old-data"),
+            Error::LengthNotMultipleOfElement { len, element_size } => write!(
+                f,
+                "byte length {len} is not a multiple of element size {element_size}"
+            ),
+            Error::Misaligned { align } => write!(f, "buffer is not aligned to {align} bytes"),
+            Error::AllocError(e) => fmt::Display::fmt(e, f),
+            Error::Unsupported(reason) => f.write_str(reason),
+            #[cfg(feature = "opencl")]
+            Error::OpenCl(code) => write!(f, "OpenCL call failed with error code {code}"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Leaked => Some(&LeakedError),
+            Error::AllocError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<LeakedError> for Error {
+    fn from(_: LeakedError) -> Self {
+        Error::Leaked
+    }
+}
+
+impl From<TryReserveError> for Error {
+    fn from(e: TryReserveError) -> Self {
+        Error::AllocError(e)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl From<opencl3::error_codes::ClError> for Error {
+    fn from(e: opencl3::error_codes::ClError) -> Self {
+        Error::OpenCl(e.0)
+    }
+}
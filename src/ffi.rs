@@ -0,0 +1,179 @@
+//! A C API over boxed [TypeErasedVec]s.
+//!
+//! C and C++ see a vec as an opaque `TypeErasedVec *`, created by `tev_vec_new` or handed over from Rust with
+//! [Box::into_raw], and released by `tev_vec_free`:
+//!
+//! ```c
+//! typedef struct TypeErasedVec TypeErasedVec;
+//! typedef struct {
+//!     void *ptr;
+//!     size_t len;
+//!     size_t capacity;
+//!     size_t element_size;
+//!     size_t element_align;
+//! } FfiRawParts;
+//!
+//! TypeErasedVec *tev_vec_new(size_t element_size, size_t element_align);
+//! bool tev_vec_push_bytes(TypeErasedVec *vec, const void *bytes, size_t len);
+//! void *tev_vec_ptr(TypeErasedVec *vec);
+//! size_t tev_vec_len(const TypeErasedVec *vec);
+//! FfiRawParts tev_vec_raw_parts(TypeErasedVec *vec);
+//! void tev_vec_free(TypeErasedVec *vec);
+//! ```
+
+#[cfg(feature = "type-id")]
+use std::any::TypeId;
+use std::{
+    alloc::Layout,
+    collections::BTreeMap,
+    ffi::c_void,
+    ptr, slice,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{ElementVTable, TypeErasedVec};
+
+#[cfg(feature = "type-id")]
+/// The element type of vecs created by `tev_vec_new`, which is plain-old-data of a layout only known at runtime.
+struct ForeignElement;
+
+/// The vtables of the layouts `tev_vec_new` was called with.
+static LAYOUT_VTABLES: Mutex<BTreeMap<(usize, usize), &'static ElementVTable>> =
+    Mutex::new(BTreeMap::new());
+
+/// Returns the vtable of plain-old-data elements of `layout`, leaking one the first time `layout` is seen.
+fn layout_vtable(layout: Layout) -> &'static ElementVTable {
+    let mut vtables = LAYOUT_VTABLES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    vtables
+        .entry((layout.size(), layout.align()))
+        .or_insert_with(|| {
+            Box::leak(Box::new(ElementVTable {
+                layout,
+                type_name: || "type_erased_vec::ffi::ForeignElement",
+                #[cfg(feature = "type-id")]
+                type_id: TypeId::of::<ForeignElement>,
+                drop: |_, _| {},
+                clone: None,
+                #[cfg(feature = "type-id")]
+                eq: None,
+                cmp: None,
+                hash: None,
+                debug: None,
+                #[cfg(feature = "serde")]
+                serialize: None,
+                #[cfg(feature = "serde")]
+                deserialize: None,
+                pod: true,
+                zeroable: true,
+                scalar_type: None,
+            }))
+        })
+}
+
+/// The raw parts of a [TypeErasedVec], as returned by `tev_vec_raw_parts`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FfiRawParts {
+    /// The first element, or null if the vec is leaked.
+    pub ptr: *mut c_void,
+    /// The number of elements.
+    pub len: usize,
+    /// The number of elements the buffer has room for.
+    pub capacity: usize,
+    /// The size of an element in bytes.
+    pub element_size: usize,
+    /// The alignment of an element in bytes.
+    pub element_align: usize,
+}
+
+/// Creates an empty vec of plain-old-data elements of `element_size` bytes aligned to `element_align`.
+///
+/// Returns null if `element_align` is not a power of two, or if the size overflows when rounded up to it.
+#[no_mangle]
+pub extern "C" fn tev_vec_new(element_size: usize, element_align: usize) -> *mut TypeErasedVec {
+    let Ok(layout) = Layout::from_size_align(element_size, element_align) else {
+        return ptr::null_mut();
+    };
+    let layout = layout.pad_to_align();
+    Box::into_raw(Box::new(TypeErasedVec::new_from_vtable(layout_vtable(
+        layout,
+    ))))
+}
+
+/// Appends the elements in the `len` bytes at `bytes`. See [TypeErasedVec::try_extend_from_byte_slice].
+///
+/// Returns `false` and leaves `vec` unchanged if its elements are not plain-old-data, if `len` is not a multiple
+/// of the element size, if the allocation fails, or if `vec` is leaked.
+///
+/// # Safety
+///
+/// `vec` must be a live vec from `tev_vec_new` or [Box::into_raw], and `bytes` must be valid for reads of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn tev_vec_push_bytes(
+    vec: *mut TypeErasedVec,
+    bytes: *const c_void,
+    len: usize,
+) -> bool {
+    let bytes = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(bytes.cast::<u8>(), len)
+    };
+    (*vec).try_extend_from_byte_slice(bytes).is_ok()
+}
+
+/// Returns the first element, or null if `vec` is leaked. The pointer is invalidated by anything growing `vec`.
+///
+/// # Safety
+///
+/// `vec` must be a live vec from `tev_vec_new` or [Box::into_raw].
+#[no_mangle]
+pub unsafe extern "C" fn tev_vec_ptr(vec: *mut TypeErasedVec) -> *mut c_void {
+    tev_vec_raw_parts(vec).ptr
+}
+
+/// Returns the number of elements, or 0 if `vec` is leaked.
+///
+/// # Safety
+///
+/// `vec` must be a live vec from `tev_vec_new` or [Box::into_raw].
+#[no_mangle]
+pub unsafe extern "C" fn tev_vec_len(vec: *const TypeErasedVec) -> usize {
+    (*vec).raw.as_ref().map_or(0, |raw| raw.len())
+}
+
+/// Describes the buffer of `vec`. Its pointer is null if `vec` is leaked.
+///
+/// # Safety
+///
+/// `vec` must be a live vec from `tev_vec_new` or [Box::into_raw].
+#[no_mangle]
+pub unsafe extern "C" fn tev_vec_raw_parts(vec: *mut TypeErasedVec) -> FfiRawParts {
+    let vec = &*vec;
+    let layout = vec.vtable.layout;
+    let (ptr, len, capacity) = match &vec.raw {
+        Some(raw) => (raw.ptr().cast(), raw.len(), raw.capacity()),
+        None => (ptr::null_mut(), 0, 0),
+    };
+    FfiRawParts {
+        ptr,
+        len,
+        capacity,
+        element_size: layout.size(),
+        element_align: layout.align(),
+    }
+}
+
+/// Drops `vec` and its elements. Does nothing if `vec` is null.
+///
+/// # Safety
+///
+/// `vec` must be null or a live vec from `tev_vec_new` or [Box::into_raw], not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn tev_vec_free(vec: *mut TypeErasedVec) {
+    if !vec.is_null() {
+        drop(Box::from_raw(vec));
+    }
+}
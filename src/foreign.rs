@@ -0,0 +1,118 @@
+//! Adopting buffers allocated outside Rust.
+
+use std::{
+    alloc::{AllocError, Layout},
+    fmt::{self, Debug},
+    ptr::NonNull,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{Allocator, ElementVTable, Global, TypeErasedVec};
+
+/// Releases a foreign buffer.
+type ReleaseFn = Box<dyn FnOnce(*mut u8) + Send>;
+
+/// The [Allocator] of vecs adopting a foreign buffer with [TypeErasedVec::from_foreign_buffer].
+///
+/// The foreign buffer is released by the callback it was adopted with, once the vec reallocates or is dropped.
+/// New buffers come from [Global].
+///
+/// Requires the `allocator-api` feature.
+pub struct ForeignAllocator {
+    buffer: *mut u8,
+    release: Mutex<Option<ReleaseFn>>,
+}
+
+// The callback is `Send`, and `buffer` is only compared with.
+unsafe impl Send for ForeignAllocator {}
+unsafe impl Sync for ForeignAllocator {}
+
+impl ForeignAllocator {
+    /// Releases the foreign buffer, if it's not already released.
+    fn release(&self) {
+        let release = self
+            .release
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some(release) = release {
+            release(self.buffer);
+        }
+    }
+
+    /// Returns if the foreign buffer is released.
+    pub fn is_released(&self) -> bool {
+        self.release
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_none()
+    }
+}
+
+unsafe impl Allocator for ForeignAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if ptr.as_ptr() == self.buffer && !self.is_released() {
+            self.release();
+        } else {
+            Global.deallocate(ptr, layout);
+        }
+    }
+}
+
+impl Drop for ForeignAllocator {
+    /// Releases the foreign buffer if the vec never deallocated it, e.g. because its capacity is 0.
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+impl Debug for ForeignAllocator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ForeignAllocator")
+            .field("buffer", &self.buffer)
+            .field("released", &self.is_released())
+            .finish()
+    }
+}
+
+impl TypeErasedVec<ForeignAllocator> {
+    /// Adopts a buffer allocated outside Rust, e.g. by a C library, a driver or in shared memory, holding `len` elements
+    /// described by `vtable` with room for `cap`. `release` is called with `ptr` once the buffer is no longer used,
+    /// after the elements in it are dropped or moved to a new buffer.
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use type_erased_vec::{ScalarType, TypeErasedVec};
+    ///
+    /// // Stands in for a buffer from `malloc`, released with `free`.
+    /// let ptr = Box::into_raw(Box::new([1u32, 2, 3])).cast::<u8>();
+    /// let vec = unsafe {
+    ///     TypeErasedVec::from_foreign_buffer(ptr, 3, 3, ScalarType::U32.vtable(), |ptr| {
+    ///         drop(Box::from_raw(ptr.cast::<[u32; 3]>()))
+    ///     })
+    /// };
+    /// assert_eq!(unsafe { vec.get::<u32>() }, [1, 2, 3]);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be aligned to the element alignment and valid for reads and writes of `cap` elements, the first `len` of which
+    /// are initialized elements of the type `vtable` describes, until `release` is called. Nothing else may use the buffer meanwhile.
+    pub unsafe fn from_foreign_buffer(
+        ptr: *mut u8,
+        len: usize,
+        cap: usize,
+        vtable: &'static ElementVTable,
+        release: impl FnOnce(*mut u8) + Send + 'static,
+    ) -> Self {
+        let alloc = ForeignAllocator {
+            buffer: ptr,
+            release: Mutex::new(Some(Box::new(release))),
+        };
+        Self::from_raw_parts(ptr, len, cap, vtable.layout, vtable, alloc)
+    }
+}
@@ -0,0 +1,112 @@
+//! Shared immutable buffers.
+
+use std::{
+    fmt::{self, Debug},
+    ops::Deref,
+    sync::Arc,
+};
+
+use crate::{Allocator, Global, TypeErasedVec};
+
+/// An immutable [TypeErasedVec] behind an [Arc], so cloning it is O(1) and all clones read the same buffer.
+/// See [TypeErasedVec::freeze].
+///
+/// It's [Send] and [Sync] if `V` is, which a plain `TypeErasedVec` never is.
+///
+/// ```
+/// use type_erased_vec::TypeErasedVec;
+///
+/// let vertices = TypeErasedVec::from_vec(vec![[0.0f32; 3]; 1024]).freeze();
+/// let for_renderer = vertices.clone();
+/// assert!(vertices.ptr_eq(&for_renderer));
+/// assert_eq!(for_renderer.len(), 1024);
+/// ```
+pub struct FrozenTypeErasedVec<V = TypeErasedVec<Global>> {
+    vec: Arc<V>,
+}
+
+impl<V> FrozenTypeErasedVec<V> {
+    pub(crate) fn new(vec: V) -> Self {
+        FrozenTypeErasedVec { vec: Arc::new(vec) }
+    }
+
+    pub(crate) fn inner(&self) -> &V {
+        &self.vec
+    }
+
+    /// Returns how many `FrozenTypeErasedVec`s share the buffer. See [Arc::strong_count].
+    pub fn ref_count(&self) -> usize {
+        Arc::strong_count(&self.vec)
+    }
+
+    /// Returns if `self` and `other` share the same buffer. See [Arc::ptr_eq].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.vec, &other.vec)
+    }
+
+    /// Gets back the mutable vec if `self` is the only reference to it, or returns `self` otherwise. See [Arc::try_unwrap].
+    pub fn try_unfreeze(self) -> Result<V, Self> {
+        Arc::try_unwrap(self.vec).map_err(|vec| FrozenTypeErasedVec { vec })
+    }
+}
+
+impl<V: Clone> FrozenTypeErasedVec<V> {
+    /// Gets a mutable reference to the vec, first cloning it if the buffer is shared. See [Arc::make_mut].
+    ///
+    /// Other `FrozenTypeErasedVec`s keep reading the original elements, so large buffers can be branched and edited cheaply.
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let base = TypeErasedVec::from_vec_clone(vec![1, 2, 3]).freeze();
+    /// let mut edited = base.clone();
+    /// unsafe { edited.make_mut().get_mut::<i32>().push(4) };
+    /// assert_eq!(unsafe { base.get::<i32>() }, [1, 2, 3]);
+    /// assert_eq!(unsafe { edited.get::<i32>() }, [1, 2, 3, 4]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is shared and cloning `V` panics, e.g. a [TypeErasedVec] whose element type is not known to be [Clone].
+    pub fn make_mut(&mut self) -> &mut V {
+        Arc::make_mut(&mut self.vec)
+    }
+}
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Moves the elements into a [FrozenTypeErasedVec], which can be cheaply cloned and shared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn freeze(self) -> FrozenTypeErasedVec<Self> {
+        raw!(self);
+        FrozenTypeErasedVec::new(self)
+    }
+}
+
+impl<A: Allocator> Deref for FrozenTypeErasedVec<TypeErasedVec<A>> {
+    type Target = TypeErasedVec<A>;
+
+    fn deref(&self) -> &TypeErasedVec<A> {
+        &self.vec
+    }
+}
+
+impl<V> Clone for FrozenTypeErasedVec<V> {
+    /// Shares the buffer, without copying any element.
+    fn clone(&self) -> Self {
+        FrozenTypeErasedVec {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<V: Debug> Debug for FrozenTypeErasedVec<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FrozenTypeErasedVec")
+            .field(&self.vec)
+            .finish()
+    }
+}
@@ -0,0 +1,179 @@
+//! Reordering and selecting plain-old-data elements by index lists and masks.
+
+use std::{cmp::Ordering, ptr};
+
+use crate::{dispatch, missing_capability, raw::RawVec, Allocator, TypeErasedSlice, TypeErasedVec};
+
+impl<A: Allocator + Clone> TypeErasedVec<A> {
+    /// Copies the elements at `indices`, in that order, into a new vec, e.g. to apply a vertex cache optimized order
+    /// or take rows of a column. Indices may repeat.
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let vec = TypeErasedVec::from_scalar_vec(vec![10u32, 11, 12]);
+    /// let taken = vec.gather(&[2, 0, 2]);
+    /// assert_eq!(unsafe { taken.get::<u32>() }, [12, 10, 12]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], if an index is out of bounds, or if `self` is leaked.
+    #[track_caller]
+    pub fn gather(&self, indices: &[u32]) -> Self {
+        let raw = raw!(self);
+        if !self.vtable.pod {
+            missing_capability(self.vtable, "from_pod_vec");
+        }
+        let layout = self.vtable.layout;
+        let size = layout.size();
+        let len = raw.len();
+        let mut gathered = RawVec::with_capacity_in(
+            layout.align_to(raw.align()).unwrap(),
+            indices.len(),
+            raw.allocator().clone(),
+        );
+        for (i, &index) in indices.iter().enumerate() {
+            let index = index as usize;
+            assert!(index < len, "index {index} out of range for length {len}");
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    raw.ptr().add(index * size),
+                    gathered.ptr().add(i * size),
+                    size,
+                );
+            }
+        }
+        unsafe {
+            gathered.set_len(indices.len());
+            Self::from_raw(gathered, self.vtable)
+        }
+    }
+
+    /// Copies the elements whose entries in `mask` are `true`, in order, into a new vec, e.g. to keep the rows of
+    /// a column a predicate selected. Runs of selected elements are copied at once.
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let vec = TypeErasedVec::from_scalar_vec(vec![10u32, 11, 12, 13]);
+    /// let filtered = vec.filter(&[true, false, true, true]);
+    /// assert_eq!(unsafe { filtered.get::<u32>() }, [10, 12, 13]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], if `mask` and `self` have different
+    /// lengths, or if `self` is leaked.
+    #[track_caller]
+    pub fn filter(&self, mask: &[bool]) -> Self {
+        let raw = raw!(self);
+        if !self.vtable.pod {
+            missing_capability(self.vtable, "from_pod_vec");
+        }
+        assert_eq!(mask.len(), raw.len(), "mask and vec lengths differ");
+        let layout = self.vtable.layout;
+        let size = layout.size();
+        let count = mask.iter().filter(|&&selected| selected).count();
+        let mut filtered = RawVec::with_capacity_in(
+            layout.align_to(raw.align()).unwrap(),
+            count,
+            raw.allocator().clone(),
+        );
+        let (mut index, mut len) = (0, 0);
+        for run in mask.split(|&selected| !selected) {
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    raw.ptr().add(index * size),
+                    filtered.ptr().add(len * size),
+                    run.len() * size,
+                );
+            }
+            index += run.len() + 1;
+            len += run.len();
+        }
+        unsafe {
+            filtered.set_len(count);
+            Self::from_raw(filtered, self.vtable)
+        }
+    }
+}
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Copies the elements of `src` to `indices`, in that order, the inverse of [TypeErasedVec::gather].
+    /// If an index repeats, the last element copied to it stays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], if `src` has a different element type,
+    /// if `indices` and `src` have different lengths, if an index is out of bounds, or if `self` is leaked.
+    #[track_caller]
+    pub fn scatter_from(&mut self, indices: &[u32], src: TypeErasedSlice<'_>) {
+        #[cfg(feature = "type-id")]
+        crate::assert_same_element_type(self.vtable, src.vtable());
+        assert_eq!(
+            src.vtable().layout,
+            self.vtable.layout,
+            "element layouts differ"
+        );
+        assert_eq!(
+            indices.len(),
+            src.len(),
+            "indices and source lengths differ"
+        );
+        let Some(src) = src.as_bytes() else {
+            missing_capability(src.vtable(), "from_pod_slice");
+        };
+        let size = self.vtable.layout.size();
+        let raw = self.pod_raw_mut();
+        let len = raw.len();
+        for (element, &index) in src.chunks_exact(size.max(1)).zip(indices) {
+            let index = index as usize;
+            assert!(index < len, "index {index} out of range for length {len}");
+            unsafe {
+                ptr::copy_nonoverlapping(element.as_ptr(), raw.ptr().add(index * size), size);
+            }
+        }
+    }
+
+    /// Returns the indices of the elements in ascending order, e.g. to sort several parallel columns by this one
+    /// with [TypeErasedVec::gather]. The sort is stable, and `NaN`s come last.
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let keys = TypeErasedVec::from_scalar_vec(vec![3.0f32, 1.0, 2.0]);
+    /// let names = TypeErasedVec::from_scalar_vec(vec![b'c', b'a', b'b']);
+    /// let order = keys.argsort();
+    /// assert_eq!(order, [1, 2, 0]);
+    /// assert_eq!(unsafe { names.gather(&order).get::<u8>() }, b"abc");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_scalar_vec] or [TypeErasedVec::new_scalar],
+    /// if the length exceeds `u32::MAX`, or if `self` is leaked.
+    #[track_caller]
+    pub fn argsort(&self) -> Vec<u32> {
+        let Some(scalar_type) = self.scalar_type() else {
+            missing_capability(self.vtable, "from_scalar_vec or new_scalar");
+        };
+        let len = u32::try_from(self.len()).expect("length exceeds u32::MAX");
+        let mut indices: Vec<u32> = (0..len).collect();
+        // `dispatch!` only calls this with the element type.
+        dispatch!(scalar_type, |T| {
+            let elements = unsafe { self.get::<T>() };
+            indices.sort_by(|&a, &b| nan_last(&elements[a as usize], &elements[b as usize]));
+        });
+        indices
+    }
+}
+
+/// Compares `a` and `b`, ordering incomparable elements like `NaN` after the others.
+fn nan_last<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    let is_nan = |x: &T| x.partial_cmp(x).is_none();
+    match (is_nan(a), is_nan(b)) {
+        (false, false) => a.partial_cmp(b).unwrap(),
+        (a, b) => a.cmp(&b),
+    }
+}
@@ -0,0 +1,78 @@
+//! Uploading to OpenGL buffers with `glow`.
+
+use std::ops::Range;
+
+use glow::HasContext;
+
+use crate::{missing_capability, Allocator, TypeErasedVec};
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Creates a buffer, binds it to `target` (e.g. `glow::ARRAY_BUFFER`) and fills it with the elements,
+    /// passing `usage` (e.g. `glow::STATIC_DRAW`) as the usage hint. The buffer stays bound.
+    ///
+    /// ```
+    /// use glow::HasContext;
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// unsafe fn upload_vertices(gl: &glow::Context, vertices: &mut TypeErasedVec) -> glow::Buffer {
+    ///     let buffer = vertices.upload_to_gl(gl, glow::ARRAY_BUFFER, glow::DYNAMIC_DRAW).unwrap();
+    ///     let mut tracked = vertices.get_mut_tracked::<[f32; 2]>();
+    ///     tracked.set(1, [0.5, 0.5]);
+    ///     let dirty = tracked.into_dirty_ranges();
+    ///     vertices.update_gl_sub_data(gl, glow::ARRAY_BUFFER, &dirty);
+    ///     buffer
+    /// }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `gl` must be current on this thread, and `target` and `usage` must be valid enums for `glBufferData`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data, or if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn upload_to_gl<G: HasContext>(
+        &self,
+        gl: &G,
+        target: u32,
+        usage: u32,
+    ) -> Result<G::Buffer, String> {
+        let Some(bytes) = self.as_bytes() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        let buffer = gl.create_buffer()?;
+        gl.bind_buffer(target, Some(buffer));
+        gl.buffer_data_u8_slice(target, bytes, usage);
+        Ok(buffer)
+    }
+
+    /// Copies the bytes in each of `dirty` to the buffer bound to `target`, at the same offsets,
+    /// e.g. with the ranges recorded by [TrackedVecMut](crate::TrackedVecMut).
+    ///
+    /// The bound buffer must have been filled by [TypeErasedVec::upload_to_gl] or otherwise be at least as large as `self`.
+    ///
+    /// # Safety
+    ///
+    /// `gl` must be current on this thread, and a buffer large enough must be bound to `target`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data, if a range is out of bounds,
+    /// or if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn update_gl_sub_data<G: HasContext>(
+        &self,
+        gl: &G,
+        target: u32,
+        dirty: &[Range<usize>],
+    ) {
+        let Some(bytes) = self.as_bytes() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        for range in dirty {
+            let offset = i32::try_from(range.start).expect("offset exceeds i32::MAX");
+            gl.buffer_sub_data_u8_slice(target, offset, &bytes[range.clone()]);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Converting between columns of attributes and interleaved vertex buffers.
+
+use crate::{missing_capability, BufferLayout, Global, TypeErasedSlice, TypeErasedVec};
+
+impl TypeErasedVec<Global> {
+    /// Interleaves `columns` into one buffer of `layout`, where column `i` holds the elements of attribute `i`,
+    /// e.g. before uploading vertex data. Bytes no attribute covers are zeroed.
+    ///
+    /// The result is a vec of `u8`.
+    ///
+    /// ```
+    /// use type_erased_vec::{BufferLayout, TypeErasedVec};
+    ///
+    /// let positions = TypeErasedVec::from_scalar_vec(vec![1.0f32, 2.0]);
+    /// let ids = TypeErasedVec::from_scalar_vec(vec![7u16, 8]);
+    /// let layout = BufferLayout::new(8)
+    ///     .with_attribute(0, positions.vtable())
+    ///     .with_attribute(4, ids.vtable());
+    /// let vertices =
+    ///     TypeErasedVec::interleave(&[positions.as_erased_slice(), ids.as_erased_slice()], &layout);
+    /// assert_eq!(vertices.len(), 16);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `columns` and the attributes of `layout` have different lengths, if a column's element type is not known
+    /// to be plain-old-data or has a different size than its attribute, or if the columns have different lengths.
+    #[track_caller]
+    pub fn interleave(columns: &[TypeErasedSlice<'_>], layout: &BufferLayout) -> Self {
+        let attributes = layout.attributes();
+        assert_eq!(
+            columns.len(),
+            attributes.len(),
+            "columns and attributes lengths differ"
+        );
+        let count = columns.first().map_or(0, |column| column.len());
+        let mut vertices = vec![0u8; count * layout.stride()];
+        for (column, attribute) in columns.iter().zip(attributes) {
+            assert_eq!(column.len(), count, "column lengths differ");
+            let Some(bytes) = column.as_bytes() else {
+                missing_capability(column.vtable(), "from_pod_vec");
+            };
+            let size = column.vtable().layout().size();
+            assert_eq!(
+                size,
+                attribute.size(),
+                "element size of {} differs from its attribute",
+                column.vtable().type_name()
+            );
+            if size == 0 {
+                continue;
+            }
+            for (index, element) in bytes.chunks_exact(size).enumerate() {
+                vertices[attribute.range(index)].copy_from_slice(element);
+            }
+        }
+        TypeErasedVec::from_scalar_vec(vertices)
+    }
+
+    /// Splits a buffer of `layout` into one column per attribute, the inverse of [TypeErasedVec::interleave],
+    /// e.g. for importers receiving packed vertex data.
+    ///
+    /// ```
+    /// use type_erased_vec::{BufferLayout, ScalarType, TypeErasedVec};
+    ///
+    /// let vertices = [1u16.to_ne_bytes(), 2u16.to_ne_bytes()].concat();
+    /// let layout = BufferLayout::new(2).with_attribute(1, ScalarType::U8.vtable());
+    /// let columns = TypeErasedVec::deinterleave(&vertices, &layout);
+    /// assert_eq!(columns[0].len(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `vertices` is not a multiple of the stride, or if an attribute's element type is not known
+    /// to be plain-old-data.
+    #[track_caller]
+    pub fn deinterleave(vertices: &[u8], layout: &BufferLayout) -> Vec<Self> {
+        let stride = layout.stride();
+        assert!(
+            stride == 0 || vertices.len().is_multiple_of(stride),
+            "byte length {} is not a multiple of stride {stride}",
+            vertices.len()
+        );
+        let count = layout.vertex_count(vertices.len());
+        layout
+            .attributes()
+            .iter()
+            .map(|attribute| {
+                let vtable = attribute.format;
+                if !vtable.pod {
+                    missing_capability(vtable, "from_pod_vec");
+                }
+                let mut column = Self::new_from_vtable(vtable);
+                if attribute.size() == 0 {
+                    column.resize_zeroed(count);
+                } else {
+                    column.reserve(count);
+                    for index in 0..count {
+                        column.extend_from_byte_slice(&vertices[attribute.range(index)]);
+                    }
+                }
+                column
+            })
+            .collect()
+    }
+}
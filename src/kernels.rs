@@ -0,0 +1,104 @@
+//! Loops over the bytes of whole buffers, vectorized with `std::simd` with the `simd` feature.
+//!
+//! Copies and zero fills are left to `memcpy` and `memset`, which are already vectorized.
+
+/// Reverses the byte order of every `width` byte scalar in `bytes`, whose length is a multiple of `width`.
+pub(crate) fn swap_bytes(bytes: &mut [u8], width: usize) {
+    #[cfg(feature = "simd")]
+    let bytes = match width {
+        2 => simd::swap_bytes::<2>(bytes),
+        4 => simd::swap_bytes::<4>(bytes),
+        8 => simd::swap_bytes::<8>(bytes),
+        16 => simd::swap_bytes::<16>(bytes),
+        _ => bytes,
+    };
+    for scalar in bytes.chunks_exact_mut(width) {
+        scalar.reverse();
+    }
+}
+
+/// Repeats `pattern`, which is not empty, over `bytes`, whose length is a multiple of the pattern length.
+pub(crate) fn fill_pattern(bytes: &mut [u8], pattern: &[u8]) {
+    debug_assert!(!pattern.is_empty());
+    #[cfg(feature = "simd")]
+    let bytes = simd::fill_pattern(bytes, pattern);
+    let Some(first) = bytes.get_mut(..pattern.len()) else {
+        return;
+    };
+    first.copy_from_slice(pattern);
+    // Doubles the filled prefix with each copy.
+    let mut filled = pattern.len();
+    while filled < bytes.len() {
+        let count = filled.min(bytes.len() - filled);
+        bytes.copy_within(..count, filled);
+        filled += count;
+    }
+}
+
+#[cfg(feature = "simd")]
+pub(crate) mod simd {
+    use std::{
+        array,
+        mem::MaybeUninit,
+        simd::{Simd, SimdElement, Swizzle},
+    };
+
+    /// The number of bytes processed at once.
+    const BYTES: usize = 32;
+
+    /// The number of scalars converted at once.
+    pub(crate) const LANES: usize = 16;
+
+    /// Reverses the bytes of each group of `W`.
+    struct ReverseGroups<const W: usize>;
+
+    impl<const W: usize> Swizzle<BYTES> for ReverseGroups<W> {
+        const INDEX: [usize; BYTES] = {
+            let mut index = [0; BYTES];
+            let mut i = 0;
+            while i < BYTES {
+                index[i] = i - i % W + (W - 1 - i % W);
+                i += 1;
+            }
+            index
+        };
+    }
+
+    /// Swaps the bytes of the `W` byte scalars in the whole blocks of `bytes`, and returns the rest.
+    pub(crate) fn swap_bytes<const W: usize>(bytes: &mut [u8]) -> &mut [u8] {
+        let (blocks, rest) = bytes.as_chunks_mut::<BYTES>();
+        for block in blocks {
+            *block = ReverseGroups::<W>::swizzle(Simd::from_array(*block)).to_array();
+        }
+        rest
+    }
+
+    /// Fills the whole blocks of `bytes` with `pattern` if it evenly divides a block, and returns the rest.
+    pub(crate) fn fill_pattern<'a>(bytes: &'a mut [u8], pattern: &[u8]) -> &'a mut [u8] {
+        if !BYTES.is_multiple_of(pattern.len()) {
+            return bytes;
+        }
+        let block = Simd::<u8, BYTES>::from_array(array::from_fn(|i| pattern[i % pattern.len()]));
+        let (blocks, rest) = bytes.as_chunks_mut::<BYTES>();
+        for chunk in blocks {
+            block.copy_to_slice(chunk);
+        }
+        rest
+    }
+
+    /// Maps the whole blocks of `src` with `f` into `dst`, which has the same length, and returns the rest of both.
+    pub(crate) fn map<'a, 'b, S: SimdElement, D: SimdElement>(
+        src: &'a [S],
+        dst: &'b mut [MaybeUninit<D>],
+        f: impl Fn(Simd<S, LANES>) -> Simd<D, LANES>,
+    ) -> (&'a [S], &'b mut [MaybeUninit<D>]) {
+        let (src_blocks, src_rest) = src.as_chunks::<LANES>();
+        let (dst_blocks, dst_rest) = dst.as_chunks_mut::<LANES>();
+        for (src, dst) in src_blocks.iter().zip(dst_blocks) {
+            for (dst, value) in dst.iter_mut().zip(f(Simd::from_array(*src)).to_array()) {
+                dst.write(value);
+            }
+        }
+        (src_rest, dst_rest)
+    }
+}
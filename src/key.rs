@@ -0,0 +1,65 @@
+//! Vecs as hash map keys.
+
+use std::hash::{Hash, Hasher};
+
+use crate::{Allocator, Global, TypeErasedVec};
+
+/// A [TypeErasedVec] known to be comparable and hashable, usable as a `HashMap` or `HashSet` key,
+/// e.g. to cache work done per buffer content.
+///
+/// [Eq] requires the element type's [PartialEq] to be reflexive, which float elements break with `NaN`.
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use type_erased_vec::{ErasedKey, TypeErasedVec, TypedVTable};
+///
+/// static VTABLE: TypedVTable<u32> = TypedVTable::new().with_eq().with_hash();
+/// let key = |vec| ErasedKey::new(TypeErasedVec::from_vec_with_vtable(vec, &VTABLE)).unwrap();
+/// let mut seen = HashSet::new();
+/// assert!(seen.insert(key(vec![1, 2])));
+/// assert!(!seen.insert(key(vec![1, 2])));
+/// assert!(ErasedKey::new(TypeErasedVec::from_vec_hash(vec![1u32])).is_none());
+/// ```
+#[derive(Debug)]
+pub struct ErasedKey<A: Allocator = Global> {
+    vec: TypeErasedVec<A>,
+}
+
+impl<A: Allocator> ErasedKey<A> {
+    /// Wraps `vec`, or returns `None` if it can't be compared and hashed or is leaked.
+    ///
+    /// Vecs constructed with [TypeErasedVec::from_vec_with_vtable] with a vtable built with both
+    /// [TypedVTable::with_eq](crate::TypedVTable::with_eq) and [TypedVTable::with_hash](crate::TypedVTable::with_hash)
+    /// can be wrapped.
+    pub fn new(vec: TypeErasedVec<A>) -> Option<Self> {
+        let capable = vec.vtable.eq.is_some() && vec.vtable.hash.is_some() && vec.raw.is_some();
+        capable.then_some(ErasedKey { vec })
+    }
+
+    /// Gets the vec.
+    pub fn as_erased(&self) -> &TypeErasedVec<A> {
+        &self.vec
+    }
+
+    /// Unwraps the vec.
+    pub fn into_inner(self) -> TypeErasedVec<A> {
+        self.vec
+    }
+}
+
+impl<A: Allocator, B: Allocator> PartialEq<ErasedKey<B>> for ErasedKey<A> {
+    /// See [TypeErasedVec::eq_erased].
+    fn eq(&self, other: &ErasedKey<B>) -> bool {
+        self.vec.eq_erased(&other.vec)
+    }
+}
+
+impl<A: Allocator> Eq for ErasedKey<A> {}
+
+impl<A: Allocator> Hash for ErasedKey<A> {
+    /// See [TypeErasedVec::hash_erased].
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.vec.hash_erased(state);
+    }
+}
@@ -24,8 +24,10 @@
 //! What's more, the content of `TypeErasedVec` is only valid after `VecMut`'s destructor is called.
 //!
 //! A `TypeErasedVec` is said to be in `leaked` state if the destructor of the returned `VecMut` of a previous call to `get_mut` didn't run.
+//! Use [TypeErasedVec::with_mut] if you don't need to hold the `VecMut`, as it can't leak.
 //!
-//! Calling any method except for [TypeErasedVec::is_leaked] on a leaked `TypeErasedVec` results in panic.
+//! Calling any method that accesses the buffer on a leaked `TypeErasedVec` results in panic. Such methods document it in their `# Panics` section.
+//! A leaked `TypeErasedVec` can be reused after [TypeErasedVec::reset].
 //!
 //! # Example
 //!
@@ -43,6 +45,54 @@
 //!
 //! assert_eq!(unsafe { vec.get::<i32>() }, (0..10).collect::<Vec<_>>());
 //! ```
+//!
+//! # Features
+//!
+//! - `type-id` (default): Records the [std::any::TypeId] of the element type, enabling checked access like [TypeErasedVec::try_get].
+//!   Element types must be `'static` when this feature is enabled. See [Erasable]. Also enables [TypeRegistry], [DynVec] and [ErasedKey].
+//! - `bytemuck` (default): Enables [TypeErasedVec::from_pod_vec] and safe byte access to plain-old-data buffers.
+//! - `allocator-api`: Supports custom allocators through the unstable `allocator_api`, which requires a nightly toolchain.
+//!   Without it, only the global allocator is supported and the crate compiles on stable.
+//!   [Allocator] and [Global] are stand-ins for the unstable ones without it, and re-exports of `std::alloc` with it.
+//!   Also enables [TypeErasedVec::from_foreign_buffer], which adopts buffers allocated outside Rust with a release callback.
+//! - `serde`: Enables [TypeErasedVec::from_vec_serialize], whose results implement `Serialize`,
+//!   and `ErasedVecSeed`, which deserializes elements of a type chosen at runtime.
+//! - `serde-pod-bytes`: Serializes and deserializes plain-old-data elements as a single length prefixed block of native endian bytes,
+//!   which binary formats like bincode and postcard encode at near `memcpy` speed.
+//! - `derive`: Adds `#[derive(ErasedElement)]`, which builds the [ErasedElement] vtable from a list of capabilities.
+//! - `rkyv`: Archives vecs constructed with [TypeErasedVec::from_pod_vec] as `ArchivedTypeErasedVec`, readable without copying.
+//! - `arrow`: Adds [TypeErasedVec::into_arrow_buffer] and [TypeErasedVec::into_arrow_array_data], which convert scalar vecs
+//!   to Apache Arrow buffers and primitive array data without copying, and their counterparts converting back.
+//! - `arrow-ffi`: Adds [TypeErasedVec::export_to_c], which hands scalar vecs over through the Arrow C Data Interface
+//!   without copying, e.g. to DuckDB, polars or pyarrow.
+//! - `ash`: Adds [TypeErasedVec::copy_to_vk_staging], which copies plain-old-data vecs into Vulkan staging memory
+//!   at offsets and sizes aligned to the `VkPhysicalDeviceLimits`.
+//! - `bytes`: Adds [TypeErasedVec::into_bytes], which converts plain-old-data vecs to `bytes::Bytes` without copying,
+//!   for network code built on `tokio` or `hyper`, and conversions to and from `BytesMut`.
+//! - `cuda`: Adds [TypeErasedVec::memcpy_htod_async] and [TypeErasedVec::memcpy_dtoh_async] for copying plain-old-data vecs
+//!   to and from CUDA device memory through `cudarc`. With `allocator-api`, also adds `CudaPinnedAllocator`,
+//!   which allocates page-locked host memory for truly asynchronous copies.
+//! - `dlpack`: Adds [TypeErasedVec::into_dlpack] and [DlpackTensor] for exchanging scalar buffers without copying
+//!   with PyTorch, TensorFlow, JAX and other libraries supporting DLPack.
+//! - `ffi`: Adds the [ffi] module, `extern "C"` functions for C and C++ to create vecs of plain-old-data elements
+//!   from their layout, fill them with bytes, read their raw parts and free them, including vecs handed over from Rust.
+//! - `glow`: Adds [TypeErasedVec::upload_to_gl] for plain-old-data vecs, and [TypeErasedVec::update_gl_sub_data]
+//!   for uploading only the byte ranges recorded by [TrackedVecMut].
+//! - `half`: Adds the [ScalarType::F16] and [ScalarType::BF16] half precision scalar types from the `half` crate,
+//!   which GPU and machine learning data is increasingly stored in, and re-exports `half`.
+//! - `mmap`: Adds [TypeErasedSlice::from_mmap], which views plain-old-data elements in a `memmap2` memory-mapped file
+//!   without copying them into memory, checking the region's alignment.
+//! - `ndarray`: Adds [TypeErasedVec::as_array_view], which views elements as an n-dimensional `ndarray` array,
+//!   and [ShapedTypeErasedVec], which stores the shape with the vec and converts from arrays.
+//! - `opencl`: Adds [TypeErasedVec::create_cl_buffer] for plain-old-data vecs, and [TypeErasedVec::from_cl_buffer]
+//!   for reading buffers back as elements of a [ScalarType], through `opencl3`. The OpenCL library is loaded at runtime.
+//! - `pyo3`: Adds [PyErasedBuffer], which exposes scalar vecs to Python as read-only objects supporting the buffer protocol,
+//!   and [TypeErasedVec::from_py_buffer], which copies `numpy` arrays and other buffers into vecs of the matching [ScalarType].
+//! - `simd`: Vectorizes [TypeErasedVec::swap_bytes_in_place], [TypeErasedVec::fill_bytes] and [TypeErasedVec::convert_to]
+//!   with the unstable `std::simd`, which requires a nightly toolchain. Copies and zero fills already use the vectorized
+//!   `memcpy` and `memset`.
+//! - `wgpu`: Adds [TypeErasedVec::create_wgpu_buffer] and [TypeErasedVec::write_wgpu_buffer] for plain-old-data vecs,
+//!   and [TypeErasedVec::from_wgpu_buffer_view] for reading mapped buffers back.
 
 #![deny(
     missing_docs,
@@ -52,133 +102,527 @@
     unused_import_braces,
     unused_qualifications
 )]
-#![feature(allocator_api)]
+#![cfg_attr(feature = "allocator-api", feature(allocator_api))]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+#[cfg(all(test, feature = "derive"))]
+// Lets derived code refer to this crate by name in tests.
+extern crate self as type_erased_vec;
+
+/// Gets the [RawVec] of a [TypeErasedVec], panicking with the element type and leak location if it's leaked.
+macro_rules! raw {
+    (take $vec:expr) => {
+        match $vec.raw.take() {
+            Some(raw) => raw,
+            None => $crate::leaked($vec.vtable, $vec.leaked_at),
+        }
+    };
+    (mut $vec:expr) => {
+        match &mut $vec.raw {
+            Some(raw) => raw,
+            None => $crate::leaked($vec.vtable, $vec.leaked_at),
+        }
+    };
+    ($vec:expr) => {
+        match &$vec.raw {
+            Some(raw) => raw,
+            None => $crate::leaked($vec.vtable, $vec.leaked_at),
+        }
+    };
+}
 
+#[cfg(not(feature = "allocator-api"))]
+mod allocator;
+#[cfg(feature = "rkyv")]
+mod archive;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "arrow-ffi")]
+mod arrow_ffi;
+#[cfg(feature = "ash")]
+mod ash_staging;
+mod boxed;
+#[cfg(feature = "bytes")]
+mod bytes_interop;
+mod chunked;
+mod collect;
+mod convert;
+#[cfg(feature = "cuda")]
+mod cuda;
+#[cfg(feature = "dlpack")]
+mod dlpack;
+mod double_buffered;
+#[cfg(feature = "type-id")]
+mod dyn_vec;
+mod element;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "allocator-api")]
+mod foreign;
+mod frozen;
+mod gather;
+#[cfg(feature = "glow")]
+mod glow_upload;
+mod interleave;
+mod kernels;
+#[cfg(feature = "type-id")]
+mod key;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod nullable;
+#[cfg(feature = "opencl")]
+mod opencl;
+mod pool;
+#[cfg(feature = "pyo3")]
+mod python;
+mod reduce;
+#[cfg(feature = "bytemuck")]
+mod ring;
+mod scalar;
+mod send;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "ndarray")]
+mod shaped;
+mod slice;
+mod sync;
+mod tracked;
+mod vec_registry;
+mod vertex_layout;
+mod vtable;
+#[cfg(feature = "wgpu")]
+mod wgpu_interop;
+mod writer;
+
+#[cfg(not(feature = "allocator-api"))]
+pub use allocator::{Allocator, Global};
+#[cfg(feature = "rkyv")]
+pub use archive::{ArchivedTypeErasedVec, TypeErasedVecResolver};
+#[cfg(feature = "ash")]
+pub use ash_staging::VkStagingRegion;
+#[cfg(feature = "allocator-api")]
+pub use std::alloc::{Allocator, Global};
+
+pub use boxed::TypeErasedBox;
+pub use chunked::ChunkedTypeErasedVec;
+pub use collect::CollectErased;
+#[cfg(all(feature = "cuda", feature = "allocator-api"))]
+pub use cuda::CudaPinnedAllocator;
+#[cfg(feature = "dlpack")]
+pub use dlpack::{DLDataType, DLDevice, DLManagedTensor, DLTensor, DlpackTensor};
+pub use double_buffered::DoubleBuffered;
+#[cfg(feature = "type-id")]
+pub use dyn_vec::{DynVec, IterErased};
+pub use element::{ElementBytes, ErasedMut, ErasedRef};
+pub use error::{Error, LeakedError, TryReserveError};
+#[cfg(feature = "allocator-api")]
+pub use foreign::ForeignAllocator;
+pub use frozen::FrozenTypeErasedVec;
+#[cfg(feature = "half")]
+pub use half;
+#[cfg(feature = "type-id")]
+pub use key::ErasedKey;
+pub use nullable::NullableTypeErasedVec;
+pub use pool::{BufferPool, PooledVec};
+#[cfg(feature = "pyo3")]
+pub use python::PyErasedBuffer;
+#[cfg(feature = "bytemuck")]
+pub use ring::RingErasedBuffer;
+pub use scalar::{Scalar, ScalarType};
+pub use send::{SendSyncTypeErasedVec, SendTypeErasedVec};
+#[cfg(feature = "serde")]
+pub use serialization::ErasedVecSeed;
+#[cfg(feature = "ndarray")]
+pub use shaped::ShapedTypeErasedVec;
+pub use slice::{TypeErasedSlice, TypeErasedSliceMut};
+pub use sync::{ReadGuard, SyncTypeErasedVec, WriteGuard};
+pub use tracked::TrackedVecMut;
+#[cfg(feature = "derive")]
+pub use type_erased_vec_derive::ErasedElement;
+pub use vec_registry::{BufferId, ErasedVecRegistry};
+pub use vertex_layout::{AttributeLayout, BufferLayout};
+pub use vtable::{ElementVTable, ErasedElement, TypedVTable};
+pub use writer::ByteWriter;
+
+#[cfg(feature = "type-id")]
+use std::any::TypeId;
 use std::{
-    alloc::{Allocator, Global},
-    mem::{forget, ManuallyDrop},
-    ops::{Deref, DerefMut},
+    alloc::Layout,
+    cmp::Ordering,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    io::{self, Read, Write},
+    mem::{forget, ManuallyDrop, MaybeUninit},
+    ops::{Bound, Deref, DerefMut, Range, RangeBounds},
+    panic::Location,
 };
 
-mod raw {
-    use super::{Allocator, Global, ManuallyDrop};
+mod raw;
+#[cfg(feature = "type-id")]
+mod registry;
 
-    #[derive(Debug)]
-    /// The raw parts of a `Vec`.
-    ///
-    /// This struct is leaking. It doesn't call the destructor of the elements or deallocate memory.
-    pub struct RawVec<A: Allocator> {
-        ptr: *mut u8,
-        len: usize,
-        cap: usize,
-        alloc: A,
-    }
+use raw::{DeallocGuard, RawVec};
+#[cfg(feature = "type-id")]
+pub use registry::TypeRegistry;
 
-    impl<A: Allocator> RawVec<A> {
-        pub fn from_vec<T>(vec: Vec<T, A>) -> Self {
-            let (ptr, len, cap, alloc) = vec.into_raw_parts_with_alloc();
-            RawVec {
-                ptr: ptr.cast(),
-                len,
-                cap,
-                alloc,
-            }
-        }
+#[cfg(feature = "type-id")]
+/// Types that can be stored in a [TypeErasedVec].
+///
+/// With the `type-id` feature, this requires `'static` so the element [TypeId] can be recorded.
+pub trait Erasable: 'static {}
 
-        pub fn allocator(&self) -> &A {
-            &self.alloc
-        }
+#[cfg(feature = "type-id")]
+impl<T: 'static> Erasable for T {}
+
+#[cfg(not(feature = "type-id"))]
+/// Types that can be stored in a [TypeErasedVec].
+///
+/// Without the `type-id` feature, this is implemented for all types.
+pub trait Erasable {}
+
+#[cfg(not(feature = "type-id"))]
+impl<T> Erasable for T {}
+
+/// A type erased [Vec].
+pub struct TypeErasedVec<A: Allocator = Global> {
+    /// The raw form of the `Vec`. It's only None after [TypeErasedVec::get_mut] and restored to `Some` after [VecMut] destruction.
+    raw: Option<RawVec<A>>,
+    vtable: &'static ElementVTable,
+    /// Where the last [VecMut] was created, to tell where a leak came from.
+    leaked_at: Option<&'static Location<'static>>,
+}
+
+/// `Vec<T, A>`, or `Vec<T>` without the `allocator-api` feature, as `Vec` has no allocator parameter on stable.
+#[cfg(feature = "allocator-api")]
+macro_rules! vec_type {
+    ($t:ty, $a:ty) => {
+        Vec<$t, $a>
+    };
+}
+
+/// `Vec<T, A>`, or `Vec<T>` without the `allocator-api` feature, as `Vec` has no allocator parameter on stable.
+#[cfg(not(feature = "allocator-api"))]
+macro_rules! vec_type {
+    ($t:ty, $a:ty) => {
+        Vec<$t>
+    };
+}
 
-        /// # Safety
-        ///
-        /// `T` must be the same as in `from_vec`.
-        pub unsafe fn into_vec<T>(self) -> Vec<T, A> {
-            Vec::from_raw_parts_in(self.ptr.cast(), self.len, self.cap, self.alloc)
+/// Implements constructors taking a `vec_type!(T, A)`.
+///
+/// Without the `allocator-api` feature, they are only implemented for `TypeErasedVec<Global>`, because `A` can't be inferred from a `Vec<T>`.
+macro_rules! vec_constructors {
+    ($($item:item)*) => {
+        #[cfg(feature = "allocator-api")]
+        impl<A: Allocator> TypeErasedVec<A> {
+            $($item)*
         }
 
-        /// # Safety
-        ///
-        /// `T` must be the same as in `from_vec`.
-        pub unsafe fn as_slice<T>(&self) -> &[T] {
-            std::slice::from_raw_parts(self.ptr.cast(), self.len)
+        #[cfg(not(feature = "allocator-api"))]
+        impl TypeErasedVec<Global> {
+            $($item)*
         }
+    };
+}
+
+vec_constructors! {
+    /// Erases the type of `vec`.
+    pub fn from_vec<T: Erasable>(vec: vec_type!(T, A)) -> Self {
+        unsafe { Self::from_raw(RawVec::from_vec(vec), &TypedVTable::<T>::NEW.vtable) }
     }
 
-    impl RawVec<Global> {
-        /// # Safety
-        /// - `T` must be the same as in `from_vec`.
-        /// - Returned value must not outlive underlying memory.
-        /// - Multiple return values of this method must not be dropped more than once.
-        pub unsafe fn as_manually_drop_vec<T>(&self) -> ManuallyDrop<Vec<T, Global>> {
-            ManuallyDrop::new(Vec::from_raw_parts_in(
-                self.ptr.cast(),
-                self.len,
-                self.cap,
-                self.alloc,
-            ))
-        }
+    /// Erases the type of `vec`, using `vtable` for the operations that don't know the element type.
+    ///
+    /// See [TypedVTable] for how to build a vtable.
+    pub fn from_vec_with_vtable<T>(vec: vec_type!(T, A), vtable: &'static TypedVTable<T>) -> Self {
+        unsafe { Self::from_raw(RawVec::from_vec(vec), vtable.erased()) }
     }
 
-    /// # Safety
+    #[cfg(feature = "bytemuck")]
+    /// Erases the type of `vec`, remembering that `T` is plain-old-data.
     ///
-    /// `T` must be the same as in `from_vec`.
-    pub unsafe fn drop_raw_vec<T, A: Allocator>(raw: RawVec<A>) {
-        drop(raw.into_vec::<T>());
+    /// The buffer can then be accessed as bytes with [TypeErasedVec::as_bytes] and [TypeErasedVec::as_bytes_mut].
+    pub fn from_pod_vec<T: bytemuck::Pod + Erasable>(vec: vec_type!(T, A)) -> Self {
+        unsafe { Self::from_raw(RawVec::from_vec(vec), &TypedVTable::<T>::POD.vtable) }
     }
-}
 
-use raw::{drop_raw_vec, RawVec};
+    /// Erases the type of `vec`, remembering how to clone it.
+    ///
+    /// `TypeErasedVec`s constructed this way can be cloned with [Clone::clone].
+    pub fn from_vec_clone<T: Clone + Erasable>(vec: vec_type!(T, A)) -> Self {
+        unsafe { Self::from_raw(RawVec::from_vec(vec), &TypedVTable::<T>::CLONE.vtable) }
+    }
 
-#[derive(Debug)]
-/// A type erased [Vec].
-pub struct TypeErasedVec<A: Allocator = Global> {
-    /// The raw form of the `Vec`. It's only None after [TypeErasedVec::get_mut] and restored to `Some` after [VecMut] destruction.
-    raw: Option<RawVec<A>>,
-    drop: unsafe fn(RawVec<A>),
+    #[cfg(feature = "type-id")]
+    /// Erases the type of `vec`, remembering how to compare it with [TypeErasedVec::eq_erased].
+    pub fn from_vec_eq<T: PartialEq + Erasable>(vec: vec_type!(T, A)) -> Self {
+        unsafe { Self::from_raw(RawVec::from_vec(vec), &TypedVTable::<T>::EQ.vtable) }
+    }
+
+    /// Erases the type of `vec`, remembering how to order it.
+    ///
+    /// `TypeErasedVec`s constructed this way can be sorted with [TypeErasedVec::sort_erased].
+    pub fn from_vec_ord<T: Ord + Erasable>(vec: vec_type!(T, A)) -> Self {
+        unsafe { Self::from_raw(RawVec::from_vec(vec), &TypedVTable::<T>::ORD.vtable) }
+    }
+
+    /// Erases the type of `vec`, remembering how to hash it.
+    ///
+    /// `TypeErasedVec`s constructed this way can be hashed with [Hash::hash] or [TypeErasedVec::hash_erased].
+    pub fn from_vec_hash<T: Hash + Erasable>(vec: vec_type!(T, A)) -> Self {
+        unsafe { Self::from_raw(RawVec::from_vec(vec), &TypedVTable::<T>::HASH.vtable) }
+    }
+
+    /// Erases the type of `vec`, remembering how to format its elements.
+    ///
+    /// The [Debug] output of `TypeErasedVec`s constructed this way includes the elements, up to 32 of them.
+    pub fn from_vec_debug<T: Debug + Erasable>(vec: vec_type!(T, A)) -> Self {
+        unsafe { Self::from_raw(RawVec::from_vec(vec), &TypedVTable::<T>::DEBUG.vtable) }
+    }
+
+    /// Erases the type of `vec`, using the vtable of its [ErasedElement] implementation.
+    pub fn from_element_vec<T: ErasedElement>(vec: vec_type!(T, A)) -> Self {
+        unsafe { Self::from_raw(RawVec::from_vec(vec), &T::VTABLE.vtable) }
+    }
+
+    /// Erases the type of `vec`, remembering its [ScalarType].
+    ///
+    /// `TypeErasedVec`s constructed this way support the same operations as [TypeErasedVec::new_scalar_in].
+    pub fn from_scalar_vec<T: Scalar>(vec: vec_type!(T, A)) -> Self {
+        unsafe { Self::from_raw(RawVec::from_vec(vec), &TypedVTable::<T>::SCALAR.vtable) }
+    }
+
+    #[cfg(feature = "serde")]
+    /// Erases the type of `vec`, remembering how to serialize its elements.
+    ///
+    /// `TypeErasedVec`s constructed this way serialize the same as `vec`.
+    pub fn from_vec_serialize<T: serde::Serialize + Erasable>(vec: vec_type!(T, A)) -> Self {
+        unsafe {
+            Self::from_raw(
+                RawVec::from_vec(vec),
+                &TypedVTable::<T>::SERIALIZE.vtable,
+            )
+        }
+    }
 }
 
 impl<A: Allocator> TypeErasedVec<A> {
     /// Constructs a new, empty `TypeErasedVec`. See [Vec::new_in].
-    pub fn new_in<T>(alloc: A) -> Self {
-        Self::from_vec(Vec::<T, A>::new_in(alloc))
+    pub fn new_in<T: Erasable>(alloc: A) -> Self {
+        Self::with_capacity_in::<T>(0, alloc)
     }
 
     /// Constructs a new, empty `TypeErasedVec` with specified capacity. See [Vec::with_capacity_in].
-    pub fn with_capacity_in<T>(capacity: usize, alloc: A) -> Self {
-        Self::from_vec(Vec::<T, A>::with_capacity_in(capacity, alloc))
+    pub fn with_capacity_in<T: Erasable>(capacity: usize, alloc: A) -> Self {
+        let vtable = &TypedVTable::<T>::NEW.vtable;
+        unsafe {
+            Self::from_raw(
+                RawVec::with_capacity_in(vtable.layout, capacity, alloc),
+                vtable,
+            )
+        }
     }
 
-    /// Erases the type of `vec`.
-    pub fn from_vec<T>(vec: Vec<T, A>) -> Self {
+    /// Constructs a new, empty `TypeErasedVec` whose buffer is aligned to at least `min_align`, even if the element alignment is smaller.
+    ///
+    /// The alignment is kept when the buffer grows or is cloned, but typed mutable access ([TypeErasedVec::get_mut], [TypeErasedVec::with_mut] and
+    /// [TypeErasedVec::into_vec]) moves the elements to a buffer with the element alignment, as a `Vec` can't deallocate an over-aligned buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_align` is not a power of two.
+    #[track_caller]
+    pub fn with_capacity_aligned_in<T: Erasable>(
+        capacity: usize,
+        min_align: usize,
+        alloc: A,
+    ) -> Self {
+        let vtable = &TypedVTable::<T>::NEW.vtable;
+        let layout = vtable
+            .layout
+            .align_to(min_align)
+            .expect("alignment is not a power of two");
+        unsafe { Self::from_raw(RawVec::with_capacity_in(layout, capacity, alloc), vtable) }
+    }
+
+    /// Constructs a new, empty `TypeErasedVec` of the elements `vtable` describes.
+    ///
+    /// Useful with vtables looked up at runtime, e.g. from a [TypeRegistry].
+    pub fn new_from_vtable_in(vtable: &'static ElementVTable, alloc: A) -> Self {
+        unsafe { Self::from_raw(RawVec::with_capacity_in(vtable.layout, 0, alloc), vtable) }
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Replaces the vtable with the one `registry` has for the element type, gaining its capabilities.
+    ///
+    /// Returns `false` and leaves `self` unchanged if the element type is not registered.
+    pub fn use_registered_vtable(&mut self, registry: &TypeRegistry) -> bool {
+        match registry.get(self.vtable.type_id()) {
+            Some(vtable) => {
+                debug_assert_eq!(vtable.layout, self.vtable.layout);
+                self.vtable = vtable;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Constructs a new, empty `TypeErasedVec`, using the vtable of the [ErasedElement] implementation of `T`.
+    pub fn new_element_in<T: ErasedElement>(alloc: A) -> Self {
+        unsafe {
+            Self::from_raw(
+                RawVec::with_capacity_in(Layout::new::<T>(), 0, alloc),
+                &T::VTABLE.vtable,
+            )
+        }
+    }
+
+    /// Constructs a new, empty `TypeErasedVec` of `scalar_type` elements.
+    ///
+    /// `TypeErasedVec`s constructed this way can be cloned, compared, formatted and accessed as bytes.
+    pub fn new_scalar_in(scalar_type: ScalarType, alloc: A) -> Self {
+        let vtable = scalar_type.vtable();
+        unsafe { Self::from_raw(RawVec::with_capacity_in(vtable.layout, 0, alloc), vtable) }
+    }
+
+    /// Returns the scalar type of the elements, or `None` if `self` was not constructed with
+    /// [TypeErasedVec::new_scalar_in] or [TypeErasedVec::from_scalar_vec].
+    pub fn scalar_type(&self) -> Option<ScalarType> {
+        self.vtable.scalar_type
+    }
+
+    #[cfg(feature = "bytemuck")]
+    /// Constructs a `TypeErasedVec` of `len` zeroed elements.
+    ///
+    /// `TypeErasedVec`s constructed this way can be resized with [TypeErasedVec::resize_zeroed].
+    pub fn zeroed_in<T: bytemuck::Zeroable + Erasable>(len: usize, alloc: A) -> Self {
+        let vtable = &TypedVTable::<T>::ZEROABLE.vtable;
+        let mut vec =
+            unsafe { Self::from_raw(RawVec::with_capacity_in(vtable.layout, len, alloc), vtable) };
+        vec.resize_zeroed(len);
+        vec
+    }
+
+    /// # Safety
+    ///
+    /// `vtable` must describe the elements of `raw`.
+    unsafe fn from_raw(raw: RawVec<A>, vtable: &'static ElementVTable) -> Self {
         TypeErasedVec {
-            raw: Some(RawVec::from_vec(vec)),
-            drop: drop_raw_vec::<T, A>,
+            raw: Some(raw),
+            vtable,
+            leaked_at: None,
         }
     }
 
+    #[cfg(feature = "allocator-api")]
+    /// Boxes the allocator as a trait object, so `TypeErasedVec`s with different allocators can be stored together.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn erase_allocator(mut self) -> TypeErasedVec<Box<dyn Allocator + Send>>
+    where
+        A: Send + 'static,
+    {
+        let raw = raw!(take self);
+        let vtable = self.vtable;
+        forget(self);
+        // `Box<dyn Allocator>` forwards to the boxed allocator.
+        let raw =
+            unsafe { raw.map_allocator(|alloc| -> Box<dyn Allocator + Send> { Box::new(alloc) }) };
+        unsafe { TypeErasedVec::from_raw(raw, vtable) }
+    }
+
+    /// Drops the elements and deallocates the buffer, returning the allocator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn into_allocator(mut self) -> A {
+        let raw = raw!(take self);
+        // Deallocates even if an element destructor panics.
+        let raw = DeallocGuard::new(raw, self.vtable.layout);
+        unsafe {
+            (self.vtable.drop)(raw.raw().ptr(), raw.raw().len());
+            raw.into_inner().deallocate(self.vtable.layout)
+        }
+    }
+
+    /// Clones the elements into a new buffer in `alloc`.
+    ///
+    /// Elements are cloned with the stored clone function, or copied if they are plain-old-data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be [Clone] or plain-old-data, or if `self` is leaked.
+    #[track_caller]
+    pub fn clone_to_allocator<B: Allocator>(&self, alloc: B) -> TypeErasedVec<B> {
+        let raw = raw!(self);
+        let layout = self.vtable.layout;
+        if !(self.vtable.clone.is_some() || self.vtable.pod) {
+            missing_capability(self.vtable, "from_vec_clone or from_pod_vec");
+        }
+        let new_raw =
+            RawVec::with_capacity_in(layout.align_to(raw.align()).unwrap(), raw.len(), alloc);
+        let new_raw = DeallocGuard::new(new_raw, layout);
+        unsafe {
+            match self.vtable.clone {
+                Some(clone) => clone(raw.ptr(), new_raw.raw().ptr(), raw.len()),
+                None => std::ptr::copy_nonoverlapping(
+                    raw.ptr(),
+                    new_raw.raw().ptr(),
+                    raw.len() * layout.size(),
+                ),
+            }
+        }
+        let mut new_raw = new_raw.into_inner();
+        unsafe {
+            new_raw.set_len(raw.len());
+            TypeErasedVec::from_raw(new_raw, self.vtable)
+        }
+    }
+
+    /// Returns the vtable of the element type.
+    pub fn vtable(&self) -> &'static ElementVTable {
+        self.vtable
+    }
+
     /// Returns if `self` is leaked.
     pub fn is_leaked(&self) -> bool {
         self.raw.is_none()
     }
 
+    /// Reinitializes `self` to an empty `Vec<T>` in `alloc`, which is the way to reuse a leaked `TypeErasedVec`.
+    ///
+    /// If `self` is not leaked, its elements are dropped.
+    pub fn reset_in<T: Erasable>(&mut self, alloc: A) {
+        *self = Self::new_in::<T>(alloc);
+    }
+
     /// Converts to `Vec<T>`.
     ///
     /// # Safety
     ///
     /// `T` must be the same type used constructing this `TypeErasedVec`.
     ///
-    /// Constructors include `new`, `new_in`, `with_capacity`, `with_capacity_in`, `from_vec`.
+    /// Constructors include `new`, `new_in`, `with_capacity`, `with_capacity_in` and the `from_*` ones.
     ///
     /// # Panics
     ///
     /// Panics if `self` is leaked.
-    pub unsafe fn into_vec<T>(mut self) -> Vec<T, A> {
-        let vec = self.raw.take().unwrap().into_vec();
+    #[track_caller]
+    pub unsafe fn into_vec<T>(mut self) -> vec_type!(T, A) {
+        let mut raw = raw!(take self);
+        raw.unalign(self.vtable.layout);
+        let vec = raw.into_vec();
         forget(self);
         vec
     }
 
-    /// Gets a reference to \[T\].
+    /// Converts to an iterator moving the elements out. See [Vec::into_iter].
     ///
     /// # Safety
     ///
@@ -187,49 +631,125 @@ impl<A: Allocator> TypeErasedVec<A> {
     /// # Panics
     ///
     /// Panics if `self` is leaked.
-    pub unsafe fn get<T>(&self) -> &[T] {
-        self.raw.as_ref().unwrap().as_slice()
+    #[track_caller]
+    pub unsafe fn into_iter<T>(self) -> <vec_type!(T, A) as IntoIterator>::IntoIter {
+        self.into_vec().into_iter()
     }
 
-    /// Gets a smart pointer to `mut Vec<T>`.
+    /// Decomposes into the pointer, length, capacity, buffer layout, vtable and allocator, without dropping or deallocating anything.
     ///
-    /// # Safety
+    /// The buffer layout is the element layout aligned as the buffer, which may be more than the element alignment.
+    /// See [TypeErasedVec::with_capacity_aligned_in]. Unless it's zero sized or the capacity is 0, the buffer is allocated by
+    /// the allocator with an array layout of capacity elements of it. Drop the elements with [ElementVTable::drop_in_place],
+    /// or put the parts back together with [TypeErasedVec::from_raw_parts].
     ///
-    /// See [TypeErasedVec::into_vec].
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let vec = TypeErasedVec::from_vec(vec![String::from("a")]);
+    /// let (ptr, len, cap, layout, vtable, alloc) = vec.into_raw_parts();
+    /// // Stored somewhere else here.
+    /// let vec = unsafe { TypeErasedVec::from_raw_parts(ptr, len, cap, layout, vtable, alloc) };
+    /// assert_eq!(unsafe { vec.get::<String>() }, ["a"]);
+    /// ```
     ///
     /// # Panics
     ///
     /// Panics if `self` is leaked.
-    pub unsafe fn get_mut<T>(&mut self) -> VecMut<T, A> {
-        VecMut::new(self)
+    #[track_caller]
+    pub fn into_raw_parts(mut self) -> (*mut u8, usize, usize, Layout, &'static ElementVTable, A) {
+        let raw = raw!(take self);
+        let vtable = self.vtable;
+        forget(self);
+        let (ptr, len, cap, align, alloc) = raw.into_parts();
+        let layout = vtable.layout.align_to(align).unwrap();
+        (ptr, len, cap, layout, vtable, alloc)
     }
 
-    /// Gets a reference to the underlying allocator.
+    /// Deliberately leaks the buffer and the allocator, returning the bytes of the elements, the element layout and the length.
+    /// See [Vec::leak].
+    ///
+    /// This is unrelated to the leaked state of a `TypeErasedVec`, which is about [VecMut]. The elements are never dropped,
+    /// so this is meant for handing buffers to APIs that keep them for the rest of the program.
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let (bytes, layout, len) = TypeErasedVec::from_scalar_vec(vec![1u16, 2]).leak();
+    /// assert_eq!((bytes.len(), layout.size(), len), (4, 2, 2));
+    /// ```
     ///
     /// # Panics
     ///
-    /// Panics if `self` is leaked.
-    pub fn allocator(&self) -> &A {
-        self.raw.as_ref().unwrap().allocator()
-    }
-}
-
-impl TypeErasedVec<Global> {
-    /// Constructs a new, empty `TypeErasedVec`. See [Vec::new].
-    pub fn new<T>() -> Self {
-        Self::new_in::<T>(Global)
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], or if `self` is leaked.
+    #[track_caller]
+    pub fn leak<'a>(mut self) -> (&'a mut [u8], Layout, usize)
+    where
+        A: 'a,
+    {
+        if !self.vtable.pod {
+            missing_capability(self.vtable, "from_pod_vec");
+        }
+        let raw = raw!(take self);
+        let layout = self.vtable.layout;
+        forget(self);
+        let (ptr, len, _, _, alloc) = raw.into_parts();
+        forget(alloc);
+        let bytes = unsafe { std::slice::from_raw_parts_mut(ptr, len * layout.size()) };
+        (bytes, layout, len)
     }
 
-    /// Constructs a new, empty `TypeErasedVec` with specified capacity. See [Vec::with_capacity].
-    pub fn with_capacity<T>(capacity: usize) -> Self {
-        Self::with_capacity_in::<T>(capacity, Global)
+    /// Deliberately leaks the buffer and the allocator, returning the elements. See [TypeErasedVec::leak] and [Vec::leak].
+    ///
+    /// # Safety
+    ///
+    /// See [TypeErasedVec::into_vec].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn leak_typed<'a, T>(self) -> &'a mut [T]
+    where
+        A: 'a,
+    {
+        self.into_vec().leak()
     }
 
-    /// Gets a smart pointer to `Vec<T>`.
+    /// Reassembles a vec from the parts returned by [TypeErasedVec::into_raw_parts].
     ///
-    /// This is usually not want you want. Check [TypeErasedVec::get] instead.
+    /// # Safety
+    ///
+    /// The parts must come from [TypeErasedVec::into_raw_parts], or describe a buffer the same way:
+    /// `ptr` must be allocated by `alloc` with an array layout of `cap` elements of `layout`, unless either is zero sized,
+    /// and hold `len` initialized elements of the type `vtable` describes.
     ///
-    /// This method is only implemented for `TypeErasedVec<Global>` because we can't get a `Vec` back without giving it a allocator.
+    /// # Panics
+    ///
+    /// Panics if `layout` has a different size than the element layout, or a smaller alignment.
+    #[track_caller]
+    pub unsafe fn from_raw_parts(
+        ptr: *mut u8,
+        len: usize,
+        cap: usize,
+        layout: Layout,
+        vtable: &'static ElementVTable,
+        alloc: A,
+    ) -> Self {
+        assert!(
+            layout.size() == vtable.layout.size() && layout.align() >= vtable.layout.align(),
+            "layout {layout:?} doesn't fit {}",
+            vtable.type_name()
+        );
+        // Zero sized elements always have room, as in `RawVec::with_capacity_in`.
+        let cap = if layout.size() == 0 { usize::MAX } else { cap };
+        Self::from_raw(
+            RawVec::from_parts(ptr, len, cap, layout.align(), alloc),
+            vtable,
+        )
+    }
+
+    /// Gets a reference to \[T\].
     ///
     /// # Safety
     ///
@@ -238,148 +758,3662 @@ impl TypeErasedVec<Global> {
     /// # Panics
     ///
     /// Panics if `self` is leaked.
-    pub unsafe fn get_ref<T>(&self) -> VecRef<T> {
-        VecRef::new(self)
+    #[track_caller]
+    pub unsafe fn get<T>(&self) -> &[T] {
+        raw!(self).as_slice()
     }
-}
 
-impl<A: Allocator> Drop for TypeErasedVec<A> {
-    fn drop(&mut self) {
-        if let Some(raw) = self.raw.take() {
-            let drop = self.drop;
-            unsafe {
-                drop(raw);
-            }
-        }
+    /// Gets a smart pointer to `mut Vec<T>`.
+    ///
+    /// # Safety
+    ///
+    /// See [TypeErasedVec::into_vec].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn get_mut<T>(&mut self) -> VecMut<'_, T, A> {
+        VecMut::new(self)
     }
-}
-
-#[derive(Debug)]
-/// `Deref`s to `Vec<T, Global>`.
-pub struct VecRef<'a, T> {
-    raw: &'a TypeErasedVec<Global>,
-    vec: ManuallyDrop<Vec<T, Global>>,
-}
 
-impl<'a, T> VecRef<'a, T> {
+    /// Calls `f` with a mutable reference to `Vec<T>`.
+    ///
+    /// Unlike [TypeErasedVec::get_mut], `self` can't be left leaked, because the [VecMut] is always dropped, even if `f` panics.
+    ///
     /// # Safety
     ///
-    /// `T` must be what `raw` was constructred with.
-    unsafe fn new(raw: &'a TypeErasedVec<Global>) -> Self {
-        let vec = raw.raw.as_ref().unwrap().as_manually_drop_vec();
-        VecRef { raw, vec }
+    /// See [TypeErasedVec::into_vec].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn with_mut<T, R>(&mut self, f: impl FnOnce(&mut vec_type!(T, A)) -> R) -> R {
+        let mut vec_mut = self.get_mut();
+        f(&mut vec_mut)
     }
-}
-
-impl<'a, T> Deref for VecRef<'a, T> {
-    type Target = Vec<T, Global>;
 
-    fn deref(&self) -> &Vec<T, Global> {
-        &self.vec
+    /// Returns the name of the element type, like `"[f32; 3]"`. See [std::any::type_name].
+    ///
+    /// Meant for diagnostics: the name is not guaranteed to be unique or stable.
+    pub fn element_type_name(&self) -> &'static str {
+        self.vtable.type_name()
     }
-}
 
-impl<'a, T> Clone for VecRef<'a, T> {
-    fn clone(&self) -> Self {
-        unsafe { Self::new(self.raw) }
+    #[cfg(feature = "type-id")]
+    /// Returns if `T` is the type used constructing this `TypeErasedVec`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.vtable.type_id() == TypeId::of::<T>()
     }
-}
 
-#[derive(Debug)]
-/// `DerefMut`s to `Vec<T, A>`.
-pub struct VecMut<'a, T, A: Allocator> {
-    raw: &'a mut TypeErasedVec<A>,
-    vec: Option<ManuallyDrop<Vec<T, A>>>,
-}
+    #[cfg(feature = "type-id")]
+    /// Returns if `self` and `other` have the same element type, e.g. before [TypeErasedVec::append_unchecked].
+    pub fn same_element_type<B: Allocator>(&self, other: &TypeErasedVec<B>) -> bool {
+        self.vtable.type_id() == other.vtable.type_id() && self.vtable.layout == other.vtable.layout
+    }
 
-impl<'a, T, A: Allocator> VecMut<'a, T, A> {
-    /// # Safety
+    #[cfg(feature = "type-id")]
+    /// Gets a reference to \[T\], or `None` if `T` is not the element type.
     ///
-    /// `T` must be what `raw` was constructred with.
-    unsafe fn new(raw: &'a mut TypeErasedVec<A>) -> Self {
-        let vec = Some(ManuallyDrop::new(raw.raw.take().unwrap().into_vec()));
-        VecMut { raw, vec }
+    /// Returns [LeakedError] instead of panicking if `self` is leaked.
+    pub fn try_get<T: 'static>(&self) -> Result<Option<&[T]>, LeakedError> {
+        let raw = self.raw.as_ref().ok_or(LeakedError)?;
+        Ok(self.is::<T>().then(|| unsafe { raw.as_slice() }))
     }
-}
-
-impl<'a, T, A: Allocator> Deref for VecMut<'a, T, A> {
-    type Target = Vec<T, A>;
 
-    fn deref(&self) -> &Vec<T, A> {
-        self.vec.as_ref().unwrap()
+    #[cfg(feature = "type-id")]
+    /// Gets a smart pointer to `mut Vec<T>`, or `None` if `T` is not the element type.
+    ///
+    /// Returns [LeakedError] instead of panicking if `self` is leaked.
+    pub fn try_get_mut<T: 'static>(&mut self) -> Result<Option<VecMut<'_, T, A>>, LeakedError> {
+        if self.is_leaked() {
+            return Err(LeakedError);
+        }
+        Ok(if self.is::<T>() {
+            Some(unsafe { self.get_mut() })
+        } else {
+            None
+        })
     }
-}
 
-impl<'a, T, A: Allocator> DerefMut for VecMut<'a, T, A> {
-    fn deref_mut(&mut self) -> &mut Vec<T, A> {
-        self.vec.as_mut().unwrap()
+    #[cfg(feature = "type-id")]
+    /// Converts to `Vec<T>`, or gives `self` back if `T` is not the element type.
+    ///
+    /// Returns [LeakedError] instead of panicking if `self` is leaked.
+    pub fn try_into_vec<T: 'static>(self) -> Result<Result<vec_type!(T, A), Self>, LeakedError> {
+        if self.is_leaked() {
+            return Err(LeakedError);
+        }
+        Ok(if self.is::<T>() {
+            Ok(unsafe { self.into_vec() })
+        } else {
+            Err(self)
+        })
     }
-}
 
-impl<'a, T, A: Allocator> Drop for VecMut<'a, T, A> {
-    fn drop(&mut self) {
-        let vec = self.vec.take().unwrap();
-        let vec = ManuallyDrop::into_inner(vec);
-        *self.raw = TypeErasedVec::from_vec(vec);
+    #[cfg(feature = "type-id")]
+    /// Gets the elements as `&[T]`, or an error if `T` is not the element type or `self` is leaked.
+    pub fn try_as_slice<T: 'static>(&self) -> Result<&[T], Error> {
+        self.check_type::<T>()?;
+        Ok(unsafe { self.raw.as_ref().ok_or(Error::Leaked)?.as_slice() })
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_new() {
-        let vec = TypeErasedVec::new::<i32>();
-        let vec_ref = unsafe { vec.get_ref::<i32>() };
-        assert_eq!(vec_ref.len(), 0);
-        assert_eq!(vec_ref.capacity(), 0);
+    #[cfg(feature = "type-id")]
+    /// Gets the elements as `&mut [T]`, or an error if `T` is not the element type or `self` is leaked.
+    pub fn try_as_mut_slice<T: 'static>(&mut self) -> Result<&mut [T], Error> {
+        self.check_type::<T>()?;
+        let raw = self.raw.as_mut().ok_or(Error::Leaked)?;
+        Ok(unsafe { std::slice::from_raw_parts_mut(raw.ptr().cast(), raw.len()) })
     }
 
-    #[test]
-    fn test_with_capacity() {
-        let vec = TypeErasedVec::with_capacity::<i32>(42);
-        assert_eq!(unsafe { vec.get_ref::<i32>().capacity() }, 42);
+    #[cfg(feature = "type-id")]
+    fn check_type<T: 'static>(&self) -> Result<(), Error> {
+        if self.is::<T>() {
+            Ok(())
+        } else {
+            Err(Error::TypeMismatch {
+                element: self.vtable.type_name(),
+                requested: std::any::type_name::<T>(),
+            })
+        }
     }
 
-    #[test]
-    fn test_from_vec() {
-        let origin: Vec<i32> = (0..10).collect();
+    #[cfg(feature = "type-id")]
+    /// Compares the elements of `self` and `other` with [PartialEq].
+    ///
+    /// Returns `false` if the element types differ.
+    ///
+    /// # Panics
+    ///
+    /// Panics if neither `self` nor `other` was constructed with [TypeErasedVec::from_vec_eq], or if either is leaked.
+    #[track_caller]
+    pub fn eq_erased<B: Allocator>(&self, other: &TypeErasedVec<B>) -> bool {
+        let raw = raw!(self);
+        let other_raw = raw!(other);
+        if self.vtable.type_id() != other.vtable.type_id() {
+            return false;
+        }
+        let Some(eq) = self.vtable.eq.or(other.vtable.eq) else {
+            missing_capability(self.vtable, "from_vec_eq");
+        };
+        raw.len() == other_raw.len() && unsafe { eq(raw.ptr(), other_raw.ptr(), raw.len()) }
+    }
+
+    /// Feeds the elements into `state`, the same way as hashing \[T\].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_vec_hash] or is leaked.
+    #[track_caller]
+    pub fn hash_erased(&self, state: &mut dyn Hasher) {
+        let raw = raw!(self);
+        let Some(hash) = self.vtable.hash else {
+            missing_capability(self.vtable, "from_vec_hash");
+        };
+        unsafe { hash(raw.ptr(), raw.len(), state) }
+    }
+
+    /// Gets a reference to the underlying allocator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn allocator(&self) -> &A {
+        raw!(self).allocator()
+    }
+
+    /// Returns the size and alignment of the element type.
+    pub fn element_layout(&self) -> Layout {
+        self.vtable.layout
+    }
+
+    /// Returns the alignment the buffer is guaranteed to have, which is at least the element alignment. See [TypeErasedVec::with_capacity_aligned_in].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn buffer_align(&self) -> usize {
+        raw!(self).align()
+    }
+
+    /// Returns the actual alignment of the buffer pointer, the largest power of two its address is a multiple of.
+    ///
+    /// This may be more than [TypeErasedVec::buffer_align], but is only valid until the buffer is reallocated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn ptr_align(&self) -> usize {
+        let addr = raw!(self).ptr().addr();
+        1 << addr.trailing_zeros()
+    }
+
+    /// Returns if the buffer pointer is currently aligned to `align`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two, or if `self` is leaked.
+    #[track_caller]
+    pub fn is_aligned_to(&self, align: usize) -> bool {
+        assert!(align.is_power_of_two(), "alignment is not a power of two");
+        self.ptr_align() >= align
+    }
+
+    /// Returns if the element type is known to be plain-old-data, see [TypeErasedVec::from_pod_vec].
+    pub fn is_pod(&self) -> bool {
+        self.vtable.pod
+    }
+
+    /// Returns if `self` can be cloned, see [TypeErasedVec::from_vec_clone] and [TypeErasedVec::from_pod_vec].
+    pub fn is_clone(&self) -> bool {
+        self.vtable.is_clone() || self.vtable.pod
+    }
+
+    /// Gets the elements as bytes, or `None` if the element type is not known to be plain-old-data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        let raw = raw!(self);
+        if self.vtable.pod {
+            Some(unsafe { raw.as_bytes(self.vtable.layout.size()) })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the elements as bytes, or an error if the element type is not known to be plain-old-data or `self` is leaked.
+    pub fn try_as_bytes(&self) -> Result<&[u8], Error> {
+        let raw = self.raw.as_ref().ok_or(Error::Leaked)?;
+        if self.vtable.pod {
+            Ok(unsafe { raw.as_bytes(self.vtable.layout.size()) })
+        } else {
+            Err(Error::NotPod)
+        }
+    }
+
+    /// Gets the elements as mutable bytes, or an error if the element type is not known to be plain-old-data or `self` is leaked.
+    pub fn try_as_bytes_mut(&mut self) -> Result<&mut [u8], Error> {
+        let raw = self.raw.as_mut().ok_or(Error::Leaked)?;
+        if self.vtable.pod {
+            Ok(unsafe { raw.as_bytes_mut(self.vtable.layout.size()) })
+        } else {
+            Err(Error::NotPod)
+        }
+    }
+
+    #[cfg(feature = "bytemuck")]
+    /// Reinterprets the elements as `&[T]`, e.g. a buffer of `[f32; 3]` as `f32`s.
+    ///
+    /// Fails if the element type is not known to be plain-old-data, if the bytes are not a whole number of `T`s
+    /// or not aligned for `T`, or if `self` is leaked.
+    pub fn try_cast_slice<T: bytemuck::Pod>(&self) -> Result<&[T], Error> {
+        let bytes = self.try_as_bytes()?;
+        bytemuck::try_cast_slice(bytes).map_err(|e| cast_error::<T>(e, bytes.len()))
+    }
+
+    #[cfg(feature = "bytemuck")]
+    /// Reinterprets the elements as `&mut [T]`. See [TypeErasedVec::try_cast_slice].
+    pub fn try_cast_slice_mut<T: bytemuck::Pod>(&mut self) -> Result<&mut [T], Error> {
+        let bytes = self.try_as_bytes_mut()?;
+        let len = bytes.len();
+        bytemuck::try_cast_slice_mut(bytes).map_err(|e| cast_error::<T>(e, len))
+    }
+
+    /// Iterates over the byte representation of each element, or returns `None` if the element type is not known to be plain-old-data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn iter_element_bytes(&self) -> Option<ElementBytes<'_>> {
+        let bytes = self.as_bytes()?;
+        Some(ElementBytes::new(
+            bytes,
+            self.vtable.layout.size(),
+            self.len(),
+        ))
+    }
+
+    /// Gets the elements as mutable bytes, or `None` if the element type is not known to be plain-old-data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn as_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        let raw = raw!(mut self);
+        if self.vtable.pod {
+            Some(unsafe { raw.as_bytes_mut(self.vtable.layout.size()) })
+        } else {
+            None
+        }
+    }
+
+    /// Reverses the byte order of every `scalar_width` byte scalar, converting between little and big endian.
+    ///
+    /// Elements made of several scalars, like `[f32; 3]`, are fixed up one scalar at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], if `scalar_width` is 0
+    /// or doesn't divide the element size, or if `self` is leaked.
+    #[track_caller]
+    pub fn swap_bytes_in_place(&mut self, scalar_width: usize) {
+        assert!(
+            scalar_width != 0 && self.vtable.layout.size().is_multiple_of(scalar_width),
+            "scalar width does not divide element size"
+        );
+        let Some(bytes) = self.as_bytes_mut() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        kernels::swap_bytes(bytes, scalar_width);
+    }
+
+    /// Sets every element to the one `element` holds the bytes of, e.g. to clear an image to a color.
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let mut vec = TypeErasedVec::from_scalar_vec(vec![0u16; 3]);
+    /// vec.fill_bytes(&7u16.to_ne_bytes());
+    /// assert_eq!(unsafe { vec.get::<u16>() }, [7, 7, 7]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], if the length of `element` is not
+    /// the element size, or if `self` is leaked.
+    #[track_caller]
+    pub fn fill_bytes(&mut self, element: &[u8]) {
+        let size = self.vtable.layout.size();
+        assert_eq!(element.len(), size, "byte length is not the element size");
+        let Some(bytes) = self.as_bytes_mut() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        if size != 0 {
+            kernels::fill_pattern(bytes, element);
+        }
+    }
+
+    /// Returns the spare capacity as uninitialized bytes. See [Vec::spare_capacity_mut].
+    ///
+    /// Write whole elements into it, then commit them with [TypeErasedVec::set_len_erased].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn spare_capacity_bytes_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        raw!(mut self).spare_capacity_bytes_mut(self.vtable.layout.size())
+    }
+
+    /// Sets the number of elements. See [Vec::set_len].
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must not exceed [TypeErasedVec::capacity], and the elements in `old_len..new_len` must be initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn set_len_erased(&mut self, new_len: usize) {
+        raw!(mut self).set_len(new_len);
+    }
+
+    /// Appends one element from its byte representation. See [Vec::push].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], if `bytes` is not exactly one element long, or if `self` is leaked.
+    #[track_caller]
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        assert_eq!(
+            bytes.len(),
+            self.vtable.layout.size(),
+            "byte length is not element size"
+        );
+        self.append_bytes(bytes, 1);
+    }
+
+    /// Appends elements from their byte representation, copying them all at once. See [Vec::extend_from_slice].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], if the length of `bytes` is not a multiple of the element size, or if `self` is leaked.
+    #[track_caller]
+    pub fn extend_from_byte_slice(&mut self, bytes: &[u8]) {
+        let count = match self.vtable.layout.size() {
+            0 => 0,
+            size => {
+                assert_eq!(
+                    bytes.len() % size,
+                    0,
+                    "byte length is not a multiple of element size"
+                );
+                bytes.len() / size
+            }
+        };
+        assert!(
+            count != 0 || bytes.is_empty(),
+            "byte length is not a multiple of element size"
+        );
+        self.append_bytes(bytes, count);
+    }
+
+    /// Appends elements from their byte representation. See [TypeErasedVec::extend_from_byte_slice].
+    ///
+    /// Fails instead of panicking if the element type is not known to be plain-old-data, if the length of `bytes`
+    /// is not a multiple of the element size, if the allocation fails, or if `self` is leaked.
+    pub fn try_extend_from_byte_slice(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let layout = self.vtable.layout;
+        let raw = self.raw.as_mut().ok_or(Error::Leaked)?;
+        if !self.vtable.pod {
+            return Err(Error::NotPod);
+        }
+        let count = element_count(bytes.len(), layout.size())?;
+        raw.try_reserve(layout, count)?;
+        self.append_bytes(bytes, count);
+        Ok(())
+    }
+
+    /// Appends `count` elements whose bytes are `bytes`.
+    #[track_caller]
+    fn append_bytes(&mut self, bytes: &[u8], count: usize) {
+        let layout = self.vtable.layout;
+        debug_assert_eq!(bytes.len(), count * layout.size());
+        let raw = self.pod_raw_mut();
+        raw.reserve(layout, count);
+        unsafe {
+            let len = raw.len();
+            let end = raw.ptr().add(len * layout.size());
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), end, bytes.len());
+            raw.set_len(len + count);
+        }
+    }
+
+    /// Writes the byte representation of all elements to `w`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], or if `self` is leaked.
+    #[track_caller]
+    pub fn write_to<W: Write>(&self, mut w: W) -> io::Result<()> {
+        let Some(bytes) = self.as_bytes() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        w.write_all(bytes)
+    }
+
+    /// Reads up to `max_elements` elements from `r` straight into spare capacity, returning how many were appended.
+    ///
+    /// Reading stops at end of file or once `max_elements` elements are read. Elements read before an error are kept,
+    /// and a trailing partial element is reported as [io::ErrorKind::UnexpectedEof]. Zero sized elements are never read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], or if `self` is leaked.
+    #[track_caller]
+    pub fn fill_from_reader<R: Read>(
+        &mut self,
+        mut r: R,
+        max_elements: usize,
+    ) -> io::Result<usize> {
+        let size = self.vtable.layout.size();
+        let layout = self.vtable.layout;
+        let raw = self.pod_raw_mut();
+        if size == 0 {
+            return Ok(0);
+        }
+        raw.reserve(layout, max_elements);
+        let len = raw.len();
+        // `Read` takes initialized bytes, so zero the target first.
+        let buf = unsafe {
+            let start = raw.ptr().add(len * size);
+            start.write_bytes(0, max_elements * size);
+            std::slice::from_raw_parts_mut(start, max_elements * size)
+        };
+        let mut filled = 0;
+        let result = loop {
+            if filled == buf.len() {
+                break Ok(());
+            }
+            match r.read(&mut buf[filled..]) {
+                Ok(0) if filled % size == 0 => break Ok(()),
+                Ok(0) => break Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => break Err(e),
+            }
+        };
+        let count = filled / size;
+        unsafe { raw.set_len(len + count) };
+        result.map(|()| count)
+    }
+
+    /// Inserts one element from its byte representation at `index`, shifting all elements after it to the right. See [Vec::insert].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], if `bytes` is not exactly one element long, if `index > len`, or if `self` is leaked.
+    #[track_caller]
+    pub fn insert_bytes(&mut self, index: usize, bytes: &[u8]) {
+        let size = self.vtable.layout.size();
+        assert_eq!(bytes.len(), size, "byte length is not element size");
+        let layout = self.vtable.layout;
+        let raw = self.pod_raw_mut();
+        let len = raw.len();
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+        raw.reserve(layout, 1);
+        unsafe {
+            let at = raw.ptr().add(index * size);
+            std::ptr::copy(at, at.add(size), (len - index) * size);
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), at, size);
+            raw.set_len(len + 1);
+        }
+    }
+
+    /// Removes the element at `index`, writing its byte representation to `out` and shifting all elements after it to the left. See [Vec::remove].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], if `out` is not exactly one element long, if `index >= len`, or if `self` is leaked.
+    #[track_caller]
+    pub fn remove_to(&mut self, index: usize, out: &mut [u8]) {
+        let size = self.vtable.layout.size();
+        assert_eq!(out.len(), size, "byte length is not element size");
+        let raw = self.pod_raw_mut();
+        let len = raw.len();
+        assert!(
+            index < len,
+            "removal index (is {index}) should be < len (is {len})"
+        );
+        unsafe {
+            let at = raw.ptr().add(index * size);
+            std::ptr::copy_nonoverlapping(at, out.as_mut_ptr(), size);
+            std::ptr::copy(at.add(size), at, (len - index - 1) * size);
+            raw.set_len(len - 1);
+        }
+    }
+
+    /// Gets the buffer for byte level modification.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_pod_vec], or if `self` is leaked.
+    #[track_caller]
+    fn pod_raw_mut(&mut self) -> &mut RawVec<A> {
+        let raw = raw!(mut self);
+        if !self.vtable.pod {
+            missing_capability(self.vtable, "from_pod_vec");
+        }
+        raw
+    }
+
+    /// Gets a handle to the element at `index`, or `None` if out of bounds. See [slice::get].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn element(&self, index: usize) -> Option<ErasedRef<'_>> {
+        let raw = raw!(self);
+        if index < raw.len() {
+            let ptr = unsafe { raw.ptr().add(index * self.vtable.layout.size()) };
+            Some(unsafe { ErasedRef::new(ptr, self.vtable) })
+        } else {
+            None
+        }
+    }
+
+    /// Borrows the elements as a [TypeErasedSlice].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn as_erased_slice(&self) -> TypeErasedSlice<'_> {
+        let raw = raw!(self);
+        unsafe { TypeErasedSlice::from_raw_parts(raw.ptr(), raw.len(), self.vtable) }
+    }
+
+    /// Borrows the elements as a [TypeErasedSliceMut].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn as_erased_slice_mut(&mut self) -> TypeErasedSliceMut<'_> {
+        let raw = raw!(mut self);
+        unsafe { TypeErasedSliceMut::from_raw_parts(raw.ptr(), raw.len(), self.vtable) }
+    }
+
+    /// Gets a mutable handle to the element at `index`, or `None` if out of bounds. See [slice::get_mut].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn element_mut(&mut self, index: usize) -> Option<ErasedMut<'_>> {
+        let raw = raw!(mut self);
+        if index < raw.len() {
+            let ptr = unsafe { raw.ptr().add(index * self.vtable.layout.size()) };
+            Some(unsafe { ErasedMut::new(ptr, self.vtable) })
+        } else {
+            None
+        }
+    }
+
+    /// Resizes to `new_len` elements, filling new ones with zero bytes or dropping the excess. See [Vec::resize].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::zeroed_in] or [TypeErasedVec::from_pod_vec], or if `self` is leaked.
+    #[track_caller]
+    pub fn resize_zeroed(&mut self, new_len: usize) {
+        if !self.vtable.zeroable {
+            missing_capability(self.vtable, "zeroed or from_pod_vec");
+        }
+        let len = self.len();
+        if new_len <= len {
+            self.truncate(new_len);
+            return;
+        }
+        let raw = raw!(mut self);
+        let size = self.vtable.layout.size();
+        raw.reserve(self.vtable.layout, new_len - len);
+        unsafe {
+            raw.ptr()
+                .add(len * size)
+                .write_bytes(0, (new_len - len) * size);
+            raw.set_len(new_len);
+        }
+    }
+
+    /// Returns the number of elements. See [Vec::len].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn len(&self) -> usize {
+        raw!(self).len()
+    }
+
+    /// Returns if there's no element. See [Vec::is_empty].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements `self` can hold without reallocating. See [Vec::capacity].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn capacity(&self) -> usize {
+        raw!(self).capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more elements. See [Vec::reserve].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows, or if `self` is leaked.
+    #[track_caller]
+    pub fn reserve(&mut self, additional: usize) {
+        raw!(mut self).reserve(self.vtable.layout, additional);
+    }
+
+    /// Reserves capacity for exactly `additional` more elements. See [Vec::reserve_exact].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows, or if `self` is leaked.
+    #[track_caller]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        raw!(mut self).reserve_exact(self.vtable.layout, additional);
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements, returning an error instead of panicking or aborting. See [Vec::try_reserve].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        raw!(mut self).try_reserve(self.vtable.layout, additional)
+    }
+
+    /// Tries to reserve capacity for exactly `additional` more elements, returning an error instead of panicking or aborting. See [Vec::try_reserve_exact].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        raw!(mut self).try_reserve_exact(self.vtable.layout, additional)
+    }
+
+    /// Shrinks the capacity to the length. See [Vec::shrink_to_fit].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn shrink_to_fit(&mut self) {
+        raw!(mut self).shrink_to_fit(self.vtable.layout);
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the rest. See [Vec::truncate].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn truncate(&mut self, len: usize) {
+        let raw = raw!(mut self);
+        let old_len = raw.len();
+        if len >= old_len {
+            return;
+        }
+        unsafe {
+            // Set length first so a panicking destructor can't cause double drop.
+            raw.set_len(len);
+            let tail = raw.ptr().add(len * self.vtable.layout.size());
+            (self.vtable.drop)(tail, old_len - len);
+        }
+    }
+
+    /// Drops all elements. See [Vec::clear].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Drops the element at `index`, moving the last element into its place. See [Vec::swap_remove].
+    ///
+    /// This is O(1) and works for any element type, as it only needs the element layout and destructor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`, or if `self` is leaked.
+    #[track_caller]
+    pub fn swap_remove_erased(&mut self, index: usize) {
+        let raw = raw!(mut self);
+        let len = raw.len();
+        assert!(
+            index < len,
+            "swap_remove index (is {index}) should be < len (is {len})"
+        );
+        let size = self.vtable.layout.size();
+        unsafe {
+            let last = raw.ptr().add((len - 1) * size);
+            if index != len - 1 {
+                std::ptr::swap_nonoverlapping(raw.ptr().add(index * size), last, size);
+            }
+            // Set length first so a panicking destructor can't cause double drop.
+            raw.set_len(len - 1);
+            (self.vtable.drop)(last, 1);
+        }
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Appends a type erased value. See [Vec::push].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type of `value` is not the element type, or if `self` is leaked.
+    #[track_caller]
+    pub fn push_erased(&mut self, value: TypeErasedBox) {
+        assert_same_element_type(self.vtable, value.vtable());
+        unsafe { self.push_erased_unchecked(value) }
+    }
+
+    /// Appends a type erased value without checking its type. See [Vec::push].
+    ///
+    /// # Safety
+    ///
+    /// The type of `value` must be the element type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn push_erased_unchecked(&mut self, value: TypeErasedBox) {
+        let raw = raw!(mut self);
+        let layout = self.vtable.layout;
+        raw.reserve(layout, 1);
+        let value = value.into_raw();
+        let len = raw.len();
+        std::ptr::copy_nonoverlapping(
+            value.ptr(),
+            raw.ptr().add(len * layout.size()),
+            layout.size(),
+        );
+        raw.set_len(len + 1);
+        value.deallocate(layout);
+    }
+
+    /// Removes the last element and returns it as a [TypeErasedBox], or `None` if there's none. See [Vec::pop].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn pop_erased(&mut self) -> Option<TypeErasedBox> {
+        let raw = raw!(mut self);
+        let len = raw.len();
+        if len == 0 {
+            return None;
+        }
+        let layout = self.vtable.layout;
+        let mut value = RawVec::with_capacity_in(layout, 1, Global);
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                raw.ptr().add((len - 1) * layout.size()),
+                value.ptr(),
+                layout.size(),
+            );
+            raw.set_len(len - 1);
+            value.set_len(1);
+            Some(TypeErasedBox::from_raw(value, self.vtable))
+        }
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Removes the element at `index` and appends it to `dst`, moving the last element of `self` into its place. See [Vec::swap_remove].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` has a different element type, if `index >= len`, or if either is leaked.
+    #[track_caller]
+    pub fn move_element_to<B: Allocator>(&mut self, index: usize, dst: &mut TypeErasedVec<B>) {
+        assert_same_element_type(self.vtable, dst.vtable);
+        unsafe { self.move_element_to_unchecked(index, dst) }
+    }
+
+    /// Removes the element at `index` and appends it to `dst` without checking the element types. See [TypeErasedVec::move_element_to].
+    ///
+    /// # Safety
+    ///
+    /// `dst` must have the same element type as `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`, or if either is leaked.
+    #[track_caller]
+    pub unsafe fn move_element_to_unchecked<B: Allocator>(
+        &mut self,
+        index: usize,
+        dst: &mut TypeErasedVec<B>,
+    ) {
+        let src = raw!(mut self);
+        let len = src.len();
+        assert!(
+            index < len,
+            "swap_remove index (is {index}) should be < len (is {len})"
+        );
+        let dst = raw!(mut dst);
+        let layout = self.vtable.layout;
+        let size = layout.size();
+        dst.reserve(layout, 1);
+        let dst_len = dst.len();
+        let element = src.ptr().add(index * size);
+        std::ptr::copy_nonoverlapping(element, dst.ptr().add(dst_len * size), size);
+        dst.set_len(dst_len + 1);
+        std::ptr::copy(src.ptr().add((len - 1) * size), element, size);
+        src.set_len(len - 1);
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Moves all elements of `other` into `self`, leaving `other` empty. See [Vec::append].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` has a different element type, or if either is leaked.
+    #[track_caller]
+    pub fn append<B: Allocator>(&mut self, other: &mut TypeErasedVec<B>) {
+        assert_same_element_type(self.vtable, other.vtable);
+        unsafe { self.append_unchecked(other) }
+    }
+
+    /// Moves all elements of `other` into `self` without checking the element types. See [TypeErasedVec::append].
+    ///
+    /// # Safety
+    ///
+    /// `other` must have the same element type as `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either is leaked.
+    #[track_caller]
+    pub unsafe fn append_unchecked<B: Allocator>(&mut self, other: &mut TypeErasedVec<B>) {
+        let dst = raw!(mut self);
+        let src = raw!(mut other);
+        let layout = self.vtable.layout;
+        let count = src.len();
+        dst.reserve(layout, count);
+        let len = dst.len();
+        std::ptr::copy_nonoverlapping(
+            src.ptr(),
+            dst.ptr().add(len * layout.size()),
+            count * layout.size(),
+        );
+        src.set_len(0);
+        dst.set_len(len + count);
+    }
+
+    /// Drops the elements in `range`, shifting the elements after it down. See [Vec::drain].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds, or if `self` is leaked.
+    #[track_caller]
+    pub fn drain_erased(&mut self, range: impl RangeBounds<usize>) {
+        /// Moves the tail down even if an element destructor panics.
+        struct TailGuard<'a, A: Allocator> {
+            raw: &'a mut RawVec<A>,
+            range: Range<usize>,
+            len: usize,
+            size: usize,
+        }
+
+        impl<A: Allocator> Drop for TailGuard<'_, A> {
+            fn drop(&mut self) {
+                let Range { start, end } = self.range;
+                unsafe {
+                    let ptr = self.raw.ptr();
+                    std::ptr::copy(
+                        ptr.add(end * self.size),
+                        ptr.add(start * self.size),
+                        (self.len - end) * self.size,
+                    );
+                    self.raw.set_len(self.len - (end - start));
+                }
+            }
+        }
+
+        let raw = raw!(mut self);
+        let len = raw.len();
+        let range = resolve_range(range, len);
+        let size = self.vtable.layout.size();
+        unsafe {
+            // Set length first so a panicking destructor can't cause double drop.
+            raw.set_len(range.start);
+            let guard = TailGuard {
+                raw,
+                range,
+                len,
+                size,
+            };
+            (self.vtable.drop)(
+                guard.raw.ptr().add(guard.range.start * size),
+                guard.range.len(),
+            );
+        }
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, dropping the rest. See [Vec::retain].
+    ///
+    /// `f` sees each element as an [ErasedRef], which it can downcast to check the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn retain_erased(&mut self, mut f: impl FnMut(ErasedRef<'_>) -> bool) {
+        let vtable = self.vtable;
+        unsafe { self.retain_raw(|current, _| f(ErasedRef::new(current, vtable))) }
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Removes consecutive equal elements. See [Vec::dedup].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_vec_eq], or if `self` is leaked.
+    #[track_caller]
+    pub fn dedup_erased(&mut self) {
+        let Some(eq) = self.vtable.eq else {
+            missing_capability(self.vtable, "from_vec_eq");
+        };
+        self.dedup_erased_by(|a, b| unsafe { eq(a.as_ptr(), b.as_ptr(), 1) });
+    }
+
+    /// Removes consecutive elements that `same_bucket` considers equal, keeping the first one. See [Vec::dedup_by].
+    ///
+    /// `same_bucket` is passed the current element and the last kept element, in this order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn dedup_erased_by(
+        &mut self,
+        mut same_bucket: impl FnMut(ErasedRef<'_>, ErasedRef<'_>) -> bool,
+    ) {
+        let vtable = self.vtable;
+        unsafe {
+            self.retain_raw(|current, kept| match kept {
+                Some(kept) => !same_bucket(
+                    ErasedRef::new(current, vtable),
+                    ErasedRef::new(kept, vtable),
+                ),
+                None => true,
+            })
+        }
+    }
+
+    /// Keeps only the elements for which `keep` returns `true`, dropping the rest.
+    ///
+    /// `keep` is passed the current element and the last kept element, if any.
+    ///
+    /// # Safety
+    ///
+    /// `keep` must only read through the pointers.
+    #[track_caller]
+    unsafe fn retain_raw(&mut self, mut keep: impl FnMut(*const u8, Option<*const u8>) -> bool) {
+        /// Closes the gap of removed elements even if `keep` or an element destructor panics.
+        struct Guard<'a, A: Allocator> {
+            raw: &'a mut RawVec<A>,
+            processed: usize,
+            deleted: usize,
+            len: usize,
+            size: usize,
+        }
+
+        impl<A: Allocator> Drop for Guard<'_, A> {
+            fn drop(&mut self) {
+                unsafe {
+                    let ptr = self.raw.ptr();
+                    if self.deleted > 0 {
+                        std::ptr::copy(
+                            ptr.add(self.processed * self.size),
+                            ptr.add((self.processed - self.deleted) * self.size),
+                            (self.len - self.processed) * self.size,
+                        );
+                    }
+                    self.raw.set_len(self.len - self.deleted);
+                }
+            }
+        }
+
+        let raw = raw!(mut self);
+        let len = raw.len();
+        let size = self.vtable.layout.size();
+        {
+            // Hide all elements while the buffer has a gap.
+            raw.set_len(0);
+            let mut guard = Guard {
+                raw,
+                processed: 0,
+                deleted: 0,
+                len,
+                size,
+            };
+            while guard.processed < len {
+                let ptr = guard.raw.ptr();
+                let current = ptr.add(guard.processed * size);
+                let kept = guard.processed - guard.deleted;
+                let kept = (kept > 0).then(|| ptr.add((kept - 1) * size).cast_const());
+                let keep = keep(current, kept);
+                guard.processed += 1;
+                if !keep {
+                    guard.deleted += 1;
+                    (self.vtable.drop)(current, 1);
+                } else if guard.deleted > 0 {
+                    let hole = ptr.add((guard.processed - 1 - guard.deleted) * size);
+                    std::ptr::copy_nonoverlapping(current, hole, size);
+                }
+            }
+        }
+    }
+
+    /// Sorts the elements, keeping the order of equal elements. See [slice::sort].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_vec_ord], or if `self` is leaked.
+    #[track_caller]
+    pub fn sort_erased(&mut self) {
+        let Some(cmp) = self.vtable.cmp else {
+            missing_capability(self.vtable, "from_vec_ord");
+        };
+        self.sort_erased_by(|a, b| unsafe { cmp(a.as_ptr(), b.as_ptr()) });
+    }
+
+    /// Sorts the elements with a comparator, keeping the order of equal elements. See [slice::sort_by].
+    ///
+    /// `compare` sees the elements as [ErasedRef]s, which it can downcast to compare the values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn sort_erased_by(
+        &mut self,
+        mut compare: impl FnMut(ErasedRef<'_>, ErasedRef<'_>) -> Ordering,
+    ) {
+        let raw = raw!(mut self);
+        let len = raw.len();
+        let size = self.vtable.layout.size();
+        let ptr = raw.ptr();
+        let mut indices = (0..len).collect::<Vec<_>>();
+        indices.sort_by(|&a, &b| unsafe {
+            compare(
+                ErasedRef::new(ptr.add(a * size), self.vtable),
+                ErasedRef::new(ptr.add(b * size), self.vtable),
+            )
+        });
+        // Elements are only moved after `compare` can no longer panic.
+        let mut sorted = Vec::<MaybeUninit<u8>>::with_capacity(len * size);
+        unsafe {
+            let dst = sorted.as_mut_ptr().cast::<u8>();
+            for (i, &index) in indices.iter().enumerate() {
+                std::ptr::copy_nonoverlapping(ptr.add(index * size), dst.add(i * size), size);
+            }
+            std::ptr::copy_nonoverlapping(dst, ptr, len * size);
+        }
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Binary searches the sorted elements for `key`. See [slice::binary_search].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_vec_ord], if `key` has a different type, or if `self` is leaked.
+    #[track_caller]
+    pub fn binary_search_erased(&self, key: ErasedRef<'_>) -> Result<usize, usize> {
+        let Some(cmp) = self.vtable.cmp else {
+            missing_capability(self.vtable, "from_vec_ord");
+        };
+        assert_same_element_type(self.vtable, key.vtable());
+        self.binary_search_erased_by(|element| unsafe { cmp(element.as_ptr(), key.as_ptr()) })
+    }
+
+    /// Binary searches the sorted elements with a comparator. See [slice::binary_search_by].
+    ///
+    /// `f` returns how an element is ordered relative to the target.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn binary_search_erased_by(
+        &self,
+        mut f: impl FnMut(ErasedRef<'_>) -> Ordering,
+    ) -> Result<usize, usize> {
+        let raw = raw!(self);
+        let size = self.vtable.layout.size();
+        let (mut left, mut right) = (0, raw.len());
+        while left < right {
+            let mid = left + (right - left) / 2;
+            let element = unsafe { ErasedRef::new(raw.ptr().add(mid * size), self.vtable) };
+            match f(element) {
+                Ordering::Less => left = mid + 1,
+                Ordering::Greater => right = mid,
+                Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(left)
+    }
+
+    /// Drops the last element, returning `false` if there was none.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn pop_and_drop(&mut self) -> bool {
+        match self.len() {
+            0 => false,
+            len => {
+                self.truncate(len - 1);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(feature = "allocator-api")]
+/// Constructors for borrowed allocators, such as per-frame bump allocators.
+impl<'a, B: Allocator + ?Sized> TypeErasedVec<&'a B> {
+    /// Constructs a new, empty `TypeErasedVec` in a borrowed allocator. See [TypeErasedVec::new_in].
+    pub fn new_in_ref<T: Erasable>(alloc: &'a B) -> Self {
+        Self::new_in::<T>(alloc)
+    }
+
+    /// Constructs a new, empty `TypeErasedVec` with specified capacity in a borrowed allocator. See [TypeErasedVec::with_capacity_in].
+    pub fn with_capacity_in_ref<T: Erasable>(capacity: usize, alloc: &'a B) -> Self {
+        Self::with_capacity_in::<T>(capacity, alloc)
+    }
+
+    /// Shortens the allocator borrow, so vecs from allocators of different lifetimes can be stored together.
+    ///
+    /// `TypeErasedVec` is covariant in `A`, so this is the same as a coercion. It's spelled out for generic code where inference needs help.
+    pub fn shorten_allocator_lifetime<'b>(self) -> TypeErasedVec<&'b B>
+    where
+        'a: 'b,
+    {
+        self
+    }
+}
+
+impl<A: Allocator + Clone> TypeErasedVec<A> {
+    /// Gets a smart pointer to `Vec<T, A>`.
+    ///
+    /// This is usually not want you want. Check [TypeErasedVec::get] instead.
+    ///
+    /// This method requires `A: Clone` because we can't get a `Vec` back without giving it a allocator.
+    ///
+    /// # Safety
+    ///
+    /// See [TypeErasedVec::into_vec].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn get_ref<T>(&self) -> VecRef<'_, T, A> {
+        VecRef::new(self)
+    }
+
+    /// Splits into two at `at`, returning the elements `[at, len)` in a new `TypeErasedVec` of the same type. See [Vec::split_off].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`, or if `self` is leaked.
+    #[track_caller]
+    pub fn split_off_erased(&mut self, at: usize) -> Self {
+        let raw = raw!(mut self);
+        let len = raw.len();
+        assert!(
+            at <= len,
+            "`at` split index (is {at}) should be <= len (is {len})"
+        );
+        let layout = self.vtable.layout;
+        let mut other = RawVec::with_capacity_in(
+            layout.align_to(raw.align()).unwrap(),
+            len - at,
+            raw.allocator().clone(),
+        );
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                raw.ptr().add(at * layout.size()),
+                other.ptr(),
+                (len - at) * layout.size(),
+            );
+            raw.set_len(at);
+            other.set_len(len - at);
+            Self::from_raw(other, self.vtable)
+        }
+    }
+}
+
+/// Converts `range` to a `Range`, the same way as slice indexing.
+///
+/// # Panics
+///
+/// Panics if `range` is out of bounds of `len` elements.
+#[track_caller]
+fn resolve_range(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).expect("range start overflow"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).expect("range end overflow"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end,
+        "range start (is {start}) should be <= end (is {end})"
+    );
+    assert!(
+        end <= len,
+        "range end (is {end}) should be <= len (is {len})"
+    );
+    start..end
+}
+
+/// Returns the number of elements of `element_size` bytes in `len` bytes, or an error if it's not a whole number.
+fn element_count(len: usize, element_size: usize) -> Result<usize, Error> {
+    match element_size {
+        0 if len == 0 => Ok(0),
+        size if size != 0 && len.is_multiple_of(size) => Ok(len / size),
+        _ => Err(Error::LengthNotMultipleOfElement { len, element_size }),
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+/// Converts a failed cast of `len` bytes to `T`.
+fn cast_error<T>(e: bytemuck::PodCastError, len: usize) -> Error {
+    match e {
+        bytemuck::PodCastError::TargetAlignmentGreaterAndInputNotAligned => Error::Misaligned {
+            align: align_of::<T>(),
+        },
+        _ => Error::LengthNotMultipleOfElement {
+            len,
+            element_size: size_of::<T>(),
+        },
+    }
+}
+
+/// Panics because a [TypeErasedVec] of elements described by `vtable` is leaked.
+#[cold]
+#[track_caller]
+fn leaked(vtable: &ElementVTable, leaked_at: Option<&'static Location<'static>>) -> ! {
+    match leaked_at {
+        Some(location) => panic!(
+            "TypeErasedVec of {} is leaked: the VecMut created at {location} was not dropped",
+            vtable.type_name()
+        ),
+        None => panic!("TypeErasedVec of {} is leaked", vtable.type_name()),
+    }
+}
+
+/// Panics because a [TypeErasedVec] of elements described by `vtable` lacks a capability only `constructors` provide.
+#[cold]
+#[track_caller]
+fn missing_capability(vtable: &ElementVTable, constructors: &str) -> ! {
+    panic!(
+        "TypeErasedVec of {} was not constructed with {constructors}",
+        vtable.type_name()
+    )
+}
+
+#[cfg(feature = "type-id")]
+/// Panics if `a` and `b` describe different element types.
+#[track_caller]
+fn assert_same_element_type(a: &ElementVTable, b: &ElementVTable) {
+    assert!(
+        a.type_id() == b.type_id() && a.layout == b.layout,
+        "element types differ: {} and {}",
+        a.type_name(),
+        b.type_name()
+    );
+}
+
+impl TypeErasedVec<Global> {
+    /// Constructs a new, empty `TypeErasedVec`. See [Vec::new].
+    pub fn new<T: Erasable>() -> Self {
+        Self::new_in::<T>(Global)
+    }
+
+    /// Constructs a new, empty `TypeErasedVec` with specified capacity. See [Vec::with_capacity].
+    pub fn with_capacity<T: Erasable>(capacity: usize) -> Self {
+        Self::with_capacity_in::<T>(capacity, Global)
+    }
+
+    /// Constructs a new, empty `TypeErasedVec` whose buffer is aligned to at least `min_align`. See [TypeErasedVec::with_capacity_aligned_in].
+    pub fn with_capacity_aligned<T: Erasable>(capacity: usize, min_align: usize) -> Self {
+        Self::with_capacity_aligned_in::<T>(capacity, min_align, Global)
+    }
+
+    /// Constructs a new, empty `TypeErasedVec` of the elements `vtable` describes. See [TypeErasedVec::new_from_vtable_in].
+    pub fn new_from_vtable(vtable: &'static ElementVTable) -> Self {
+        Self::new_from_vtable_in(vtable, Global)
+    }
+
+    /// Constructs a new, empty `TypeErasedVec`, using the vtable of the [ErasedElement] implementation of `T`.
+    pub fn new_element<T: ErasedElement>() -> Self {
+        Self::new_element_in::<T>(Global)
+    }
+
+    /// Constructs a new, empty `TypeErasedVec` of `scalar_type` elements. See [TypeErasedVec::new_scalar_in].
+    pub fn new_scalar(scalar_type: ScalarType) -> Self {
+        Self::new_scalar_in(scalar_type, Global)
+    }
+
+    #[cfg(feature = "bytemuck")]
+    /// Constructs a `TypeErasedVec` of `len` zeroed elements. See [TypeErasedVec::zeroed_in].
+    pub fn zeroed<T: bytemuck::Zeroable + Erasable>(len: usize) -> Self {
+        Self::zeroed_in::<T>(len, Global)
+    }
+
+    /// Reinitializes `self` to an empty `Vec<T>`. See [TypeErasedVec::reset_in].
+    pub fn reset<T: Erasable>(&mut self) {
+        self.reset_in::<T>(Global);
+    }
+}
+
+impl<A: Allocator> Drop for TypeErasedVec<A> {
+    fn drop(&mut self) {
+        if let Some(raw) = self.raw.take() {
+            // Deallocates even if an element destructor panics.
+            let raw = DeallocGuard::new(raw, self.vtable.layout);
+            unsafe {
+                (self.vtable.drop)(raw.raw().ptr(), raw.raw().len());
+            }
+        }
+    }
+}
+
+impl<A: Allocator + Clone> Clone for TypeErasedVec<A> {
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be [Clone] or plain-old-data, or if `self` is leaked.
+    #[track_caller]
+    fn clone(&self) -> Self {
+        self.clone_to_allocator(self.allocator().clone())
+    }
+}
+
+impl<A: Allocator + Debug> Debug for TypeErasedVec<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = f.debug_struct("TypeErasedVec");
+        debug_struct
+            .field("raw", &self.raw)
+            .field("vtable", self.vtable);
+        if let (Some(raw), Some(debug)) = (&self.raw, self.vtable.debug) {
+            debug_struct.field("elements", &DebugElements { raw, debug });
+        }
+        debug_struct.finish()
+    }
+}
+
+/// Formats the elements of a [RawVec] with [ElementVTable::debug].
+struct DebugElements<'a, A: Allocator> {
+    raw: &'a RawVec<A>,
+    debug: vtable::DebugFn,
+}
+
+impl<'a, A: Allocator> Debug for DebugElements<'a, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        unsafe { (self.debug)(self.raw.ptr(), self.raw.len(), f) }
+    }
+}
+
+impl<A: Allocator> Hash for TypeErasedVec<A> {
+    /// See [TypeErasedVec::hash_erased].
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash_erased(state);
+    }
+}
+
+/// `Deref`s to `Vec<T, A>`.
+pub struct VecRef<'a, T, A: Allocator + Clone = Global> {
+    raw: &'a TypeErasedVec<A>,
+    vec: ManuallyDrop<vec_type!(T, A)>,
+}
+
+impl<'a, T, A: Allocator + Clone> VecRef<'a, T, A> {
+    /// # Safety
+    ///
+    /// `T` must be what `raw` was constructred with.
+    unsafe fn new(raw: &'a TypeErasedVec<A>) -> Self {
+        let vec = raw!(raw).as_manually_drop_vec();
+        VecRef { raw, vec }
+    }
+}
+
+impl<T: Debug, A: Allocator + Clone + Debug> Debug for VecRef<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VecRef")
+            .field("raw", &self.raw)
+            .field("vec", &self.vec)
+            .finish()
+    }
+}
+
+impl<T, A: Allocator + Clone> Deref for VecRef<'_, T, A> {
+    type Target = vec_type!(T, A);
+
+    fn deref(&self) -> &vec_type!(T, A) {
+        &self.vec
+    }
+}
+
+impl<T, A: Allocator + Clone> Clone for VecRef<'_, T, A> {
+    fn clone(&self) -> Self {
+        unsafe { Self::new(self.raw) }
+    }
+}
+
+#[cfg(feature = "allocator-api")]
+impl<T, A: Allocator + Clone> Drop for VecRef<'_, T, A> {
+    fn drop(&mut self) {
+        // Drops the allocator cloned in `new`, but not the elements or buffer.
+        let vec = unsafe { ManuallyDrop::take(&mut self.vec) };
+        drop(vec.into_raw_parts_with_alloc().3);
+    }
+}
+
+/// `DerefMut`s to `Vec<T, A>`.
+pub struct VecMut<'a, T, A: Allocator> {
+    raw: &'a mut TypeErasedVec<A>,
+    vec: Option<ManuallyDrop<vec_type!(T, A)>>,
+}
+
+impl<T: Debug, A: Allocator + Debug> Debug for VecMut<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VecMut")
+            .field("raw", &self.raw)
+            .field("vec", &self.vec)
+            .finish()
+    }
+}
+
+impl<'a, T, A: Allocator> VecMut<'a, T, A> {
+    /// # Safety
+    ///
+    /// `T` must be what `raw` was constructred with.
+    #[track_caller]
+    unsafe fn new(raw: &'a mut TypeErasedVec<A>) -> Self {
+        let mut raw_vec = raw!(take raw);
+        raw.leaked_at = Some(Location::caller());
+        raw_vec.unalign(raw.vtable.layout);
+        let vec = Some(ManuallyDrop::new(raw_vec.into_vec()));
+        VecMut { raw, vec }
+    }
+}
+
+impl<'a, T, A: Allocator> Deref for VecMut<'a, T, A> {
+    type Target = vec_type!(T, A);
+
+    fn deref(&self) -> &vec_type!(T, A) {
+        self.vec.as_ref().unwrap()
+    }
+}
+
+impl<'a, T, A: Allocator> DerefMut for VecMut<'a, T, A> {
+    fn deref_mut(&mut self) -> &mut vec_type!(T, A) {
+        self.vec.as_mut().unwrap()
+    }
+}
+
+impl<'a, T, A: Allocator> Drop for VecMut<'a, T, A> {
+    fn drop(&mut self) {
+        let vec = self.vec.take().unwrap();
+        let vec = ManuallyDrop::into_inner(vec);
+        self.raw.raw = Some(RawVec::from_vec(vec));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let vec = TypeErasedVec::new::<i32>();
+        let vec_ref = unsafe { vec.get_ref::<i32>() };
+        assert_eq!(vec_ref.len(), 0);
+        assert_eq!(vec_ref.capacity(), 0);
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let vec = TypeErasedVec::with_capacity::<i32>(42);
+        assert_eq!(unsafe { vec.get_ref::<i32>().capacity() }, 42);
+    }
+
+    #[cfg(feature = "allocator-api")]
+    #[test]
+    fn test_get_ref_in() {
+        use std::{alloc::AllocError, ptr::NonNull, rc::Rc};
+
+        #[derive(Clone)]
+        struct CountingAlloc {
+            _counter: Rc<()>,
+        }
+
+        unsafe impl Allocator for CountingAlloc {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                Global.allocate(layout)
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                Global.deallocate(ptr, layout)
+            }
+        }
+
+        let counter = Rc::new(());
+        let mut vec = Vec::new_in(CountingAlloc {
+            _counter: counter.clone(),
+        });
+        vec.extend([1i32, 2]);
+        let vec = TypeErasedVec::from_vec(vec);
+        {
+            let vec_ref = unsafe { vec.get_ref::<i32>() };
+            assert_eq!(*vec_ref, [1, 2]);
+            assert_eq!(Rc::strong_count(&counter), 3);
+        }
+        assert_eq!(Rc::strong_count(&counter), 2);
+    }
+
+    #[cfg(feature = "allocator-api")]
+    #[test]
+    fn test_erase_allocator() {
+        let vecs = [
+            TypeErasedVec::from_vec(vec![1i32, 2]).erase_allocator(),
+            TypeErasedVec::from_vec(Vec::<i32, _>::new_in(&Global)).erase_allocator(),
+        ];
+        let [mut a, b] = vecs;
+        a.push_erased(TypeErasedBox::new(3i32));
+        assert_eq!(unsafe { a.get::<i32>() }, [1, 2, 3]);
+        assert!(b.is_empty());
+    }
+
+    #[test]
+    fn test_into_allocator() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let vec = TypeErasedVec::from_vec(vec![counter.clone(); 3]);
+        let _: Global = vec.into_allocator();
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[cfg(feature = "allocator-api")]
+    #[test]
+    fn test_borrowed_allocator() {
+        static LONG_LIVED: Global = Global;
+        let short_lived = Global;
+
+        let long = TypeErasedVec::new_in_ref::<i32>(&LONG_LIVED);
+        let mut short = TypeErasedVec::with_capacity_in_ref::<i32>(2, &short_lived);
+        short.push_erased(TypeErasedBox::new(1i32));
+        let vecs = [long.shorten_allocator_lifetime(), short];
+        assert_eq!(vecs.iter().map(TypeErasedVec::len).sum::<usize>(), 1);
+    }
+
+    #[test]
+    fn test_with_capacity_aligned() {
+        let is_aligned = |vec: &TypeErasedVec| vec.is_aligned_to(256);
+        let mut vec = TypeErasedVec::with_capacity_aligned::<u8>(3, 256);
+        vec.reserve(100);
+        vec.reserve(1000);
+        assert!(is_aligned(&vec));
+        assert!(is_aligned(&vec.split_off_erased(0)));
+
+        let mut vec = TypeErasedVec::with_capacity_aligned::<u8>(0, 256);
+        unsafe { vec.with_mut(|vec: &mut Vec<u8>| vec.extend([1, 2, 3])) };
+        assert_eq!(unsafe { vec.into_vec::<u8>() }, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_alignment_queries() {
+        let vec = TypeErasedVec::with_capacity_aligned::<u16>(4, 64);
+        assert_eq!(vec.buffer_align(), 64);
+        assert!(vec.ptr_align() >= 64);
+        assert!(vec.is_aligned_to(64));
+        assert_eq!(TypeErasedVec::new::<u32>().buffer_align(), 4);
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let origin: Vec<i32> = (0..10).collect();
         let vec = TypeErasedVec::from_vec(origin.clone());
         let vec_ref = unsafe { vec.get::<i32>() };
         assert_eq!(origin, *vec_ref);
     }
 
     #[test]
-    fn test_into_vec() {
-        let origin: Vec<i32> = (0..10).collect();
-        let vec = TypeErasedVec::from_vec(origin.clone());
-        assert_eq!(unsafe { vec.into_vec::<i32>() }, origin);
+    fn test_collect_erased() {
+        let vec = (0..3).map(|i| i.to_string()).collect_erased();
+        assert_eq!(unsafe { vec.get::<String>() }, ["0", "1", "2"]);
+        let vec = std::iter::empty::<i32>().collect_erased_in(Global);
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_into_vec() {
+        let origin: Vec<i32> = (0..10).collect();
+        let vec = TypeErasedVec::from_vec(origin.clone());
+        assert_eq!(unsafe { vec.into_vec::<i32>() }, origin);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let vec = TypeErasedVec::from_vec(vec![String::from("a"), String::from("b")]);
+        let mut iter = unsafe { vec.into_iter::<String>() };
+        assert_eq!(iter.next_back().unwrap(), "b");
+        assert_eq!(iter.collect::<Vec<_>>(), ["a"]);
+    }
+
+    #[test]
+    fn test_get() {
+        let vec = if true {
+            TypeErasedVec::new::<i32>()
+        } else {
+            TypeErasedVec::new::<f64>()
+        };
+        let _: &[u8] = if true {
+            bytemuck::cast_slice(unsafe { vec.get::<i32>() })
+        } else {
+            bytemuck::cast_slice(unsafe { vec.get::<f64>() })
+        };
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut vec = TypeErasedVec::new::<i32>();
+        let mut vec_mut = unsafe { vec.get_mut::<i32>() };
+        for i in 0..10 {
+            vec_mut.push(i);
+        }
+        drop(vec_mut);
+        let vec_ref = unsafe { vec.get::<i32>() };
+        assert_eq!((0..10).collect::<Vec<_>>(), *vec_ref);
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_try_get() {
+        let vec = TypeErasedVec::from_vec(vec![1i32, 2, 3]);
+        assert_eq!(vec.try_get::<i32>(), Ok(Some(&[1, 2, 3][..])));
+        assert_eq!(vec.try_get::<u32>(), Ok(None));
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_try_get_mut() {
+        let mut vec = TypeErasedVec::new::<i32>();
+        assert!(vec.try_get_mut::<f32>().unwrap().is_none());
+        vec.try_get_mut::<i32>().unwrap().unwrap().push(1);
+        assert_eq!(vec.try_get::<i32>(), Ok(Some(&[1][..])));
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_try_into_vec() {
+        let vec = TypeErasedVec::from_vec(vec![1i32, 2]);
+        let vec = vec.try_into_vec::<u32>().unwrap().unwrap_err();
+        assert_eq!(vec.try_into_vec::<i32>().unwrap().unwrap(), [1, 2]);
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_try_on_leaked() {
+        let mut vec = TypeErasedVec::new::<i32>();
+        forget(unsafe { vec.get_mut::<i32>() });
+        assert!(vec.is_leaked());
+        assert_eq!(vec.try_get::<i32>(), Err(LeakedError));
+        assert!(matches!(vec.try_get_mut::<i32>(), Err(LeakedError)));
+        assert!(matches!(vec.try_into_vec::<i32>(), Err(LeakedError)));
+    }
+
+    #[test]
+    fn test_len_and_capacity() {
+        let mut vec = TypeErasedVec::with_capacity::<i32>(10);
+        assert!(vec.is_empty());
+        assert_eq!(vec.capacity(), 10);
+        unsafe { vec.get_mut::<i32>() }.extend([1, 2, 3]);
+        assert_eq!(vec.len(), 3);
+        assert!(!vec.is_empty());
+        assert_eq!(TypeErasedVec::new::<()>().capacity(), usize::MAX);
+    }
+
+    #[test]
+    fn test_reserve_and_shrink_to_fit() {
+        let mut vec = TypeErasedVec::from_vec(vec![1i32, 2]);
+        vec.reserve_exact(8);
+        assert_eq!(vec.capacity(), 10);
+        vec.reserve(8);
+        assert_eq!(vec.capacity(), 10);
+        vec.reserve(9);
+        assert_eq!(vec.capacity(), 20);
+        vec.shrink_to_fit();
+        assert_eq!(vec.capacity(), 2);
+        assert_eq!(unsafe { vec.get::<i32>() }, [1, 2]);
+
+        vec.clear();
+        vec.shrink_to_fit();
+        assert_eq!(vec.capacity(), 0);
+        vec.reserve(1);
+        assert_eq!(vec.capacity(), 4);
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut vec = TypeErasedVec::new::<u64>();
+        assert_eq!(vec.try_reserve(10), Ok(()));
+        assert!(vec.capacity() >= 10);
+        assert_eq!(
+            vec.try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(
+            vec.try_reserve_exact(usize::MAX / 8),
+            Err(TryReserveError::CapacityOverflow)
+        );
+    }
+
+    #[test]
+    fn test_element_layout() {
+        let vec = TypeErasedVec::new::<[f32; 3]>();
+        assert_eq!(vec.element_layout(), Layout::new::<[f32; 3]>());
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_pod_bytes() {
+        let mut vec = TypeErasedVec::from_pod_vec(vec![1u16, 2]);
+        assert_eq!(
+            vec.as_bytes().unwrap(),
+            bytemuck::cast_slice::<u16, u8>(&[1, 2])
+        );
+        vec.as_bytes_mut()
+            .unwrap()
+            .copy_from_slice(bytemuck::cast_slice::<u16, u8>(&[3, 4]));
+        assert_eq!(unsafe { vec.get::<u16>() }, [3, 4]);
+        assert!(TypeErasedVec::from_vec(vec![1u16]).as_bytes().is_none());
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_iter_element_bytes() {
+        let vec = TypeErasedVec::from_pod_vec(vec![1u16, 2, 3]);
+        let bytes = vec.iter_element_bytes().unwrap();
+        assert_eq!(bytes.len(), 3);
+        assert_eq!(
+            bytes.rev().collect::<Vec<_>>(),
+            [3u16.to_ne_bytes(), 2u16.to_ne_bytes(), 1u16.to_ne_bytes()]
+        );
+        let vec = TypeErasedVec::from_pod_vec(vec![(); 2]);
+        assert_eq!(vec.iter_element_bytes().unwrap().count(), 2);
+        assert!(TypeErasedVec::from_vec(vec![1u16])
+            .iter_element_bytes()
+            .is_none());
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_push_bytes() {
+        let mut vec = TypeErasedVec::from_pod_vec(Vec::<u32>::new());
+        for i in 0..10u32 {
+            vec.push_bytes(&i.to_ne_bytes());
+        }
+        assert_eq!(unsafe { vec.get::<u32>() }, (0..10).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    #[should_panic]
+    fn test_push_bytes_wrong_size() {
+        TypeErasedVec::from_pod_vec(Vec::<u32>::new()).push_bytes(&[0; 3]);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_extend_from_byte_slice() {
+        let mut vec = TypeErasedVec::from_pod_vec(vec![0u16]);
+        vec.extend_from_byte_slice(bytemuck::cast_slice::<u16, u8>(&[1, 2, 3]));
+        vec.extend_from_byte_slice(&[]);
+        assert_eq!(unsafe { vec.get::<u16>() }, [0, 1, 2, 3]);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    #[should_panic]
+    fn test_extend_from_byte_slice_wrong_size() {
+        TypeErasedVec::from_pod_vec(Vec::<u16>::new()).extend_from_byte_slice(&[0; 3]);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_insert_bytes_and_remove_to() {
+        let mut vec = TypeErasedVec::from_pod_vec(vec![1u32, 3]);
+        vec.insert_bytes(1, &2u32.to_ne_bytes());
+        vec.insert_bytes(3, &4u32.to_ne_bytes());
+        assert_eq!(unsafe { vec.get::<u32>() }, [1, 2, 3, 4]);
+
+        let mut out = [0; 4];
+        vec.remove_to(0, &mut out);
+        assert_eq!(u32::from_ne_bytes(out), 1);
+        assert_eq!(unsafe { vec.get::<u32>() }, [2, 3, 4]);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    #[should_panic]
+    fn test_remove_to_out_of_bounds() {
+        TypeErasedVec::from_pod_vec(vec![1u32]).remove_to(1, &mut [0; 4]);
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_element() {
+        let mut vec = TypeErasedVec::from_vec_debug(vec![1i32, 2]);
+        let element = vec.element(1).unwrap();
+        assert_eq!(element.downcast_ref::<i32>(), Some(&2));
+        assert_eq!(element.downcast_ref::<u32>(), None);
+        assert_eq!(
+            format!("{element:?}"),
+            "ErasedRef { type_name: \"i32\", element: [2] }"
+        );
+        assert!(vec.element(2).is_none());
+
+        *vec.element_mut(0).unwrap().downcast_mut::<i32>().unwrap() = 3;
+        assert_eq!(unsafe { vec.get::<i32>() }, [3, 2]);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_zeroed() {
+        let mut vec = TypeErasedVec::zeroed::<[f32; 2]>(2);
+        assert_eq!(unsafe { vec.get::<[f32; 2]>() }, [[0.0; 2]; 2]);
+        vec.resize_zeroed(1);
+        assert_eq!(vec.len(), 1);
+
+        let mut vec = TypeErasedVec::from_pod_vec(vec![1u8]);
+        vec.resize_zeroed(3);
+        assert_eq!(unsafe { vec.get::<u8>() }, [1, 0, 0]);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_swap_bytes_in_place() {
+        let mut vec = TypeErasedVec::from_pod_vec(vec![[0x0102u16, 0x0304], [0x0506, 0x0708]]);
+        vec.swap_bytes_in_place(2);
+        assert_eq!(
+            unsafe { vec.get::<[u16; 2]>() },
+            [[0x0201, 0x0403], [0x0605, 0x0807]]
+        );
+        vec.swap_bytes_in_place(4);
+        assert_eq!(
+            unsafe { vec.get::<[u16; 2]>() },
+            [[0x0304, 0x0102], [0x0708, 0x0506]]
+        );
+    }
+
+    #[test]
+    fn test_spare_capacity_bytes_mut() {
+        let mut vec = TypeErasedVec::new::<u16>();
+        vec.reserve(2);
+        let spare = vec.spare_capacity_bytes_mut();
+        assert!(spare.len() >= 4);
+        for (byte, value) in spare
+            .iter_mut()
+            .zip(1u16.to_ne_bytes().iter().chain(2u16.to_ne_bytes().iter()))
+        {
+            byte.write(*value);
+        }
+        unsafe {
+            vec.set_len_erased(2);
+            assert_eq!(vec.get::<u16>(), [1, 2]);
+        }
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_write_to() {
+        let vec = TypeErasedVec::from_pod_vec(vec![1u16, 2]);
+        let mut out = Vec::new();
+        vec.write_to(&mut out).unwrap();
+        assert_eq!(out, vec.as_bytes().unwrap());
+
+        let mut copy = TypeErasedVec::from_pod_vec(Vec::<u16>::new());
+        copy.fill_from_reader(&out[..], 2).unwrap();
+        assert_eq!(unsafe { copy.get::<u16>() }, [1, 2]);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_fill_from_reader() {
+        let mut vec = TypeErasedVec::from_pod_vec(vec![0u16]);
+        let bytes: Vec<u8> = [1u16, 2, 3].iter().flat_map(|x| x.to_ne_bytes()).collect();
+        assert_eq!(vec.fill_from_reader(&bytes[..], 2).unwrap(), 2);
+        assert_eq!(unsafe { vec.get::<u16>() }, [0, 1, 2]);
+        assert_eq!(vec.fill_from_reader(&bytes[..], 8).unwrap(), 3);
+        assert_eq!(vec.len(), 6);
+
+        let error = vec.fill_from_reader(&bytes[..5], 8).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(unsafe { vec.get::<u16>() }[6..], [1, 2]);
+    }
+
+    #[test]
+    fn test_truncate_and_clear() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut vec = TypeErasedVec::from_vec(vec![counter.clone(); 4]);
+        vec.truncate(5);
+        assert_eq!(Rc::strong_count(&counter), 5);
+        vec.truncate(3);
+        assert_eq!(vec.len(), 3);
+        assert_eq!(Rc::strong_count(&counter), 4);
+        assert!(vec.pop_and_drop());
+        assert_eq!(Rc::strong_count(&counter), 3);
+        vec.clear();
+        assert!(vec.is_empty());
+        assert_eq!(Rc::strong_count(&counter), 1);
+        assert!(!vec.pop_and_drop());
+    }
+
+    #[test]
+    fn test_swap_remove_erased() {
+        use std::rc::Rc;
+
+        let first = Rc::new(1);
+        let mut vec =
+            TypeErasedVec::from_vec(vec![first.clone(), Rc::new(2), Rc::new(3), Rc::new(4)]);
+        vec.swap_remove_erased(0);
+        assert_eq!(Rc::strong_count(&first), 1);
+        vec.swap_remove_erased(2);
+        assert_eq!(unsafe { vec.get::<Rc<i32>>() }, [Rc::new(4), Rc::new(2)]);
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_push_and_pop_erased() {
+        let mut vec = TypeErasedVec::new::<String>();
+        vec.push_erased(TypeErasedBox::new(String::from("a")));
+        vec.push_erased(TypeErasedBox::new(String::from("b")));
+        assert_eq!(unsafe { vec.get::<String>() }, ["a", "b"]);
+
+        let value = vec.pop_erased().unwrap();
+        assert_eq!(value.downcast::<String>().unwrap(), "b");
+        drop(vec.pop_erased());
+        assert!(vec.pop_erased().is_none());
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    #[should_panic]
+    fn test_push_erased_wrong_type() {
+        TypeErasedVec::new::<i32>().push_erased(TypeErasedBox::new(1u32));
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_type_erased_box() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let value = TypeErasedBox::new(counter.clone());
+        assert!(value.as_erased_ref().is::<Rc<()>>());
+        let value = value.downcast::<i32>().unwrap_err();
+        drop(value);
+        assert_eq!(Rc::strong_count(&counter), 1);
+        assert!(TypeErasedBox::new(()).downcast::<()>().is_ok());
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_move_element_to() {
+        let mut src = TypeErasedVec::from_vec(vec![
+            String::from("a"),
+            String::from("b"),
+            String::from("c"),
+        ]);
+        let mut dst = TypeErasedVec::new::<String>();
+        src.move_element_to(0, &mut dst);
+        src.move_element_to(1, &mut dst);
+        assert_eq!(unsafe { src.get::<String>() }, ["c"]);
+        assert_eq!(unsafe { dst.get::<String>() }, ["a", "b"]);
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    #[should_panic]
+    fn test_move_element_to_wrong_type() {
+        TypeErasedVec::from_vec(vec![1i32]).move_element_to(0, &mut TypeErasedVec::new::<u32>());
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_append() {
+        let mut vec = TypeErasedVec::from_vec(vec![String::from("a")]);
+        let mut other = TypeErasedVec::from_vec(vec![String::from("b"), String::from("c")]);
+        vec.append(&mut other);
+        assert_eq!(unsafe { vec.get::<String>() }, ["a", "b", "c"]);
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn test_split_off_erased() {
+        let mut vec = TypeErasedVec::from_vec(vec![
+            String::from("a"),
+            String::from("b"),
+            String::from("c"),
+        ]);
+        let tail = vec.split_off_erased(1);
+        assert_eq!(unsafe { vec.get::<String>() }, ["a"]);
+        assert_eq!(unsafe { tail.get::<String>() }, ["b", "c"]);
+        assert!(vec.split_off_erased(1).is_empty());
+    }
+
+    #[test]
+    fn test_drain_erased() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut vec = TypeErasedVec::from_vec(vec![counter.clone(); 5]);
+        vec.drain_erased(1..3);
+        assert_eq!(vec.len(), 3);
+        assert_eq!(Rc::strong_count(&counter), 4);
+        vec.drain_erased(..);
+        assert!(vec.is_empty());
+        assert_eq!(Rc::strong_count(&counter), 1);
+
+        let mut vec = TypeErasedVec::from_vec(vec![1i32, 2, 3, 4]);
+        vec.drain_erased(1..=2);
+        assert_eq!(unsafe { vec.get::<i32>() }, [1, 4]);
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_retain_erased() {
+        let mut vec = TypeErasedVec::from_vec((0..10).map(|i| i.to_string()).collect());
+        vec.retain_erased(|element| {
+            element
+                .downcast_ref::<String>()
+                .unwrap()
+                .parse::<i32>()
+                .unwrap()
+                % 3
+                == 0
+        });
+        assert_eq!(unsafe { vec.get::<String>() }, ["0", "3", "6", "9"]);
+    }
+
+    #[test]
+    fn test_sort_erased() {
+        let mut vec = TypeErasedVec::from_vec_ord(vec![3, 1, 2]);
+        vec.sort_erased();
+        assert_eq!(unsafe { vec.get::<i32>() }, [1, 2, 3]);
+
+        let mut vec = TypeErasedVec::from_vec(vec![(1, "a"), (0, "b"), (1, "c"), (0, "d")]);
+        vec.sort_erased_by(|a, b| unsafe {
+            let a = a.downcast_ref_unchecked::<(i32, &str)>();
+            let b = b.downcast_ref_unchecked::<(i32, &str)>();
+            a.0.cmp(&b.0)
+        });
+        assert_eq!(
+            unsafe { vec.get::<(i32, &str)>() },
+            [(0, "b"), (0, "d"), (1, "a"), (1, "c")]
+        );
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_binary_search_erased() {
+        let vec = TypeErasedVec::from_vec_ord(vec![1, 3, 5]);
+        let key = |value: i32| TypeErasedBox::new(value);
+        assert_eq!(vec.binary_search_erased(key(3).as_erased_ref()), Ok(1));
+        assert_eq!(vec.binary_search_erased(key(0).as_erased_ref()), Err(0));
+        assert_eq!(vec.binary_search_erased(key(4).as_erased_ref()), Err(2));
+        assert_eq!(vec.binary_search_erased(key(6).as_erased_ref()), Err(3));
+        assert_eq!(
+            vec.binary_search_erased_by(|element| element.downcast_ref::<i32>().unwrap().cmp(&5)),
+            Ok(2)
+        );
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_dedup_erased() {
+        let mut vec = TypeErasedVec::from_vec_eq(vec![1, 1, 2, 3, 3, 3, 1]);
+        vec.dedup_erased();
+        assert_eq!(unsafe { vec.get::<i32>() }, [1, 2, 3, 1]);
+
+        let mut vec = TypeErasedVec::from_vec(vec![10, 11, 20, 21, 12]);
+        vec.dedup_erased_by(|a, b| unsafe {
+            a.downcast_ref_unchecked::<i32>() / 10 == b.downcast_ref_unchecked::<i32>() / 10
+        });
+        assert_eq!(unsafe { vec.get::<i32>() }, [10, 20, 12]);
+    }
+
+    #[test]
+    fn test_with_mut() {
+        let mut vec = TypeErasedVec::new::<i32>();
+        let len = unsafe {
+            vec.with_mut(|vec: &mut Vec<i32>| {
+                vec.extend(0..10);
+                vec.len()
+            })
+        };
+        assert_eq!(len, 10);
+        assert_eq!(unsafe { vec.get::<i32>() }, (0..10).collect::<Vec<_>>());
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            vec.with_mut(|_: &mut Vec<i32>| panic!())
+        }));
+        assert!(result.is_err());
+        assert!(!vec.is_leaked());
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut vec = TypeErasedVec::from_vec(vec![1i32]);
+        forget(unsafe { vec.get_mut::<i32>() });
+        vec.reset::<f32>();
+        assert!(!vec.is_leaked());
+        assert!(vec.is_empty());
+        assert_eq!(vec.element_layout(), Layout::new::<f32>());
+        unsafe { vec.get_mut::<f32>() }.push(1.0);
+        assert_eq!(unsafe { vec.get::<f32>() }, [1.0]);
+    }
+
+    #[test]
+    fn test_clone() {
+        let vec = TypeErasedVec::from_vec_clone(vec![String::from("a"), String::from("b")]);
+        assert!(vec.is_clone());
+        let mut cloned = vec.clone();
+        unsafe { cloned.get_mut::<String>() }.push(String::from("c"));
+        assert_eq!(unsafe { vec.get::<String>() }, ["a", "b"]);
+        assert_eq!(unsafe { cloned.get::<String>() }, ["a", "b", "c"]);
+        assert!(cloned.is_clone());
+    }
+
+    #[test]
+    fn test_clone_to_allocator() {
+        let vec = TypeErasedVec::from_vec_clone(vec![String::from("a")]);
+        let clone = vec.clone_to_allocator(Global);
+        assert_eq!(unsafe { clone.get::<String>() }, ["a"]);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_clone_pod() {
+        let vec = TypeErasedVec::from_pod_vec(vec![1u8, 2]);
+        assert!(vec.is_clone());
+        assert_eq!(unsafe { vec.clone().get::<u8>() }, [1, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_clone_without_clone_fn() {
+        let _ = TypeErasedVec::from_vec(vec![1i32]).clone();
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_eq_erased() {
+        let a = TypeErasedVec::from_vec_eq(vec![1i32, 2]);
+        assert!(a.eq_erased(&TypeErasedVec::from_vec_eq(vec![1i32, 2])));
+        assert!(a.eq_erased(&TypeErasedVec::from_vec(vec![1i32, 2])));
+        assert!(!a.eq_erased(&TypeErasedVec::from_vec_eq(vec![1i32])));
+        assert!(!a.eq_erased(&TypeErasedVec::from_vec_eq(vec![1i32, 3])));
+        assert!(!a.eq_erased(&TypeErasedVec::from_vec_eq(vec![1u32, 2])));
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of(value: &(impl Hash + ?Sized)) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let origin = vec![1i32, 2, 3];
+        let vec = TypeErasedVec::from_vec_hash(origin.clone());
+        assert_eq!(hash_of(&vec), hash_of(&origin[..]));
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_hash_set() {
+        use std::collections::HashSet;
+
+        static VTABLE: TypedVTable<i32> = TypedVTable::new().with_eq().with_hash();
+        let key = |vec: Vec<i32>| {
+            ErasedKey::new(TypeErasedVec::from_vec_with_vtable(vec, &VTABLE)).unwrap()
+        };
+        let mut set = HashSet::new();
+        assert!(set.insert(key(vec![1, 2])));
+        assert!(set.insert(key(vec![3])));
+        assert!(!set.insert(key(vec![1, 2])));
+        assert!(set.contains(&key(vec![3])));
+        assert!(!set.contains(&key(vec![2, 1])));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.take(&key(vec![3])).unwrap().into_inner().len(), 1);
+
+        assert!(ErasedKey::new(TypeErasedVec::from_vec_eq(vec![1i32])).is_none());
+        assert!(ErasedKey::new(TypeErasedVec::from_vec_hash(vec![1i32])).is_none());
+        let mut leaked = TypeErasedVec::from_vec_with_vtable(vec![1], &VTABLE);
+        forget(unsafe { leaked.get_mut::<i32>() });
+        assert!(ErasedKey::new(leaked).is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        let vec = TypeErasedVec::from_vec_serialize(vec![1i32, 2, 3]);
+        assert_eq!(serde_json::to_string(&vec).unwrap(), "[1,2,3]");
+        assert!(serde_json::to_string(&TypeErasedVec::new::<i32>()).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_erased_vec_seed() {
+        use serde::de::DeserializeSeed;
+
+        static VTABLE: TypedVTable<String> = TypedVTable::new().with_deserialize();
+        let mut deserializer = serde_json::Deserializer::from_str(r#"["a", "b"]"#);
+        let vec = ErasedVecSeed::new(VTABLE.erased())
+            .deserialize(&mut deserializer)
+            .unwrap();
+        assert_eq!(unsafe { vec.get::<String>() }, ["a", "b"]);
+
+        let mut deserializer = serde_json::Deserializer::from_str("[1]");
+        let seed = ErasedVecSeed::new(&TypedVTable::<String>::NEW.vtable);
+        assert!(seed.deserialize(&mut deserializer).is_err());
+    }
+
+    #[cfg(feature = "serde-pod-bytes")]
+    #[test]
+    fn test_serde_pod_bytes() {
+        use serde::de::DeserializeSeed;
+
+        let vec = TypeErasedVec::from_pod_vec(vec![1u16, 2]);
+        let json = serde_json::to_string(&vec).unwrap();
+        assert_eq!(json.matches(',').count(), 3);
+
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        let vec = ErasedVecSeed::new(&TypedVTable::<u16>::POD.vtable)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        assert_eq!(unsafe { vec.get::<u16>() }, [1, 2]);
+
+        let mut deserializer = serde_json::Deserializer::from_str("[1, 2, 3]");
+        assert!(ErasedVecSeed::new(&TypedVTable::<u16>::POD.vtable)
+            .deserialize(&mut deserializer)
+            .is_err());
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn test_archive() {
+        let vec = TypeErasedVec::from_pod_vec(vec![1u32, 2, 3]);
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&vec).unwrap();
+        let archived = rkyv::access::<ArchivedTypeErasedVec, rkyv::rancor::Error>(&bytes).unwrap();
+        assert_eq!(archived.len(), 3);
+        assert_eq!(archived.get::<u32>(), Some(&[1, 2, 3][..]));
+        assert_eq!(archived.get::<u16>(), None);
+        assert_eq!(archived.iter_element_bytes().count(), 3);
+
+        // The root is at the end, starting with the little endian length.
+        let mut forged = bytes.clone();
+        let root = forged.len() - size_of::<ArchivedTypeErasedVec>();
+        forged[root] = 4;
+        assert!(rkyv::access::<ArchivedTypeErasedVec, rkyv::rancor::Error>(&forged).is_err());
+
+        let vec = TypeErasedVec::new::<u32>();
+        assert!(rkyv::to_bytes::<rkyv::rancor::Error>(&vec).is_err());
+    }
+
+    #[test]
+    fn test_scalar() {
+        let mut vec = TypeErasedVec::new_scalar(ScalarType::F32);
+        assert_eq!(vec.scalar_type(), Some(ScalarType::F32));
+        assert_eq!(vec.vtable().layout().size(), ScalarType::F32.size());
+        vec.push_bytes(&1.5f32.to_ne_bytes());
+        assert_eq!(unsafe { vec.get::<f32>() }, [1.5]);
+        assert_eq!(vec.clone().as_bytes(), vec.as_bytes());
+
+        let vec = TypeErasedVec::from_scalar_vec(vec![1u16, 2]);
+        assert_eq!(vec.scalar_type(), Some(u16::SCALAR_TYPE));
+        assert_eq!(TypeErasedVec::new::<u16>().scalar_type(), None);
+        assert_eq!(ScalarType::U16.to_string(), "u16");
+        assert!(ScalarType::ALL
+            .iter()
+            .all(|t| t.vtable().scalar_type() == Some(*t)));
+    }
+
+    #[test]
+    fn test_dispatch() {
+        fn byte_len<T: Scalar>(vec: &TypeErasedVec) -> usize {
+            size_of_val(unsafe { vec.get::<T>() })
+        }
+
+        for scalar_type in ScalarType::ALL {
+            let mut vec = TypeErasedVec::new_scalar(scalar_type);
+            vec.push_bytes(&[0; 8][..scalar_type.size()]);
+            assert_eq!(
+                crate::dispatch!(scalar_type, |T| byte_len::<T>(&vec)),
+                scalar_type.size()
+            );
+            assert_eq!(
+                crate::dispatch!(scalar_type, |T| T::SCALAR_TYPE),
+                scalar_type
+            );
+        }
+    }
+
+    #[cfg(all(feature = "derive", feature = "type-id"))]
+    #[test]
+    fn test_derive_erased_element() {
+        #[derive(Clone, Debug, PartialEq, Hash, ErasedElement)]
+        #[erased_element(clone, debug, eq, hash)]
+        struct Point {
+            x: i32,
+            name: String,
+        }
+
+        let point = Point {
+            x: 1,
+            name: "a".into(),
+        };
+        let vec = TypeErasedVec::from_element_vec(vec![point.clone()]);
+        assert!(vec.eq_erased(&vec.clone()));
+        assert!(format!("{vec:?}").contains("name: \"a\""));
+        assert!(vec.vtable().is_hash());
+        assert!(!vec.vtable().is_ord());
+
+        let mut vec = TypeErasedVec::new_element::<Point>();
+        unsafe { vec.get_mut() }.push(point);
+        vec.clear();
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_type_registry() {
+        static VTABLE: TypedVTable<String> = TypedVTable::new().with_clone().with_debug();
+
+        let mut registry = TypeRegistry::new();
+        assert!(registry.register(&VTABLE).is_none());
+        assert_eq!(registry.len(), 1);
+        let vtable = registry.get(TypeId::of::<String>()).unwrap();
+        assert!(std::ptr::eq(vtable, VTABLE.erased()));
+        assert!(registry.get_by_name("u8").is_none());
+
+        let mut vec = TypeErasedVec::from_vec(vec![String::from("a")]);
+        assert!(!vec.vtable().is_clone());
+        assert!(vec.use_registered_vtable(&registry));
+        assert_eq!(unsafe { vec.clone().get::<String>() }, ["a"]);
+
+        let mut vec = TypeErasedVec::new_from_vtable(vtable);
+        unsafe { vec.get_mut::<String>() }.push("b".into());
+        assert!(format!("{vec:?}").contains("\"b\""));
+
+        TypeRegistry::global().write().unwrap().register(&VTABLE);
+        assert!(TypeRegistry::global()
+            .read()
+            .unwrap()
+            .get(TypeId::of::<String>())
+            .is_some());
     }
 
+    #[cfg(feature = "type-id")]
     #[test]
-    fn test_get() {
-        let vec = if true {
-            TypeErasedVec::new::<i32>()
-        } else {
-            TypeErasedVec::new::<f64>()
+    fn test_same_element_type() {
+        let a = TypeErasedVec::new::<i32>();
+        assert!(a.same_element_type(&TypeErasedVec::from_vec_debug(vec![1i32])));
+        assert!(!a.same_element_type(&TypeErasedVec::new::<u32>()));
+    }
+
+    #[cfg(feature = "type-id")]
+    #[test]
+    fn test_dyn_vec() {
+        let mut vec = DynVec::from(vec![String::from("a")]);
+        vec.push(String::from("c"));
+        vec.insert(1, String::from("b"));
+        assert_eq!(vec.downcast_ref::<String>().unwrap(), ["a", "b", "c"]);
+        assert!(vec.downcast_mut::<i32>().is_none());
+        vec.downcast_mut::<String>().unwrap()[0].push('!');
+        assert_eq!(vec.remove::<String>(0), "a!");
+        assert_eq!(vec.with_vec_mut(|v: &mut Vec<String>| v.len()), Some(2));
+        assert_eq!(vec.iter::<String>().unwrap().count(), 2);
+        assert!(vec
+            .iter_erased()
+            .all(|element| element.downcast_ref::<String>().is_some()));
+        assert_eq!(vec.swap_remove::<String>(0), "b");
+        assert_eq!(vec.pop::<String>().as_deref(), Some("c"));
+        assert!(vec.is_empty());
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| vec.push(1)));
+        assert!(result.is_err());
+        assert!(!vec.as_erased().is_leaked());
+    }
+
+    #[test]
+    fn test_element_type_name() {
+        assert_eq!(
+            TypeErasedVec::new::<[f32; 3]>().element_type_name(),
+            "[f32; 3]"
+        );
+    }
+
+    #[test]
+    fn test_erased_slice() {
+        let vec = TypeErasedVec::from_vec_debug(vec![1i32, 2, 3]);
+        let slice = vec.as_erased_slice();
+        assert_eq!(slice.len(), 3);
+        assert_eq!(unsafe { slice.get::<i32>() }, [1, 2, 3]);
+        assert_eq!(
+            unsafe { slice.element(2).unwrap().downcast_ref_unchecked::<i32>() },
+            &3
+        );
+        assert!(slice.element(3).is_none());
+        assert!(format!("{slice:?}").contains("elements: [1, 2, 3]"));
+
+        let strings = ["a".to_string(), "b".to_string()];
+        let slice = TypeErasedSlice::from_slice(&strings);
+        assert!(slice.as_bytes().is_none());
+        #[cfg(feature = "type-id")]
+        {
+            assert_eq!(slice.downcast::<String>(), Some(&strings[..]));
+            assert_eq!(slice.downcast::<&str>(), None);
+        }
+
+        let units = [(); 4];
+        let slice = TypeErasedSlice::from_slice(&units);
+        assert_eq!(unsafe { slice.get::<()>() }.len(), 4);
+        assert!(slice.element(3).is_some());
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_from_static() {
+        static VERTICES: [[f32; 2]; 3] = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+
+        let slice = TypeErasedSlice::from_static_pod(&VERTICES);
+        assert_eq!(slice.as_ptr(), VERTICES.as_ptr().cast());
+        assert_eq!(slice.as_bytes().unwrap().len(), 24);
+        assert_eq!(slice.iter_element_bytes().unwrap().len(), 3);
+        assert!(TypeErasedSlice::from_static(&VERTICES).as_bytes().is_none());
+    }
+
+    #[test]
+    fn test_erased_slice_mut() {
+        let mut vec = TypeErasedVec::from_vec_debug(vec![1i32, 2, 3]);
+        let mut slice = vec.as_erased_slice_mut();
+        slice.swap(0, 2);
+        unsafe {
+            *slice
+                .element_mut(1)
+                .unwrap()
+                .downcast_mut_unchecked::<i32>() = 5
         };
-        let _: &[u8] = if true {
-            bytemuck::cast_slice(unsafe { vec.get::<i32>() })
-        } else {
-            bytemuck::cast_slice(unsafe { vec.get::<f64>() })
+        assert!(slice.element_mut(3).is_none());
+        assert!(format!("{slice:?}").contains("elements: [3, 5, 1]"));
+        assert_eq!(unsafe { vec.get::<i32>() }, [3, 5, 1]);
+
+        let mut strings = ["a".to_string(), "b".to_string()];
+        let mut slice = TypeErasedSliceMut::from_mut_slice(&mut strings);
+        slice.swap(0, 1);
+        slice.swap(1, 1);
+        #[cfg(feature = "type-id")]
+        slice.reborrow().downcast::<String>().unwrap()[0].push('!');
+        assert!(slice.as_bytes_mut().is_none());
+        #[cfg(feature = "type-id")]
+        assert_eq!(strings, ["b!", "a"]);
+    }
+
+    #[test]
+    fn test_freeze() {
+        let frozen = TypeErasedVec::from_vec(vec!["a".to_string(), "b".to_string()]).freeze();
+        let shared = frozen.clone();
+        assert_eq!(frozen.ref_count(), 2);
+        assert!(frozen.ptr_eq(&shared));
+        assert_eq!(unsafe { shared.get::<String>() }, ["a", "b"]);
+
+        let frozen = frozen.try_unfreeze().unwrap_err();
+        drop(shared);
+        let vec = frozen.try_unfreeze().unwrap();
+        assert_eq!(unsafe { vec.into_vec::<String>() }, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_make_mut() {
+        let mut frozen = TypeErasedVec::from_vec_clone(vec!["a".to_string()]).freeze();
+        let ptr = frozen.as_erased_slice().as_ptr();
+        unsafe { frozen.make_mut().get_mut::<String>()[0].push('!') };
+        assert_eq!(frozen.as_erased_slice().as_ptr(), ptr);
+
+        let shared = frozen.clone();
+        unsafe { frozen.make_mut().get_mut::<String>().push("b".to_string()) };
+        assert!(!frozen.ptr_eq(&shared));
+        assert_eq!(unsafe { shared.get::<String>() }, ["a!"]);
+        assert_eq!(unsafe { frozen.get::<String>() }, ["a!", "b"]);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let mut vec = TypeErasedVec::from_vec_send(vec![std::cell::Cell::new(1)]);
+        unsafe {
+            vec.get_mut::<std::cell::Cell<i32>>()
+                .push(std::cell::Cell::new(2))
         };
+        let vec = std::thread::spawn(move || vec).join().unwrap();
+        assert_eq!(vec.len(), 2);
+
+        let vec = TypeErasedVec::from_vec_sync(vec![1u8, 2]);
+        assert_send_sync(&vec);
+        let frozen = vec.freeze();
+        assert_send_sync(&frozen);
+        let shared = frozen.clone();
+        let len = std::thread::spawn(move || shared.len()).join().unwrap();
+        assert_eq!(len, 2);
+        let vec = frozen.try_unfreeze().unwrap();
+        assert_eq!(unsafe { vec.into_erased().into_vec::<u8>() }, [1, 2]);
     }
 
     #[test]
-    fn test_get_mut() {
+    fn test_sync_type_erased_vec() {
+        let vec = SyncTypeErasedVec::new(TypeErasedVec::from_vec_sync(vec![1i32]));
+        std::thread::scope(|s| {
+            for i in 2..5 {
+                let vec = &vec;
+                s.spawn(move || unsafe { vec.write_unchecked::<i32>().push(i) });
+            }
+        });
+        assert_eq!(vec.read_erased().len(), 4);
+        let mut elements = unsafe { vec.read_unchecked::<i32>() }.to_vec();
+        elements.sort();
+        assert_eq!(elements, [1, 2, 3, 4]);
+        #[cfg(feature = "type-id")]
+        {
+            assert!(vec.write::<u32>().is_none());
+            vec.write::<i32>().unwrap().truncate(1);
+            assert_eq!(*vec.read::<i32>().unwrap(), [1]);
+        }
+        assert!(!vec.into_inner().is_leaked());
+    }
+
+    #[test]
+    fn test_buffer_pool() {
+        let mut pool = BufferPool::new();
+        let mut vec = pool.take::<String>();
+        unsafe { vec.get_mut::<String>().push("a".to_string()) };
+        let ptr = vec.as_erased_slice().as_ptr();
+        drop(vec);
+        assert_eq!(pool.len(), 1);
+
+        let vec = pool.take::<[u8; 5]>();
+        assert_eq!(pool.len(), 1);
+        drop(vec);
+        let vec = pool.take::<Vec<u8>>();
+        assert_eq!(vec.as_erased_slice().as_ptr(), ptr);
+        assert!(vec.is_empty());
+        let vec = vec.into_inner();
+        assert_eq!(pool.len(), 0);
+
+        pool.recycle(vec);
+        pool.recycle(TypeErasedVec::new::<String>());
+        pool.recycle(TypeErasedVec::from_vec(vec![(); 3]));
+        assert_eq!(pool.len(), 1);
+        pool.clear();
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_erased_vec_registry() {
+        let mut registry = ErasedVecRegistry::new();
+        let a = registry.insert(TypeErasedVec::from_vec(vec![1i32]));
+        let b = registry.insert(TypeErasedVec::from_vec(vec!["b".to_string()]));
+        unsafe { registry.get_mut(a).unwrap().get_mut::<i32>().push(2) };
+        assert_eq!(unsafe { registry.get(a).unwrap().get::<i32>() }, [1, 2]);
+
+        assert!(registry.remove(a).is_some());
+        assert!(registry.remove(a).is_none());
+        let c = registry.insert(TypeErasedVec::new::<u8>());
+        assert_ne!(a, c);
+        assert!(!registry.contains(a));
+        assert!(registry.get_mut(a).is_none());
+        assert_eq!(registry.len(), 2);
+        let ids: Vec<_> = registry.iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, [c, b]);
+        for (_, vec) in registry.iter_mut() {
+            vec.clear();
+        }
+        assert!(registry.get(b).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_mut_tracked() {
+        let mut vec = TypeErasedVec::from_vec(vec![0u16; 10]);
+        let mut tracked = unsafe { vec.get_mut_tracked::<u16>() };
+        *tracked.get_mut(4).unwrap() = 1;
+        assert!(tracked.get_mut(10).is_none());
+        tracked.set(2, 2);
+        assert_eq!(tracked.dirty_ranges(), [4..6, 8..10]);
+        tracked.set(3, 3);
+        assert_eq!(tracked.dirty_ranges(), vec![4..10]);
+        tracked.swap(0, 9);
+        tracked.range_mut(..=0).fill(5);
+        tracked.mark_dirty(7..7);
+        assert_eq!(tracked.dirty_ranges(), [0..2, 4..10, 18..20]);
+        tracked.clear_dirty();
+        tracked.truncate(8);
+        tracked.push(6);
+        tracked.extend([7, 8]);
+        assert_eq!(tracked.pop(), Some(8));
+        assert_eq!(tracked.into_dirty_ranges(), vec![16..22]);
+        assert_eq!(unsafe { vec.get::<u16>() }, [5, 0, 2, 3, 1, 0, 0, 0, 6, 7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end overflow")]
+    fn test_tracked_range_overflow() {
+        let mut vec = TypeErasedVec::from_vec(vec![0u16; 10]);
+        unsafe { vec.get_mut_tracked::<u16>() }.mark_dirty(..=usize::MAX);
+    }
+
+    #[test]
+    fn test_double_buffered() {
+        let mut buffers = DoubleBuffered::from_front(TypeErasedVec::from_vec(vec![1u8]));
+        assert!(buffers.back().is_empty());
+        unsafe { buffers.back_mut().get_mut::<u8>().push(2) };
+        buffers.swap();
+        assert_eq!(unsafe { buffers.front().get::<u8>() }, [2]);
+        let (front, back) = buffers.into_inner();
+        assert_eq!(unsafe { back.get::<u8>() }, [1]);
+        assert_eq!(front.len(), 1);
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn test_arrow() {
+        let vec = TypeErasedVec::from_scalar_vec(vec![1.0f64, 2.0, 3.0, 4.0]);
+        let ptr = unsafe { vec.get::<f64>() }.as_ptr();
+        let data = vec.into_arrow_array_data();
+        data.validate_full().unwrap();
+        assert_eq!(data.data_type(), &arrow_schema::DataType::Float64);
+        let vec = TypeErasedVec::from_arrow_array_data(data).unwrap();
+        assert_eq!(unsafe { vec.get::<f64>() }.as_ptr(), ptr);
+
+        let data = vec.into_arrow_array_data().slice(1, 2);
+        let vec = TypeErasedVec::from_arrow_array_data(data).unwrap();
+        assert_eq!(unsafe { vec.get::<f64>() }, [2.0, 3.0]);
+
+        let buffer = arrow_buffer::Buffer::from_vec(vec![0u8; 6]);
+        assert_eq!(
+            TypeErasedVec::from_arrow_buffer(buffer, ScalarType::U32).unwrap_err(),
+            Error::LengthNotMultipleOfElement {
+                len: 6,
+                element_size: 4
+            }
+        );
+    }
+
+    #[cfg(feature = "arrow-ffi")]
+    #[test]
+    fn test_export_to_c() {
+        let vec = TypeErasedVec::from_scalar_vec(vec![1u16, 2, 3]);
+        let ptr = unsafe { vec.get::<u16>() }.as_ptr();
+        let (mut array, schema) = vec.export_to_c();
+        assert_eq!(schema.format(), "S");
+        assert_eq!(
+            (array.len(), array.null_count(), array.num_buffers()),
+            (3, 0, 2)
+        );
+        assert!(array.buffer(0).is_null());
+        assert_eq!(array.buffer(1), ptr.cast());
+
+        let release = array.release().unwrap();
+        unsafe { release(&mut array) };
+        assert!(array.is_released());
+    }
+
+    #[cfg(feature = "ash")]
+    #[test]
+    fn test_vk_staging() {
+        use ash::vk;
+
+        let limits = vk::PhysicalDeviceLimits {
+            optimal_buffer_copy_offset_alignment: 4,
+            non_coherent_atom_size: 8,
+            ..Default::default()
+        };
+        let vec = TypeErasedVec::from_pod_vec(vec![1u8, 2, 3]);
+        assert_eq!(vec.vk_staging_size(&limits), 8);
+
+        let mut staging = vec![0xff; 20];
+        let region = vec.copy_to_vk_staging(&mut staging, 1, &limits).unwrap();
+        assert_eq!(
+            (region.offset(), region.size(), region.padded_size()),
+            (8, 3, 8)
+        );
+        assert_eq!(staging[8..16], [1, 2, 3, 0, 0, 0, 0, 0]);
+        assert_eq!(staging[..8], [0xff; 8]);
+        assert_eq!(region.buffer_copy(32).src_offset, 8);
+        assert_eq!(
+            region
+                .mapped_memory_range(vk::DeviceMemory::null(), 64)
+                .offset,
+            72
+        );
+        assert_eq!(
+            vec.copy_to_vk_staging(&mut staging, region.end(), &limits),
+            None
+        );
+    }
+
+    #[cfg(feature = "wgpu")]
+    #[test]
+    fn test_wgpu_buffer() {
+        let (device, queue) = wgpu::Device::noop(&Default::default());
+        let vec = TypeErasedVec::from_pod_vec(vec![1u16, 2, 3]);
+        assert_eq!(vec.wgpu_buffer_size(), 8);
+        let usage = wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST;
+        let buffer = vec.create_wgpu_buffer(&device, None, usage);
+        assert_eq!(buffer.size(), 8);
+        vec.write_wgpu_buffer(&queue, &buffer, 0);
+        queue.submit([]);
+
+        buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+        let view = buffer.get_mapped_range(..).unwrap();
+        let mut read = TypeErasedVec::from_wgpu_buffer_view(&view, ScalarType::U16).unwrap();
+        read.truncate(3);
+        assert_eq!(unsafe { read.get::<u16>() }, [1, 2, 3]);
+    }
+
+    #[cfg(feature = "dlpack")]
+    #[test]
+    fn test_dlpack() {
+        let vec = TypeErasedVec::from_scalar_vec(vec![1i16, 2, 3, 4, 5, 6]);
+        let mut tensor = vec.into_dlpack(&[3, 2]);
+        let dl_tensor = unsafe { &mut tensor.as_mut().dl_tensor };
+        assert_eq!(
+            dl_tensor.dtype,
+            DLDataType {
+                code: 0,
+                bits: 16,
+                lanes: 1
+            }
+        );
+        let mut strides = [2i64, 1];
+        dl_tensor.strides = strides.as_mut_ptr();
+        let tensor = unsafe { DlpackTensor::from_raw(tensor) }.unwrap();
+        assert_eq!(tensor.shape(), [3, 2]);
+        assert_eq!(tensor.scalar_type(), ScalarType::I16);
+        assert_eq!(unsafe { tensor.to_vec().get::<i16>() }, [1, 2, 3, 4, 5, 6]);
+
+        let mut tensor = tensor.into_raw();
+        let mut strides = [1i64, 3];
+        unsafe { tensor.as_mut().dl_tensor.strides = strides.as_mut_ptr() };
+        assert_eq!(
+            unsafe { DlpackTensor::from_raw(tensor) }.unwrap_err(),
+            Error::Unsupported("tensor is not compact and row-major")
+        );
+
+        let mut tensor = TypeErasedVec::from_scalar_vec(vec![0u8; 6]).into_dlpack(&[6]);
+        let mut shape = [1i64 << 62, 4, 6];
+        unsafe {
+            tensor.as_mut().dl_tensor.shape = shape.as_mut_ptr();
+            tensor.as_mut().dl_tensor.ndim = 3;
+        }
+        assert_eq!(
+            unsafe { DlpackTensor::from_raw(tensor) }.unwrap_err(),
+            Error::Unsupported("tensor is too large")
+        );
+        let mut tensor = TypeErasedVec::from_scalar_vec(vec![0u8; 6]).into_dlpack(&[6]);
+        let mut shape = [-2i64, -3];
+        unsafe {
+            tensor.as_mut().dl_tensor.shape = shape.as_mut_ptr();
+            tensor.as_mut().dl_tensor.ndim = 2;
+        }
+        assert_eq!(
+            unsafe { DlpackTensor::from_raw(tensor) }.unwrap_err(),
+            Error::Unsupported("tensor has a negative dimension length")
+        );
+        let mut tensor = TypeErasedVec::from_scalar_vec(vec![0u8; 6]).into_dlpack(&[6]);
+        unsafe { tensor.as_mut().dl_tensor.ndim = -1 };
+        assert_eq!(
+            unsafe { DlpackTensor::from_raw(tensor) }.unwrap_err(),
+            Error::Unsupported("tensor has a negative number of dimensions")
+        );
+        assert_eq!(
+            DLDataType::from_scalar_type(ScalarType::F64).scalar_type(),
+            Some(ScalarType::F64)
+        );
+    }
+
+    #[cfg(feature = "dlpack")]
+    #[test]
+    #[should_panic]
+    fn test_dlpack_negative_shape() {
+        TypeErasedVec::from_scalar_vec(vec![0u8; 6]).into_dlpack(&[-2, -3]);
+    }
+
+    #[cfg(feature = "dlpack")]
+    #[test]
+    #[should_panic]
+    fn test_dlpack_overflowing_shape() {
+        // The product wraps to 6.
+        TypeErasedVec::from_scalar_vec(vec![0u8; 6]).into_dlpack(&[6, 7, 0x6db6_db6d_b6db_6db7]);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_array_view() {
+        let mut vec = TypeErasedVec::from_vec((0..24).collect::<Vec<i32>>());
+        let view = unsafe { vec.as_array_view::<i32>(&[2, 3, 4]) };
+        assert_eq!(view[[1, 2, 3]], 23);
+        let mut view = unsafe { vec.as_array_view_mut::<i32>(&[4, 6]) };
+        view[[0, 1]] = -1;
+        assert_eq!(unsafe { vec.get::<i32>() }[1], -1);
+
+        let transposed = ndarray::Array::from_shape_vec((2, 3), vec![1, 2, 3, 4, 5, 6])
+            .unwrap()
+            .reversed_axes();
+        let shaped = ShapedTypeErasedVec::from_array(transposed);
+        assert_eq!(shaped.shape(), [3, 2]);
+        assert_eq!(
+            unsafe { shaped.as_erased().get::<i32>() },
+            [1, 4, 2, 5, 3, 6]
+        );
+
+        let sliced = ndarray::Array::from_iter(0..10u32).slice_move(ndarray::s![3..6]);
+        let shaped = ShapedTypeErasedVec::from_scalar_array(sliced);
+        assert_eq!(shaped.as_erased().scalar_type(), Some(ScalarType::U32));
+        assert_eq!(
+            unsafe { shaped.view::<u32>() }.as_slice(),
+            Some(&[3, 4, 5][..])
+        );
+    }
+
+    #[cfg(feature = "pyo3")]
+    #[test]
+    fn test_py_buffer() {
+        use pyo3::{prelude::*, types::PyDict};
+
+        Python::initialize();
+        Python::attach(|py| {
+            let array = py
+                .eval(c"__import__('array').array('h', [1, -2, 3])", None, None)
+                .unwrap();
+            let vec = TypeErasedVec::from_py_buffer(&array).unwrap();
+            assert_eq!(vec.scalar_type(), Some(ScalarType::I16));
+            assert_eq!(unsafe { vec.get::<i16>() }, [1, -2, 3]);
+            let bytes = py.eval(c"b'ab'", None, None).unwrap();
+            let vec = TypeErasedVec::from_py_buffer(&bytes).unwrap();
+            assert_eq!(unsafe { vec.get::<u8>() }, b"ab");
+            let text = py.eval(c"'ab'", None, None).unwrap();
+            assert!(TypeErasedVec::from_py_buffer(&text).is_err());
+
+            let buffer = Py::new(
+                py,
+                PyErasedBuffer::new(TypeErasedVec::from_scalar_vec(vec![0.5f64, 2.0])),
+            )
+            .unwrap();
+            let locals = PyDict::new(py);
+            locals.set_item("buffer", buffer).unwrap();
+            let view = py.eval(c"memoryview(buffer)", None, Some(&locals)).unwrap();
+            assert_eq!(
+                view.call_method0("tolist")
+                    .unwrap()
+                    .extract::<Vec<f64>>()
+                    .unwrap(),
+                [0.5, 2.0]
+            );
+            assert!(view.getattr("readonly").unwrap().is_truthy().unwrap());
+            let vec = TypeErasedVec::from_py_buffer(&view).unwrap();
+            assert_eq!(unsafe { vec.get::<f64>() }, [0.5, 2.0]);
+            view.call_method0("release").unwrap();
+            assert!(py
+                .eval(c"len(buffer)", None, Some(&locals))
+                .unwrap()
+                .eq(2)
+                .unwrap());
+        });
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_ffi() {
+        use ffi::*;
+
+        assert!(tev_vec_new(4, 3).is_null());
+        let vec = tev_vec_new(3, 2);
+        unsafe {
+            assert!(tev_vec_push_bytes(
+                vec,
+                [1u8, 2, 3, 4, 5, 6, 7, 8].as_ptr().cast(),
+                8
+            ));
+            assert!(!tev_vec_push_bytes(vec, [1u8, 2, 3].as_ptr().cast(), 3));
+            assert!(tev_vec_push_bytes(vec, std::ptr::null(), 0));
+            assert_eq!(tev_vec_len(vec), 2);
+            let parts = tev_vec_raw_parts(vec);
+            assert_eq!((parts.element_size, parts.element_align), (4, 2));
+            assert_eq!(parts.ptr, tev_vec_ptr(vec));
+            assert_eq!(
+                std::slice::from_raw_parts(parts.ptr.cast::<u16>(), 4),
+                [
+                    u16::from_ne_bytes([1, 2]),
+                    u16::from_ne_bytes([3, 4]),
+                    u16::from_ne_bytes([5, 6]),
+                    u16::from_ne_bytes([7, 8])
+                ]
+            );
+            let other = tev_vec_new(8, 2);
+            #[cfg(feature = "type-id")]
+            assert!(!(*vec).same_element_type(&*other));
+            tev_vec_free(other);
+            tev_vec_free(vec);
+
+            let vec = Box::into_raw(Box::new(TypeErasedVec::from_pod_vec(vec![1u32])));
+            assert!(tev_vec_push_bytes(
+                vec,
+                2u32.to_ne_bytes().as_ptr().cast(),
+                4
+            ));
+            assert_eq!(Box::from_raw(vec).get::<u32>(), [1, 2]);
+            tev_vec_free(std::ptr::null_mut());
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mmap() {
+        use std::io::Write;
+
+        let path =
+            std::env::temp_dir().join(format!("type_erased_vec_mmap_{}", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        for value in [0u32, 1, 2, 3] {
+            file.write_all(&value.to_ne_bytes()).unwrap();
+        }
+        let file = std::fs::File::open(&path).unwrap();
+        let mmap = unsafe { memmap2::Mmap::map(&file) }.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let slice = TypeErasedSlice::from_mmap(&mmap, 4..16, ScalarType::U32.vtable()).unwrap();
+        assert_eq!(unsafe { slice.get::<u32>() }, [1, 2, 3]);
+        assert_eq!(
+            TypeErasedSlice::from_mmap(&mmap, 2..14, ScalarType::U32.vtable()).unwrap_err(),
+            Error::Misaligned { align: 4 }
+        );
+        assert_eq!(
+            TypeErasedSlice::from_mmap(&mmap, 0..6, ScalarType::U32.vtable()).unwrap_err(),
+            Error::LengthNotMultipleOfElement {
+                len: 6,
+                element_size: 4
+            }
+        );
+        assert_eq!(
+            TypeErasedSlice::from_mmap(&mmap, 0..16, TypeErasedVec::new::<u32>().vtable())
+                .unwrap_err(),
+            Error::NotPod
+        );
+    }
+
+    #[cfg(feature = "allocator-api")]
+    #[test]
+    fn test_foreign_buffer() {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let released = Arc::new(AtomicUsize::new(0));
+        let adopt = |strings: Vec<String>| {
+            let mut strings = ManuallyDrop::new(strings);
+            let (ptr, len, cap) = (strings.as_mut_ptr(), strings.len(), strings.capacity());
+            let released = released.clone();
+            unsafe {
+                TypeErasedVec::from_foreign_buffer(
+                    ptr.cast(),
+                    len,
+                    cap,
+                    TypeErasedVec::new::<String>().vtable(),
+                    move |ptr| {
+                        released.fetch_add(1, Ordering::Relaxed);
+                        drop(Vec::from_raw_parts(ptr.cast::<String>(), 0, cap));
+                    },
+                )
+            }
+        };
+
+        let mut vec = adopt(vec![String::from("a")]);
+        assert_eq!(unsafe { vec.get::<String>() }, ["a"]);
+        unsafe { vec.get_mut::<String>() }.push(String::from("b"));
+        assert_eq!(released.load(Ordering::Relaxed), 1);
+        assert!(vec.allocator().is_released());
+        assert_eq!(unsafe { vec.get::<String>() }, ["a", "b"]);
+        drop(vec);
+        assert_eq!(released.load(Ordering::Relaxed), 1);
+
+        drop(adopt(vec![String::from("c")]));
+        drop(adopt(Vec::new()));
+        assert_eq!(released.load(Ordering::Relaxed), 3);
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_bytes() {
+        let bytes = TypeErasedVec::from_scalar_vec(vec![1u32, 2]).into_bytes();
+        let slice = TypeErasedSlice::try_from_bytes(&bytes, ScalarType::U32.vtable()).unwrap();
+        assert_eq!(unsafe { slice.get::<u32>() }, [1, 2]);
+        assert_eq!(
+            TypeErasedSlice::try_from_bytes(&bytes[1..], ScalarType::U32.vtable()).unwrap_err(),
+            Error::LengthNotMultipleOfElement {
+                len: 7,
+                element_size: 4
+            }
+        );
+        let vec = TypeErasedVec::from_bytes(&bytes, ScalarType::U32.vtable()).unwrap();
+        assert_eq!(unsafe { vec.get::<u32>() }, [1, 2]);
+
+        let bytes = TypeErasedVec::from_scalar_vec(vec![1u8, 2, 3]).into_bytes_mut();
+        let ptr = bytes.as_ptr();
+        let vec = TypeErasedVec::from_bytes_mut(bytes, ScalarType::U8.vtable()).unwrap();
+        assert_eq!(unsafe { vec.get::<u8>() }, [1, 2, 3]);
+        assert_eq!(unsafe { vec.get::<u8>() }.as_ptr(), ptr);
+        let bytes = TypeErasedVec::from_scalar_vec(vec![1u16]).into_bytes_mut();
+        assert_eq!(&bytes[..], 1u16.to_ne_bytes());
+        assert_eq!(
+            TypeErasedVec::from_bytes_mut(bytes, TypeErasedVec::new::<u16>().vtable()).unwrap_err(),
+            Error::NotPod
+        );
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_ring_erased_buffer() {
+        let mut ring = RingErasedBuffer::new::<[u8; 2]>(4);
+        assert!(!ring.retire());
+        assert_eq!(ring.push_bytes(&[1, 1, 2, 2, 3, 3]), Some(0..3));
+        ring.end_frame();
+        assert_eq!(ring.push_bytes(&[4, 4]), Some(3..4));
+        assert_eq!(ring.push_bytes(&[5, 5]), None);
+        ring.end_frame();
+        assert_eq!(ring.frames_in_flight(), 2);
+        assert!(ring.retire());
+        assert_eq!(ring.used(), 1);
+        assert_eq!(ring.push(&[[5u8, 5], [6, 6]]), Some(0..2));
+        assert_eq!(ring.push(&[[7u8, 7]]), Some(2..3));
+        assert_eq!(ring.push(&[[8u8, 8]]), None);
+        assert_eq!(
+            ring.as_erased().as_bytes().unwrap(),
+            [5, 5, 6, 6, 7, 7, 4, 4]
+        );
+        ring.end_frame();
+        assert!(ring.retire());
+        assert!(ring.retire());
+        assert_eq!(ring.used(), 0);
+        assert_eq!(ring.capacity(), 4);
+    }
+
+    #[test]
+    fn test_panic_diagnostics() {
+        let panic_message =
+            |result: std::thread::Result<()>| *result.unwrap_err().downcast::<String>().unwrap();
+
         let mut vec = TypeErasedVec::new::<i32>();
-        let mut vec_mut = unsafe { vec.get_mut::<i32>() };
-        for i in 0..10 {
-            vec_mut.push(i);
+        let leaked_line = line!() + 1;
+        forget(unsafe { vec.get_mut::<i32>() });
+        let leaked_at = format!("{}:{leaked_line}:", file!());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            vec.len();
+        }));
+        let message = panic_message(result);
+        assert!(message.starts_with("TypeErasedVec of i32 is leaked"));
+        assert!(message.contains(&leaked_at));
+        // Borrowing the leaked vec again still reports where it was leaked.
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            drop(unsafe { vec.get_mut::<i32>() });
+        }));
+        assert!(panic_message(result).contains(&leaked_at));
+
+        let vec = TypeErasedVec::new::<String>();
+        let result = std::panic::catch_unwind(|| vec.clone());
+        assert_eq!(
+            panic_message(result.map(drop)),
+            "TypeErasedVec of alloc::string::String was not constructed with from_vec_clone or from_pod_vec"
+        );
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_checked_apis() {
+        let mut vec = TypeErasedVec::from_pod_vec(vec![[1u16, 2]]);
+        #[cfg(feature = "type-id")]
+        {
+            assert_eq!(vec.try_as_slice::<[u16; 2]>(), Ok(&[[1, 2]][..]));
+            assert_eq!(
+                vec.try_as_slice::<u16>(),
+                Err(Error::TypeMismatch {
+                    element: "[u16; 2]",
+                    requested: "u16"
+                })
+            );
         }
-        drop(vec_mut);
-        let vec_ref = unsafe { vec.get::<i32>() };
-        assert_eq!((0..10).collect::<Vec<_>>(), *vec_ref);
+        assert_eq!(vec.try_cast_slice::<u16>(), Ok(&[1, 2][..]));
+        assert!(matches!(
+            vec.try_cast_slice::<[u8; 3]>(),
+            Err(Error::LengthNotMultipleOfElement { len: 4, .. })
+        ));
+        assert_eq!(
+            vec.try_extend_from_byte_slice(&[0; 3]),
+            Err(Error::LengthNotMultipleOfElement {
+                len: 3,
+                element_size: 4
+            })
+        );
+        vec.try_extend_from_byte_slice(&[0; 4]).unwrap();
+        vec.try_cast_slice_mut::<u16>().unwrap()[3] = 7;
+        assert_eq!(unsafe { vec.get::<[u16; 2]>() }, [[1, 2], [0, 7]]);
+
+        let vec = TypeErasedVec::new::<String>();
+        assert_eq!(vec.try_as_bytes(), Err(Error::NotPod));
+        assert_eq!(
+            Error::from(LeakedError).to_string(),
+            "TypeErasedVec is leaked"
+        );
+    }
+
+    #[test]
+    fn test_element_count() {
+        assert_eq!(element_count(12, 4), Ok(3));
+        assert_eq!(element_count(0, 4), Ok(0));
+        assert_eq!(element_count(0, 0), Ok(0));
+        assert_eq!(
+            element_count(6, 4),
+            Err(Error::LengthNotMultipleOfElement {
+                len: 6,
+                element_size: 4
+            })
+        );
+        assert!(element_count(1, 0).is_err());
+        #[cfg(feature = "opencl")]
+        assert_eq!(
+            Error::from(opencl3::error_codes::ClError(-5)),
+            Error::OpenCl(-5)
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        let vec = TypeErasedVec::from_vec_debug(vec![1i32, 2, 3]);
+        assert!(format!("{vec:?}").contains("elements: [1, 2, 3]"));
+
+        let vec = TypeErasedVec::from_vec_debug((0..100).collect::<Vec<i32>>());
+        let expected = format!(
+            "elements: [{}, ..]",
+            (0..32)
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        assert!(format!("{vec:?}").contains(&expected));
+
+        assert!(!format!("{:?}", TypeErasedVec::new::<i32>()).contains("elements"));
+    }
+
+    #[test]
+    fn test_drop() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        drop(TypeErasedVec::from_vec(vec![counter.clone(); 3]));
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_from_vec_with_vtable() {
+        static VTABLE: TypedVTable<String> = TypedVTable::new().with_clone().with_debug();
+
+        let vec = TypeErasedVec::from_vec_with_vtable(vec![String::from("a")], &VTABLE);
+        assert!(vec.vtable().is_clone());
+        assert!(vec.vtable().is_debug());
+        assert!(!vec.vtable().is_hash());
+        assert_eq!(vec.vtable().type_name(), "alloc::string::String");
+        assert_eq!(unsafe { vec.clone().get::<String>() }, ["a"]);
+        assert!(format!("{vec:?}").contains(r#"elements: ["a"]"#));
+    }
+
+    #[test]
+    fn test_raw_parts() {
+        use std::rc::Rc;
+
+        let mut vec = TypeErasedVec::with_capacity_aligned::<u16>(3, 64);
+        unsafe {
+            vec.push_erased_unchecked(TypeErasedBox::new(1u16));
+            vec.push_erased_unchecked(TypeErasedBox::new(2u16));
+        }
+        let (ptr, len, cap, layout, vtable, alloc) = vec.into_raw_parts();
+        assert_eq!((len, cap), (2, 3));
+        assert_eq!(layout, Layout::from_size_align(2, 64).unwrap());
+        assert!(ptr.addr().is_multiple_of(64));
+        let vec = unsafe { TypeErasedVec::from_raw_parts(ptr, len, cap, layout, vtable, alloc) };
+        assert_eq!(vec.buffer_align(), 64);
+        assert_eq!(unsafe { vec.get::<u16>() }, [1, 2]);
+
+        let counter = Rc::new(());
+        let vec = TypeErasedVec::from_vec(vec![counter.clone(); 2]);
+        let (ptr, len, cap, layout, vtable, _) = vec.into_raw_parts();
+        assert_eq!(Rc::strong_count(&counter), 3);
+        unsafe {
+            vtable.drop_in_place(ptr, len);
+            std::alloc::dealloc(ptr, raw::array_layout(layout, cap).unwrap());
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_leak() {
+        let (bytes, layout, len) = TypeErasedVec::from_scalar_vec(vec![1u32, 2]).leak();
+        assert_eq!(bytes, [1u32, 2].map(u32::to_ne_bytes).concat());
+        assert_eq!((layout, len), (Layout::new::<u32>(), 2));
+        // Reclaimed, so the test doesn't leak.
+        drop(unsafe { Vec::from_raw_parts(bytes.as_mut_ptr().cast::<u32>(), 2, 2) });
+
+        let strings =
+            unsafe { TypeErasedVec::from_vec(vec![String::from("a")]).leak_typed::<String>() };
+        strings[0].push('b');
+        assert_eq!(strings, ["ab"]);
+        drop(unsafe { Vec::from_raw_parts(strings.as_mut_ptr(), 1, 1) });
+    }
+
+    #[test]
+    fn test_byte_writer() {
+        let mut vec = TypeErasedVec::from_scalar_vec(vec![1i8]);
+        let mut writer = vec.byte_writer().unwrap();
+        writer.write_all(&[2, 3]).unwrap();
+        assert_eq!(writer.write(&[4]).unwrap(), 1);
+        assert_eq!(writer.as_erased().len(), 4);
+        assert_eq!(unsafe { vec.get::<i8>() }, [1, 2, 3, 4]);
+
+        assert!(TypeErasedVec::new::<u8>().byte_writer().is_none());
+        assert!(TypeErasedVec::from_scalar_vec(vec![0u16])
+            .byte_writer()
+            .is_none());
+    }
+
+    #[test]
+    fn test_gather_scatter() {
+        let vec = TypeErasedVec::from_scalar_vec(vec![1.0f64, 2.0, 3.0]);
+        let gathered = vec.gather(&[2, 2, 0]);
+        assert_eq!(unsafe { gathered.get::<f64>() }, [3.0, 3.0, 1.0]);
+        assert!(gathered.gather(&[]).is_empty());
+
+        let mut vec = TypeErasedVec::from_scalar_vec(vec![0u16; 4]);
+        let src = TypeErasedVec::from_scalar_vec(vec![5u16, 6, 7]);
+        vec.scatter_from(&[3, 1, 3], src.as_erased_slice());
+        assert_eq!(unsafe { vec.get::<u16>() }, [0, 6, 0, 7]);
+    }
+
+    #[test]
+    fn test_interleave() {
+        let positions = TypeErasedVec::from_scalar_vec(vec![1u32, 2]);
+        let ids = TypeErasedVec::from_scalar_vec(vec![3u8, 4]);
+        let layout = BufferLayout::packed(&[positions.vtable(), ids.vtable()]);
+        assert_eq!(layout.stride(), 8);
+        assert_eq!(layout.attributes()[1].offset, 4);
+        let vertices = TypeErasedVec::interleave(
+            &[positions.as_erased_slice(), ids.as_erased_slice()],
+            &layout,
+        );
+        let mut expected = Vec::new();
+        for (position, id) in [(1u32, 3u8), (2, 4)] {
+            expected.extend(position.to_ne_bytes());
+            expected.extend([id, 0, 0, 0]);
+        }
+        assert_eq!(unsafe { vertices.get::<u8>() }, expected);
+
+        let columns = TypeErasedVec::deinterleave(vertices.as_bytes().unwrap(), &layout);
+        assert_eq!(unsafe { columns[0].get::<u32>() }, [1, 2]);
+        assert_eq!(unsafe { columns[1].get::<u8>() }, [3, 4]);
+
+        // Padding between the attributes and at the end.
+        let weights = TypeErasedVec::from_scalar_vec(vec![0.5f32, 1.5, 2.5]);
+        let joints = TypeErasedVec::from_scalar_vec(vec![5u16, 6, 7]);
+        let layout = BufferLayout::new(12)
+            .with_attribute(0, weights.vtable())
+            .with_attribute(8, joints.vtable());
+        let vertices = TypeErasedVec::interleave(
+            &[weights.as_erased_slice(), joints.as_erased_slice()],
+            &layout,
+        );
+        assert_eq!(vertices.len(), 36);
+        assert_eq!(unsafe { &vertices.get::<u8>()[4..8] }, [0; 4]);
+        let columns = TypeErasedVec::deinterleave(vertices.as_bytes().unwrap(), &layout);
+        assert_eq!(unsafe { columns[0].get::<f32>() }, [0.5, 1.5, 2.5]);
+        assert_eq!(unsafe { columns[1].get::<u16>() }, [5, 6, 7]);
+    }
+
+    #[test]
+    fn test_packed_layout() {
+        let layout = BufferLayout::packed(&[
+            ScalarType::U8.vtable(),
+            ScalarType::F64.vtable(),
+            ScalarType::U16.vtable(),
+        ]);
+        let offsets: Vec<_> = layout.attributes().iter().map(|a| a.offset).collect();
+        assert_eq!(offsets, [0, 8, 16]);
+        assert_eq!(layout.stride(), 24);
+        assert_eq!(layout.attributes()[2].range(1), 40..42);
+        assert_eq!(layout.vertex_count(50), 2);
+        assert_eq!(BufferLayout::packed(&[]).stride(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_attribute_offset_overflow() {
+        BufferLayout::new(8).with_attribute(usize::MAX, ScalarType::U16.vtable());
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_interleave_zero_sized() {
+        let ids = TypeErasedVec::from_scalar_vec(vec![1u8, 2]);
+        let markers = TypeErasedVec::from_pod_vec(vec![(); 2]);
+        let layout = BufferLayout::new(1)
+            .with_attribute(0, ids.vtable())
+            .with_attribute(1, markers.vtable());
+        let vertices =
+            TypeErasedVec::interleave(&[ids.as_erased_slice(), markers.as_erased_slice()], &layout);
+        let columns = TypeErasedVec::deinterleave(vertices.as_bytes().unwrap(), &layout);
+        assert_eq!(unsafe { columns[0].get::<u8>() }, [1, 2]);
+        assert_eq!(columns[1].len(), 2);
+        let columns = [columns[0].as_erased_slice(), columns[1].as_erased_slice()];
+        assert_eq!(TypeErasedVec::interleave(&columns, &layout).len(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_deinterleave_partial_vertex() {
+        let layout = BufferLayout::new(4).with_attribute(0, ScalarType::U16.vtable());
+        TypeErasedVec::deinterleave(&[0; 6], &layout);
+    }
+
+    #[test]
+    fn test_convert_to() {
+        let vec = TypeErasedVec::from_scalar_vec(vec![1.5f64, -2.5, 1e10]);
+        let floats = vec.convert_to(ScalarType::F32);
+        assert_eq!(floats.scalar_type(), Some(ScalarType::F32));
+        assert_eq!(unsafe { floats.get::<f32>() }, [1.5, -2.5, 1e10]);
+        let ints = vec.convert_to(ScalarType::I32);
+        assert_eq!(unsafe { ints.get::<i32>() }, [1, -2, i32::MAX]);
+
+        let indices = TypeErasedVec::from_scalar_vec(vec![300u16]);
+        assert_eq!(
+            unsafe { indices.convert_to(ScalarType::U8).get::<u8>() },
+            [44]
+        );
+        assert!(TypeErasedVec::new_scalar(ScalarType::I8)
+            .convert_to(ScalarType::F64)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_convert_normalized_to() {
+        let vec = TypeErasedVec::from_scalar_vec(vec![i16::MIN, -16384, 0, i16::MAX]);
+        let floats = vec.convert_normalized_to(ScalarType::F64);
+        assert_eq!(
+            unsafe { floats.get::<f64>() },
+            [-1.0, -16384.0 / 32767.0, 0.0, 1.0]
+        );
+        assert_eq!(
+            unsafe { floats.convert_normalized_to(ScalarType::I16).get::<i16>() },
+            [-32767, -16384, 0, 32767]
+        );
+
+        let weights = TypeErasedVec::from_scalar_vec(vec![-0.5f32, 0.5, 2.0, f32::NAN]);
+        assert_eq!(
+            unsafe { weights.convert_normalized_to(ScalarType::U8).get::<u8>() },
+            [0, 128, 255, 0]
+        );
+        assert_eq!(
+            unsafe {
+                TypeErasedVec::from_scalar_vec(vec![255u8])
+                    .convert_normalized_to(ScalarType::U16)
+                    .get::<u16>()
+            },
+            [65535]
+        );
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_half() {
+        use half::{bf16, f16};
+
+        assert_eq!(ScalarType::F16.to_string(), "f16");
+        assert!(ScalarType::BF16.is_float() && ScalarType::BF16.is_signed());
+        let vec = TypeErasedVec::from_scalar_vec(vec![0.5f32, -3.0, 65536.0]);
+        let halves = vec.convert_to(ScalarType::F16);
+        assert_eq!(halves.scalar_type(), Some(ScalarType::F16));
+        assert_eq!(
+            unsafe { halves.get::<f16>() },
+            [f16::from_f32(0.5), f16::from_f32(-3.0), f16::INFINITY]
+        );
+        let bfloats = halves.convert_to(ScalarType::BF16);
+        assert_eq!(
+            unsafe { bfloats.get::<bf16>() }[..2],
+            [bf16::from_f32(0.5), bf16::from_f32(-3.0)]
+        );
+        assert_eq!(
+            unsafe { bfloats.convert_to(ScalarType::I32).get::<i32>() },
+            [0, -3, i32::MAX]
+        );
+        assert_eq!(
+            unsafe { halves.convert_normalized_to(ScalarType::U8).get::<u8>() },
+            [128, 0, 255]
+        );
+    }
+
+    #[test]
+    fn test_kernels() {
+        // Long enough for whole blocks and a rest with the `simd` feature.
+        let values: Vec<u32> = (0..37).map(|i| i * 0x0102_0304).collect();
+        let mut vec = TypeErasedVec::from_scalar_vec(values.clone());
+        vec.swap_bytes_in_place(4);
+        let swapped: Vec<u32> = values.iter().map(|value| value.swap_bytes()).collect();
+        assert_eq!(unsafe { vec.get::<u32>() }, swapped);
+        vec.swap_bytes_in_place(2);
+        let swapped: Vec<u32> = values.iter().map(|value| value.rotate_left(16)).collect();
+        assert_eq!(unsafe { vec.get::<u32>() }, swapped);
+
+        let converted = TypeErasedVec::from_scalar_vec(values.clone()).convert_to(ScalarType::F64);
+        let expected: Vec<f64> = values.iter().map(|&value| value as f64).collect();
+        assert_eq!(unsafe { converted.get::<f64>() }, expected);
+
+        let mut vec = TypeErasedVec::from_scalar_vec(vec![0u16; 21]);
+        vec.fill_bytes(&9u16.to_ne_bytes());
+        assert_eq!(unsafe { vec.get::<u16>() }, [9; 21]);
+        #[cfg(feature = "bytemuck")]
+        {
+            let mut vec = TypeErasedVec::from_pod_vec(vec![[0u8; 3]; 13]);
+            vec.fill_bytes(&[1, 2, 3]);
+            assert_eq!(unsafe { vec.get::<[u8; 3]>() }, [[1, 2, 3]; 13]);
+        }
+    }
+
+    #[test]
+    fn test_reductions() {
+        let vec = TypeErasedVec::from_scalar_vec(vec![2.5f32, f32::NAN, -1.0, 4.0, -1.0]);
+        assert!(vec.sum().is_nan());
+        let min = vec.min().unwrap();
+        assert_eq!(min.as_ptr(), vec.element(2).unwrap().as_ptr());
+        assert_eq!(unsafe { min.downcast_ref_unchecked::<f32>() }, &-1.0);
+        assert_eq!(
+            unsafe { vec.max().unwrap().downcast_ref_unchecked::<f32>() },
+            &4.0
+        );
+
+        let vec = TypeErasedVec::from_scalar_vec(vec![-3i64, 10, 5]);
+        assert_eq!(vec.sum(), 12.0);
+        assert_eq!(vec.mean(), Some(4.0));
+        let empty = TypeErasedVec::new_scalar(ScalarType::U16);
+        assert_eq!(empty.sum(), 0.0);
+        assert_eq!(empty.mean(), None);
+        assert!(empty.min().is_none() && empty.max().is_none());
+    }
+
+    #[test]
+    fn test_argsort() {
+        let vec = TypeErasedVec::from_scalar_vec(vec![2.0f64, f64::NAN, -1.0, 2.0, 0.5]);
+        assert_eq!(vec.argsort(), [2, 4, 0, 3, 1]);
+        let vec = TypeErasedVec::from_scalar_vec(vec![7i8, -7, 0]);
+        assert_eq!(vec.argsort(), [1, 2, 0]);
+        assert!(TypeErasedVec::new_scalar(ScalarType::U32)
+            .argsort()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_filter() {
+        let vec = TypeErasedVec::from_scalar_vec(vec![1u16, 2, 3, 4, 5, 6]);
+        let mask = [false, true, true, false, false, true];
+        let filtered = vec.filter(&mask);
+        assert_eq!(unsafe { filtered.get::<u16>() }, [2, 3, 6]);
+        assert_eq!(filtered.capacity(), 3);
+        assert!(vec.filter(&[false; 6]).is_empty());
+        assert_eq!(
+            unsafe { vec.filter(&[true; 6]).get::<u16>() },
+            [1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn test_nullable() {
+        let vec = TypeErasedVec::from_scalar_vec((0..9u32).collect::<Vec<_>>());
+        let mut vec = NullableTypeErasedVec::new(vec);
+        assert_eq!(vec.validity_bitmap(), [0xff, 0x01]);
+        vec.push_null();
+        vec.push_bytes(&10u32.to_ne_bytes());
+        assert_eq!((vec.len(), vec.null_count()), (11, 1));
+        assert_eq!(vec.validity_bitmap(), [0xff, 0x05]);
+        assert!(vec.element(9).is_none());
+        assert_eq!(unsafe { vec.as_erased().get::<u32>() }[9], 0);
+        assert_eq!(
+            unsafe { vec.element(10).unwrap().downcast_ref_unchecked::<u32>() },
+            &10
+        );
+        assert_eq!(
+            unsafe { vec.compact().get::<u32>() },
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 10]
+        );
+
+        let vec = TypeErasedVec::from_scalar_vec(vec![1i8, 2, 3]);
+        let vec = NullableTypeErasedVec::from_mask(vec, &[false, true, false]);
+        assert_eq!(vec.null_count(), 2);
+        assert_eq!(unsafe { vec.compact().get::<i8>() }, [2]);
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn test_nullable_arrow() {
+        let vec = TypeErasedVec::from_scalar_vec(vec![1.0f32, 0.0, 3.0, 4.0]);
+        let ptr = unsafe { vec.get::<f32>() }.as_ptr();
+        let vec = NullableTypeErasedVec::from_mask(vec, &[true, false, true, true]);
+        let data = vec.into_arrow_array_data();
+        data.validate_full().unwrap();
+        assert_eq!(data.null_count(), 1);
+        assert!(TypeErasedVec::from_arrow_array_data(data.clone()).is_err());
+
+        let vec = NullableTypeErasedVec::from_arrow_array_data(data.slice(1, 3)).unwrap();
+        assert_eq!(vec.validity_bitmap(), [0b110]);
+        assert_eq!(vec.null_count(), 1);
+        let vec = NullableTypeErasedVec::from_arrow_array_data(data).unwrap();
+        assert_eq!(unsafe { vec.as_erased().get::<f32>() }.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_chunked() {
+        let mut vec = ChunkedTypeErasedVec::new(ScalarType::U32.vtable(), 3);
+        assert!(vec.is_empty() && vec.chunks().is_empty());
+        vec.push_bytes(&1u32.to_ne_bytes());
+        let first = vec.element(0).unwrap().as_ptr();
+        let bytes: Vec<u8> = (2..=7u32).flat_map(u32::to_ne_bytes).collect();
+        vec.extend_from_byte_slice(&bytes);
+        assert_eq!(vec.len(), 7);
+        assert_eq!(vec.element(0).unwrap().as_ptr(), first);
+        assert_eq!(
+            vec.chunks()
+                .iter()
+                .map(TypeErasedVec::len)
+                .collect::<Vec<_>>(),
+            [3, 3, 1]
+        );
+        assert!(vec.chunks().iter().all(|chunk| chunk.capacity() == 3));
+        assert_eq!(
+            unsafe { vec.element(4).unwrap().downcast_ref_unchecked::<u32>() },
+            &5
+        );
+        assert!(vec.element(7).is_none());
+        assert_eq!(
+            unsafe { vec.to_contiguous().get::<u32>() },
+            [1, 2, 3, 4, 5, 6, 7]
+        );
+        vec.clear();
+        assert_eq!(vec.len(), 0);
     }
 }
@@ -0,0 +1,40 @@
+//! Views of memory-mapped files with `memmap2`.
+
+use std::ops::Range;
+
+use memmap2::Mmap;
+
+use crate::{ElementVTable, Error, TypeErasedSlice};
+
+impl<'a> TypeErasedSlice<'a> {
+    /// Views the bytes of `mmap` in `range` as elements described by `vtable`, without copying them into memory.
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    ///
+    /// use memmap2::Mmap;
+    /// use type_erased_vec::{ScalarType, TypeErasedSlice};
+    ///
+    /// let file = File::open("positions.bin")?;
+    /// let mmap = unsafe { Mmap::map(&file)? };
+    /// let positions = TypeErasedSlice::from_mmap(&mmap, 64..mmap.len(), ScalarType::F32.vtable())?;
+    /// let first = unsafe { positions.get::<f32>() }.first();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// Fails if the element type is not known to be plain-old-data, as any bytes may be in the file, if it's zero sized,
+    /// if the length of `range` is not a multiple of the element size, or if the region is not aligned to the element alignment.
+    /// See [TypeErasedSlice::try_from_bytes].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    #[track_caller]
+    pub fn from_mmap(
+        mmap: &'a Mmap,
+        range: Range<usize>,
+        vtable: &'static ElementVTable,
+    ) -> Result<Self, Error> {
+        Self::try_from_bytes(&mmap[range], vtable)
+    }
+}
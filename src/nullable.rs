@@ -0,0 +1,177 @@
+//! Vecs with missing values.
+
+use crate::{missing_capability, Allocator, ErasedRef, Global, TypeErasedVec};
+
+/// A [TypeErasedVec] of plain-old-data elements, any of which may be null, like a column of a dataset with missing values.
+///
+/// Which elements are valid is recorded in a validity bitmap laid out like Arrow's: bit `i % 8` of byte `i / 8`
+/// is set if element `i` is valid. Null elements are zeroed when pushed. With the `arrow` feature, it converts to
+/// and from nullable primitive Arrow arrays.
+///
+/// ```
+/// use type_erased_vec::{NullableTypeErasedVec, ScalarType, TypeErasedVec};
+///
+/// let mut ages = NullableTypeErasedVec::new(TypeErasedVec::new_scalar(ScalarType::U8));
+/// ages.push_bytes(&[31]);
+/// ages.push_null();
+/// ages.push_bytes(&[58]);
+/// assert_eq!(ages.null_count(), 1);
+/// assert!(!ages.is_valid(1));
+/// assert_eq!(unsafe { ages.compact().get::<u8>() }, [31, 58]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NullableTypeErasedVec<A: Allocator = Global> {
+    vec: TypeErasedVec<A>,
+    validity: Vec<u8>,
+    null_count: usize,
+}
+
+impl<A: Allocator> NullableTypeErasedVec<A> {
+    /// Wraps `vec`, with all its elements valid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `vec` was not constructed with [TypeErasedVec::from_pod_vec], or if `vec` is leaked.
+    #[track_caller]
+    pub fn new(vec: TypeErasedVec<A>) -> Self {
+        let len = vec.len();
+        Self::from_validity_bitmap(vec, all_valid(len), 0)
+    }
+
+    /// Wraps `vec`, with the elements whose entries in `valid` are `false` null. Their values are kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `vec` was not constructed with [TypeErasedVec::from_pod_vec], if `valid` and `vec` have different
+    /// lengths, or if `vec` is leaked.
+    #[track_caller]
+    pub fn from_mask(vec: TypeErasedVec<A>, valid: &[bool]) -> Self {
+        assert_eq!(valid.len(), vec.len(), "mask and vec lengths differ");
+        let mut validity = vec![0; valid.len().div_ceil(8)];
+        for (index, _) in valid.iter().enumerate().filter(|(_, &valid)| valid) {
+            validity[index / 8] |= 1 << (index % 8);
+        }
+        let null_count = valid.iter().filter(|&&valid| !valid).count();
+        Self::from_validity_bitmap(vec, validity, null_count)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `vec` was not constructed with [TypeErasedVec::from_pod_vec], or if `vec` is leaked.
+    #[track_caller]
+    pub(crate) fn from_validity_bitmap(
+        vec: TypeErasedVec<A>,
+        validity: Vec<u8>,
+        null_count: usize,
+    ) -> Self {
+        if !vec.vtable.pod {
+            missing_capability(vec.vtable, "from_pod_vec");
+        }
+        debug_assert_eq!(validity.len(), vec.len().div_ceil(8));
+        NullableTypeErasedVec {
+            vec,
+            validity,
+            null_count,
+        }
+    }
+
+    /// Returns the number of elements, including nulls.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the number of null elements.
+    pub fn null_count(&self) -> usize {
+        self.null_count
+    }
+
+    /// Returns if the element at `index` is valid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn is_valid(&self, index: usize) -> bool {
+        assert!(
+            index < self.len(),
+            "index {index} out of range for length {}",
+            self.len()
+        );
+        self.validity[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    /// Returns the validity bitmap, with bit `i % 8` of byte `i / 8` set if element `i` is valid. Padding bits are clear.
+    pub fn validity_bitmap(&self) -> &[u8] {
+        &self.validity
+    }
+
+    /// Gets the element at `index`, or `None` if it's null or out of bounds.
+    pub fn element(&self, index: usize) -> Option<ErasedRef<'_>> {
+        if index < self.len() && self.is_valid(index) {
+            self.vec.element(index)
+        } else {
+            None
+        }
+    }
+
+    /// Appends a valid element from its byte representation. See [TypeErasedVec::push_bytes].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is not exactly one element long.
+    #[track_caller]
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        self.vec.push_bytes(bytes);
+        self.push_validity(true);
+    }
+
+    /// Appends a null element, whose bytes are zeroed.
+    pub fn push_null(&mut self) {
+        self.vec.resize_zeroed(self.vec.len() + 1);
+        self.push_validity(false);
+        self.null_count += 1;
+    }
+
+    fn push_validity(&mut self, valid: bool) {
+        let index = self.vec.len() - 1;
+        if index.is_multiple_of(8) {
+            self.validity.push(0);
+        }
+        self.validity[index / 8] |= u8::from(valid) << (index % 8);
+    }
+
+    /// Gets the vec, with null elements in place. It can't be borrowed mutably, as changing its length would invalidate
+    /// the validity bitmap.
+    pub fn as_erased(&self) -> &TypeErasedVec<A> {
+        &self.vec
+    }
+
+    /// Unwraps the vec, with null elements in place, and the validity bitmap.
+    pub fn into_inner(self) -> (TypeErasedVec<A>, Vec<u8>) {
+        (self.vec, self.validity)
+    }
+}
+
+impl<A: Allocator + Clone> NullableTypeErasedVec<A> {
+    /// Copies the valid elements, in order, into a new vec. See [TypeErasedVec::filter].
+    pub fn compact(&self) -> TypeErasedVec<A> {
+        let valid: Vec<bool> = (0..self.len()).map(|index| self.is_valid(index)).collect();
+        self.vec.filter(&valid)
+    }
+}
+
+/// Returns the validity bitmap of `len` valid elements.
+pub(crate) fn all_valid(len: usize) -> Vec<u8> {
+    let mut validity = vec![u8::MAX; len.div_ceil(8)];
+    if let Some(last) = validity.last_mut() {
+        if !len.is_multiple_of(8) {
+            *last = (1 << (len % 8)) - 1;
+        }
+    }
+    validity
+}
@@ -0,0 +1,75 @@
+//! OpenCL buffers with `opencl3`.
+
+use std::ptr;
+
+use opencl3::{
+    command_queue::CommandQueue,
+    context::Context,
+    memory::{Buffer, ClMem, CL_MEM_COPY_HOST_PTR},
+    types::{cl_mem_flags, CL_BLOCKING},
+};
+
+use crate::{
+    element_count, missing_capability, Allocator, Error, Global, ScalarType, TypeErasedVec,
+};
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Creates a buffer in `context` initialized with a copy of the elements.
+    ///
+    /// `flags` are access flags like `CL_MEM_READ_ONLY`. `CL_MEM_COPY_HOST_PTR` is added to them.
+    ///
+    /// ```
+    /// use opencl3::{command_queue::CommandQueue, context::Context, memory::CL_MEM_READ_WRITE};
+    /// use type_erased_vec::{Error, ScalarType, TypeErasedVec};
+    ///
+    /// fn round_trip(context: &Context, queue: &CommandQueue) -> Result<TypeErasedVec, Error> {
+    ///     let input = TypeErasedVec::from_pod_vec(vec![1.0f32, 2.0, 3.0]);
+    ///     let buffer = input.create_cl_buffer(context, CL_MEM_READ_WRITE)?;
+    ///     // Enqueue kernels writing to `buffer` here.
+    ///     TypeErasedVec::from_cl_buffer(queue, &buffer, ScalarType::F32)
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data, or if `self` is leaked.
+    #[track_caller]
+    pub fn create_cl_buffer(
+        &self,
+        context: &Context,
+        flags: cl_mem_flags,
+    ) -> opencl3::Result<Buffer<u8>> {
+        let Some(bytes) = self.as_bytes() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        // The bytes are copied before `create` returns, and never written through the pointer.
+        let host_ptr = if bytes.is_empty() {
+            ptr::null_mut()
+        } else {
+            bytes.as_ptr().cast_mut().cast()
+        };
+        unsafe { Buffer::create(context, flags | CL_MEM_COPY_HOST_PTR, bytes.len(), host_ptr) }
+    }
+}
+
+impl TypeErasedVec<Global> {
+    /// Reads all of `buffer` into a vec of `scalar_type` elements, waiting for the commands enqueued before.
+    ///
+    /// Fails if an OpenCL call fails, or if the size of `buffer` is not a multiple of the size of `scalar_type`.
+    pub fn from_cl_buffer(
+        queue: &CommandQueue,
+        buffer: &Buffer<u8>,
+        scalar_type: ScalarType,
+    ) -> Result<Self, Error> {
+        let size = buffer.size()?;
+        let len = element_count(size, scalar_type.size())?;
+        let mut vec = Self::new_scalar(scalar_type);
+        vec.resize_zeroed(len);
+        if size != 0 {
+            let bytes = vec.as_bytes_mut().unwrap();
+            // Blocking, so `bytes` is written before this returns.
+            unsafe { queue.enqueue_read_buffer(buffer, CL_BLOCKING, 0, bytes, &[])? };
+        }
+        Ok(vec)
+    }
+}
@@ -0,0 +1,160 @@
+//! Recycling of type erased buffers.
+
+use std::{
+    alloc::Layout,
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt::{self, Debug},
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{raw::RawVec, ElementVTable, Erasable, Global, TypeErasedVec, TypedVTable};
+
+/// A pool of empty buffers, keyed by element layout, to reuse capacity instead of reallocating every frame.
+///
+/// A buffer can be handed out again for any element type with the same size and alignment.
+///
+/// ```
+/// use type_erased_vec::BufferPool;
+///
+/// let pool = BufferPool::new();
+/// for frame in 0..3 {
+///     let mut vertices = pool.take::<[f32; 3]>();
+///     unsafe { vertices.get_mut::<[f32; 3]>().extend([[frame as f32; 3]; 100]) };
+///     // Returned to the pool here, keeping its capacity.
+/// }
+/// assert_eq!(pool.len(), 1);
+/// assert!(pool.take::<[u32; 3]>().capacity() >= 100);
+/// ```
+#[derive(Default)]
+pub struct BufferPool {
+    buffers: RefCell<Buffers>,
+}
+
+/// Empty buffers by element size and alignment.
+type Buffers = BTreeMap<(usize, usize), Vec<RawVec<Global>>>;
+
+impl BufferPool {
+    /// Constructs an empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes an empty vec of `T`s, reusing a pooled buffer if there's one.
+    pub fn take<T: Erasable>(&self) -> PooledVec<'_> {
+        self.take_from_vtable(&TypedVTable::<T>::NEW.vtable)
+    }
+
+    /// Takes an empty vec of elements described by `vtable`, reusing a pooled buffer if there's one.
+    pub fn take_from_vtable(&self, vtable: &'static ElementVTable) -> PooledVec<'_> {
+        let raw = self
+            .buffers
+            .borrow_mut()
+            .get_mut(&key(vtable))
+            .and_then(Vec::pop);
+        let vec = match raw {
+            // The buffer is empty and allocated for this layout.
+            Some(raw) => unsafe { TypeErasedVec::from_raw(raw, vtable) },
+            None => TypeErasedVec::new_from_vtable(vtable),
+        };
+        PooledVec {
+            vec: ManuallyDrop::new(vec),
+            pool: self,
+        }
+    }
+
+    /// Drops the elements of `vec` and keeps its buffer for reuse.
+    ///
+    /// Leaked vecs and vecs without allocated capacity are dropped.
+    pub fn recycle(&self, mut vec: TypeErasedVec) {
+        if vec.is_leaked() || vec.capacity() == 0 || vec.vtable.layout.size() == 0 {
+            return;
+        }
+        vec.clear();
+        let key = key(vec.vtable);
+        let raw = vec.raw.take().unwrap();
+        self.buffers.borrow_mut().entry(key).or_default().push(raw);
+    }
+
+    /// Returns the number of pooled buffers.
+    pub fn len(&self) -> usize {
+        self.buffers.borrow().values().map(Vec::len).sum()
+    }
+
+    /// Returns if there are no pooled buffers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Deallocates all pooled buffers.
+    pub fn clear(&mut self) {
+        for (&(size, align), buffers) in self.buffers.get_mut().iter_mut() {
+            let layout = Layout::from_size_align(size, align).unwrap();
+            for raw in buffers.drain(..) {
+                unsafe { raw.deallocate(layout) };
+            }
+        }
+        self.buffers.get_mut().clear();
+    }
+}
+
+impl Drop for BufferPool {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl Debug for BufferPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferPool")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The pool key of the element type described by `vtable`.
+fn key(vtable: &ElementVTable) -> (usize, usize) {
+    (vtable.layout.size(), vtable.layout.align())
+}
+
+/// A [TypeErasedVec] taken from a [BufferPool], which gives its buffer back when dropped. See [BufferPool::take].
+pub struct PooledVec<'a> {
+    vec: ManuallyDrop<TypeErasedVec>,
+    pool: &'a BufferPool,
+}
+
+impl PooledVec<'_> {
+    /// Detaches the vec from the pool, so its buffer won't be given back.
+    pub fn into_inner(self) -> TypeErasedVec {
+        let mut this = ManuallyDrop::new(self);
+        unsafe { ManuallyDrop::take(&mut this.vec) }
+    }
+}
+
+impl Deref for PooledVec<'_> {
+    type Target = TypeErasedVec;
+
+    fn deref(&self) -> &TypeErasedVec {
+        &self.vec
+    }
+}
+
+impl DerefMut for PooledVec<'_> {
+    fn deref_mut(&mut self) -> &mut TypeErasedVec {
+        &mut self.vec
+    }
+}
+
+impl Drop for PooledVec<'_> {
+    fn drop(&mut self) {
+        let vec = unsafe { ManuallyDrop::take(&mut self.vec) };
+        self.pool.recycle(vec);
+    }
+}
+
+impl Debug for PooledVec<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PooledVec").field(&*self.vec).finish()
+    }
+}
@@ -0,0 +1,199 @@
+//! Python buffer protocol interop with `pyo3`.
+
+use std::{
+    ffi::{c_int, c_void, CStr},
+    fmt::{self, Debug},
+    ptr,
+};
+
+use pyo3::{
+    buffer::{ElementType, PyUntypedBuffer},
+    exceptions::{PyBufferError, PyTypeError},
+    ffi,
+    prelude::*,
+};
+
+use crate::{dispatch, missing_capability, ScalarType, SendSyncTypeErasedVec, TypeErasedVec};
+
+/// A read-only Python object exposing the elements of a scalar [TypeErasedVec] through the buffer protocol,
+/// so `memoryview`, `bytes` or `numpy.frombuffer` can read them without copying.
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use type_erased_vec::{PyErasedBuffer, TypeErasedVec};
+///
+/// #[pyfunction]
+/// fn load_samples(py: Python<'_>) -> PyResult<Py<PyErasedBuffer>> {
+///     let samples = TypeErasedVec::from_scalar_vec(vec![0.5f32, 0.25]);
+///     Py::new(py, PyErasedBuffer::new(samples))
+/// }
+/// ```
+#[pyclass(name = "ErasedBuffer", frozen)]
+pub struct PyErasedBuffer {
+    vec: SendSyncTypeErasedVec,
+    format: &'static CStr,
+    /// The `shape` of the views, which must live as long as they do.
+    shape: [ffi::Py_ssize_t; 1],
+    /// The `strides` of the views.
+    strides: [ffi::Py_ssize_t; 1],
+}
+
+impl PyErasedBuffer {
+    /// Wraps `vec` for Python.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not a [ScalarType] or is `BF16`, which the buffer protocol has no format for,
+    /// or if `vec` is leaked.
+    #[track_caller]
+    pub fn new(vec: TypeErasedVec) -> Self {
+        let Some(scalar_type) = vec.scalar_type() else {
+            missing_capability(vec.vtable, "from_scalar_vec or new_scalar");
+        };
+        let len = vec.len() as ffi::Py_ssize_t;
+        let size = scalar_type.size() as ffi::Py_ssize_t;
+        PyErasedBuffer {
+            // Safety: scalars are `Send` and `Sync`.
+            vec: unsafe { SendSyncTypeErasedVec::from_erased_unchecked(vec) },
+            format: format(scalar_type)
+                .unwrap_or_else(|| panic!("buffer protocol has no format for {scalar_type}")),
+            shape: [len],
+            strides: [size],
+        }
+    }
+
+    /// Gets the vec.
+    pub fn as_erased(&self) -> &TypeErasedVec {
+        &self.vec
+    }
+}
+
+#[pymethods]
+impl PyErasedBuffer {
+    fn __len__(&self) -> usize {
+        self.vec.len()
+    }
+
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("view is null"));
+        }
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("ErasedBuffer is read-only"));
+        }
+        let this = slf.get();
+        let bytes = this.vec.as_bytes().unwrap();
+        let view = &mut *view;
+        view.buf = bytes.as_ptr().cast_mut().cast::<c_void>();
+        view.len = bytes.len() as ffi::Py_ssize_t;
+        view.readonly = 1;
+        view.itemsize = this.strides[0];
+        view.format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+            this.format.as_ptr().cast_mut()
+        } else {
+            ptr::null_mut()
+        };
+        view.ndim = 1;
+        view.shape = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+            this.shape.as_ptr().cast_mut()
+        } else {
+            ptr::null_mut()
+        };
+        view.strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+            this.strides.as_ptr().cast_mut()
+        } else {
+            ptr::null_mut()
+        };
+        view.suboffsets = ptr::null_mut();
+        view.internal = ptr::null_mut();
+        view.obj = slf.into_any().into_ptr();
+        Ok(())
+    }
+}
+
+impl Debug for PyErasedBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PyErasedBuffer").field(&*self.vec).finish()
+    }
+}
+
+impl TypeErasedVec {
+    /// Copies the elements of a Python object supporting the buffer protocol, like a `numpy` array, `array.array`
+    /// or `bytes`, into a vec of the [ScalarType] matching its format. Arrays that are not C-contiguous are copied in C order.
+    ///
+    /// Fails if the object doesn't support the buffer protocol, or if its format is not a scalar type.
+    pub fn from_py_buffer(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let buffer = PyUntypedBuffer::get(obj)?;
+        let scalar_type = match ElementType::from_format(buffer.format()) {
+            ElementType::SignedInteger { bytes: 1 } => ScalarType::I8,
+            ElementType::SignedInteger { bytes: 2 } => ScalarType::I16,
+            ElementType::SignedInteger { bytes: 4 } => ScalarType::I32,
+            ElementType::SignedInteger { bytes: 8 } => ScalarType::I64,
+            ElementType::UnsignedInteger { bytes: 1 } => ScalarType::U8,
+            ElementType::UnsignedInteger { bytes: 2 } => ScalarType::U16,
+            ElementType::UnsignedInteger { bytes: 4 } => ScalarType::U32,
+            ElementType::UnsignedInteger { bytes: 8 } => ScalarType::U64,
+            ElementType::Float { bytes: 4 } => ScalarType::F32,
+            ElementType::Float { bytes: 8 } => ScalarType::F64,
+            #[cfg(feature = "half")]
+            ElementType::Float { bytes: 2 } => ScalarType::F16,
+            _ => {
+                return Err(PyTypeError::new_err(format!(
+                    "buffer format {:?} is not a scalar type",
+                    buffer.format()
+                )))
+            }
+        };
+        match scalar_type {
+            // `pyo3` can't read `f16` buffers, so contiguous ones in native byte order are copied as bytes.
+            #[cfg(feature = "half")]
+            ScalarType::F16 => {
+                if !matches!(buffer.format().to_bytes(), b"e" | b"@e" | b"=e")
+                    || !buffer.is_c_contiguous()
+                {
+                    return Err(PyBufferError::new_err(
+                        "f16 buffer is not contiguous in native byte order",
+                    ));
+                }
+                let mut vec = TypeErasedVec::new_scalar(ScalarType::F16);
+                if buffer.len_bytes() != 0 {
+                    vec.extend_from_byte_slice(unsafe {
+                        std::slice::from_raw_parts(
+                            buffer.buf_ptr().cast::<u8>(),
+                            buffer.len_bytes(),
+                        )
+                    });
+                }
+                Ok(vec)
+            }
+            scalar_type => dispatch!(@primitive scalar_type, |T| {
+                let elements = buffer.as_typed::<T>()?.to_vec(obj.py())?;
+                Ok(TypeErasedVec::from_scalar_vec(elements))
+            }),
+        }
+    }
+}
+
+/// The `struct` module format of `scalar_type`, or `None` if there's none.
+fn format(scalar_type: ScalarType) -> Option<&'static CStr> {
+    Some(match scalar_type {
+        ScalarType::I8 => c"b",
+        ScalarType::I16 => c"h",
+        ScalarType::I32 => c"i",
+        ScalarType::I64 => c"q",
+        ScalarType::U8 => c"B",
+        ScalarType::U16 => c"H",
+        ScalarType::U32 => c"I",
+        ScalarType::U64 => c"Q",
+        ScalarType::F32 => c"f",
+        ScalarType::F64 => c"d",
+        #[cfg(feature = "half")]
+        ScalarType::F16 => c"e",
+        #[cfg(feature = "half")]
+        ScalarType::BF16 => return None,
+    })
+}
@@ -0,0 +1,406 @@
+#[cfg(not(feature = "allocator-api"))]
+#[allow(unused_imports)] // Brings `allocate` and `deallocate` into scope.
+use crate::allocator::{private::Sealed, Allocator};
+use crate::TryReserveError;
+#[cfg(feature = "allocator-api")]
+use std::alloc::Allocator;
+use std::{
+    alloc::{handle_alloc_error, Layout},
+    mem::{align_of, ManuallyDrop, MaybeUninit},
+    ptr::NonNull,
+};
+
+#[derive(Debug)]
+/// The raw parts of a `Vec`.
+///
+/// This struct is leaking. It doesn't call the destructor of the elements or deallocate memory.
+pub struct RawVec<A: Allocator> {
+    ptr: *mut u8,
+    len: usize,
+    cap: usize,
+    /// Alignment of the buffer, which is at least the element alignment.
+    align: usize,
+    alloc: A,
+}
+
+impl<A: Allocator> RawVec<A> {
+    #[cfg(feature = "allocator-api")]
+    pub fn from_vec<T>(vec: Vec<T, A>) -> Self {
+        let (ptr, len, cap, alloc) = vec.into_raw_parts_with_alloc();
+        RawVec {
+            ptr: ptr.cast(),
+            len,
+            cap,
+            align: align_of::<T>(),
+            alloc,
+        }
+    }
+
+    #[cfg(not(feature = "allocator-api"))]
+    pub fn from_vec<T>(vec: Vec<T>) -> Self {
+        let mut vec = ManuallyDrop::new(vec);
+        RawVec {
+            ptr: vec.as_mut_ptr().cast(),
+            len: vec.len(),
+            cap: vec.capacity(),
+            align: align_of::<T>(),
+            alloc: A::default(),
+        }
+    }
+
+    /// Reassembles the parts returned by [RawVec::into_parts].
+    ///
+    /// # Safety
+    ///
+    /// The parts must come from [RawVec::into_parts], or describe a buffer allocated by `alloc` the same way.
+    pub unsafe fn from_parts(ptr: *mut u8, len: usize, cap: usize, align: usize, alloc: A) -> Self {
+        RawVec {
+            ptr,
+            len,
+            cap,
+            align,
+            alloc,
+        }
+    }
+
+    /// Splits into the pointer, length, capacity, buffer alignment and allocator.
+    pub fn into_parts(self) -> (*mut u8, usize, usize, usize, A) {
+        (self.ptr, self.len, self.cap, self.align, self.alloc)
+    }
+
+    /// Allocates room for `cap` elements of `layout`, the same way as [Vec::with_capacity_in].
+    ///
+    /// The buffer is aligned to `layout.align()`, which may be more than the element alignment.
+    pub fn with_capacity_in(layout: Layout, cap: usize, alloc: A) -> Self {
+        let align = layout.align();
+        if layout.size() == 0 {
+            return RawVec {
+                ptr: dangling(layout),
+                len: 0,
+                cap: usize::MAX,
+                align,
+                alloc,
+            };
+        }
+        if cap == 0 {
+            return RawVec {
+                ptr: dangling(layout),
+                len: 0,
+                cap: 0,
+                align,
+                alloc,
+            };
+        }
+        let ptr = handle_reserve(try_allocate_buffer(&alloc, layout, cap));
+        RawVec {
+            ptr,
+            len: 0,
+            cap,
+            align,
+            alloc,
+        }
+    }
+
+    /// Reserves room for at least `additional` more elements of `layout`, growing the same way as [Vec::reserve].
+    pub fn reserve(&mut self, layout: Layout, additional: usize) {
+        handle_reserve(self.try_reserve(layout, additional));
+    }
+
+    /// See [RawVec::reserve] and [Vec::try_reserve].
+    pub fn try_reserve(
+        &mut self,
+        layout: Layout,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        let required = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if required <= self.cap {
+            return Ok(());
+        }
+        // Zero sized elements never get here, as their capacity is `usize::MAX`.
+        let min_cap = match layout.size() {
+            1 => 8,
+            size if size <= 1024 => 4,
+            _ => 1,
+        };
+        self.reallocate(layout, required.max(self.cap * 2).max(min_cap), self.align)
+    }
+
+    /// Reserves room for exactly `additional` more elements of `layout`. See [Vec::reserve_exact].
+    pub fn reserve_exact(&mut self, layout: Layout, additional: usize) {
+        handle_reserve(self.try_reserve_exact(layout, additional));
+    }
+
+    /// See [RawVec::reserve_exact] and [Vec::try_reserve_exact].
+    pub fn try_reserve_exact(
+        &mut self,
+        layout: Layout,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        let required = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if required > self.cap {
+            self.reallocate(layout, required, self.align)?;
+        }
+        Ok(())
+    }
+
+    /// Shrinks the capacity to the length. See [Vec::shrink_to_fit].
+    pub fn shrink_to_fit(&mut self, layout: Layout) {
+        if layout.size() != 0 && self.cap > self.len {
+            handle_reserve(self.reallocate(layout, self.len, self.align));
+        }
+    }
+
+    /// Moves the elements to a buffer aligned to the element alignment, if it's over-aligned.
+    ///
+    /// A `Vec` always deallocates with the element alignment, so this is required before converting to one.
+    pub fn unalign(&mut self, layout: Layout) {
+        if self.align <= layout.align() {
+            return;
+        }
+        if layout.size() == 0 {
+            self.ptr = dangling(layout);
+            self.align = layout.align();
+        } else {
+            handle_reserve(self.reallocate(layout, self.cap, layout.align()));
+        }
+    }
+
+    /// Moves the elements to a new buffer of `cap` elements of `layout` aligned to `align`. `layout` must not be zero sized.
+    fn reallocate(
+        &mut self,
+        layout: Layout,
+        cap: usize,
+        align: usize,
+    ) -> Result<(), TryReserveError> {
+        debug_assert!(cap >= self.len);
+        let new_layout = layout.align_to(align).unwrap();
+        let ptr = if cap == 0 {
+            dangling(new_layout)
+        } else {
+            try_allocate_buffer(&self.alloc, new_layout, cap)?
+        };
+        unsafe {
+            std::ptr::copy_nonoverlapping(self.ptr, ptr, self.len * layout.size());
+            if self.cap != 0 {
+                let old_layout = array_layout(self.aligned(layout), self.cap).unwrap();
+                self.alloc
+                    .deallocate(NonNull::new_unchecked(self.ptr), old_layout);
+            }
+        }
+        self.ptr = ptr;
+        self.cap = cap;
+        self.align = align;
+        Ok(())
+    }
+
+    /// Returns the alignment of the buffer.
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Returns the element layout aligned as the buffer.
+    fn aligned(&self, layout: Layout) -> Layout {
+        layout.align_to(self.align).unwrap()
+    }
+
+    /// Deallocates the buffer without dropping the elements, returning the allocator.
+    ///
+    /// # Safety
+    ///
+    /// `layout` must be the element layout.
+    pub unsafe fn deallocate(self, layout: Layout) -> A {
+        if layout.size() != 0 && self.cap != 0 {
+            let buffer_layout = array_layout(self.aligned(layout), self.cap).unwrap();
+            self.alloc
+                .deallocate(NonNull::new_unchecked(self.ptr), buffer_layout);
+        }
+        self.alloc
+    }
+
+    #[cfg(feature = "allocator-api")]
+    /// Converts the allocator with `f`.
+    ///
+    /// # Safety
+    ///
+    /// The returned allocator must be able to deallocate memory allocated by `self.alloc`.
+    pub unsafe fn map_allocator<B: Allocator>(self, f: impl FnOnce(A) -> B) -> RawVec<B> {
+        RawVec {
+            ptr: self.ptr,
+            len: self.len,
+            cap: self.cap,
+            align: self.align,
+            alloc: f(self.alloc),
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    pub fn ptr(&self) -> *mut u8 {
+        self.ptr
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// # Safety
+    ///
+    /// See [Vec::set_len].
+    pub unsafe fn set_len(&mut self, len: usize) {
+        self.len = len;
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// # Safety
+    ///
+    /// `T` must be the same as in `from_vec`.
+    #[cfg(feature = "allocator-api")]
+    pub unsafe fn into_vec<T>(self) -> Vec<T, A> {
+        Vec::from_raw_parts_in(self.ptr.cast(), self.len, self.cap, self.alloc)
+    }
+
+    /// # Safety
+    ///
+    /// `T` must be the same as in `from_vec`.
+    #[cfg(not(feature = "allocator-api"))]
+    pub unsafe fn into_vec<T>(self) -> Vec<T> {
+        Vec::from_raw_parts(self.ptr.cast(), self.len, self.cap)
+    }
+
+    /// # Safety
+    ///
+    /// `T` must be the same as in `from_vec`.
+    pub unsafe fn as_slice<T>(&self) -> &[T] {
+        std::slice::from_raw_parts(self.ptr.cast(), self.len)
+    }
+
+    /// # Safety
+    ///
+    /// `element_size` must be the size of `T` in `from_vec`, and `T` must not contain uninitialized bytes.
+    pub unsafe fn as_bytes(&self, element_size: usize) -> &[u8] {
+        std::slice::from_raw_parts(self.ptr, self.len * element_size)
+    }
+
+    /// # Safety
+    ///
+    /// `element_size` must be the size of `T` in `from_vec`, and `T` must be valid for any bit pattern.
+    pub unsafe fn as_bytes_mut(&mut self, element_size: usize) -> &mut [u8] {
+        std::slice::from_raw_parts_mut(self.ptr, self.len * element_size)
+    }
+
+    /// `element_size` must be the size of `T` in `from_vec`.
+    pub fn spare_capacity_bytes_mut(&mut self, element_size: usize) -> &mut [MaybeUninit<u8>] {
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.ptr.add(self.len * element_size).cast(),
+                (self.cap - self.len) * element_size,
+            )
+        }
+    }
+}
+
+impl<A: Allocator + Clone> RawVec<A> {
+    /// # Safety
+    /// - `T` must be the same as in `from_vec`.
+    /// - Returned value must not outlive underlying memory.
+    /// - Multiple return values of this method must not be dropped more than once.
+    #[cfg(feature = "allocator-api")]
+    pub unsafe fn as_manually_drop_vec<T>(&self) -> ManuallyDrop<Vec<T, A>> {
+        ManuallyDrop::new(Vec::from_raw_parts_in(
+            self.ptr.cast(),
+            self.len,
+            self.cap,
+            self.alloc.clone(),
+        ))
+    }
+
+    /// # Safety
+    /// - `T` must be the same as in `from_vec`.
+    /// - Returned value must not outlive underlying memory.
+    /// - Multiple return values of this method must not be dropped more than once.
+    #[cfg(not(feature = "allocator-api"))]
+    pub unsafe fn as_manually_drop_vec<T>(&self) -> ManuallyDrop<Vec<T>> {
+        ManuallyDrop::new(Vec::from_raw_parts(self.ptr.cast(), self.len, self.cap))
+    }
+}
+
+/// Deallocates the buffer of a [RawVec] on drop, without dropping the elements.
+///
+/// Used to not leak memory when an element operation panics.
+pub struct DeallocGuard<A: Allocator> {
+    raw: Option<RawVec<A>>,
+    layout: Layout,
+}
+
+impl<A: Allocator> DeallocGuard<A> {
+    pub fn new(raw: RawVec<A>, layout: Layout) -> Self {
+        DeallocGuard {
+            raw: Some(raw),
+            layout,
+        }
+    }
+
+    pub fn raw(&self) -> &RawVec<A> {
+        self.raw.as_ref().unwrap()
+    }
+
+    pub fn into_inner(mut self) -> RawVec<A> {
+        self.raw.take().unwrap()
+    }
+}
+
+impl<A: Allocator> Drop for DeallocGuard<A> {
+    fn drop(&mut self) {
+        if let Some(raw) = self.raw.take() {
+            unsafe {
+                raw.deallocate(self.layout);
+            }
+        }
+    }
+}
+
+/// Layout of a buffer of `cap` elements of `layout`, the same as [Layout::array].
+pub fn array_layout(layout: Layout, cap: usize) -> Option<Layout> {
+    let size = layout.size().checked_mul(cap)?;
+    Layout::from_size_align(size, layout.align()).ok()
+}
+
+/// Allocates a buffer of `cap` elements of `layout`, which must not be zero sized.
+fn try_allocate_buffer<A: Allocator>(
+    alloc: &A,
+    layout: Layout,
+    cap: usize,
+) -> Result<*mut u8, TryReserveError> {
+    let buffer_layout = array_layout(layout, cap).ok_or(TryReserveError::CapacityOverflow)?;
+    match alloc.allocate(buffer_layout) {
+        Ok(ptr) => Ok(ptr.cast::<u8>().as_ptr()),
+        Err(_) => Err(TryReserveError::AllocError {
+            layout: buffer_layout,
+        }),
+    }
+}
+
+/// Panics or aborts the same way as infallible `Vec` methods on error.
+fn handle_reserve<T>(result: Result<T, TryReserveError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(TryReserveError::CapacityOverflow) => panic!("capacity overflow"),
+        Err(TryReserveError::AllocError { layout }) => handle_alloc_error(layout),
+    }
+}
+
+/// A well aligned dangling pointer, the same as [NonNull::dangling].
+fn dangling(layout: Layout) -> *mut u8 {
+    std::ptr::without_provenance_mut(layout.align())
+}
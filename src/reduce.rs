@@ -0,0 +1,103 @@
+//! Summarizing scalar elements.
+
+use std::cmp::Ordering;
+
+use crate::{
+    convert::CastFrom, dispatch, missing_capability, Allocator, ErasedRef, ScalarType,
+    TypeErasedVec,
+};
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Returns the sum of the elements, e.g. to summarize a column whose type is only known at runtime.
+    ///
+    /// Each element is converted to `f64` before summing, so 64 bit integers may lose precision. The sum of no elements is 0.
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let vec = TypeErasedVec::from_scalar_vec(vec![3u8, 250, 7]);
+    /// assert_eq!(vec.sum(), 260.0);
+    /// assert_eq!(vec.mean(), Some(260.0 / 3.0));
+    /// assert_eq!(unsafe { vec.max().unwrap().downcast_ref_unchecked::<u8>() }, &250);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_scalar_vec] or [TypeErasedVec::new_scalar],
+    /// or if `self` is leaked.
+    #[track_caller]
+    pub fn sum(&self) -> f64 {
+        // `dispatch!` only calls this with the element type.
+        dispatch!(self.reduced_scalar_type(), |T| unsafe { self.get::<T>() }
+            .iter()
+            .map(|&value| f64::cast_from(value))
+            .sum())
+    }
+
+    /// Returns the mean of the elements, or `None` if there are none. See [TypeErasedVec::sum].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_scalar_vec] or [TypeErasedVec::new_scalar],
+    /// or if `self` is leaked.
+    #[track_caller]
+    pub fn mean(&self) -> Option<f64> {
+        let sum = self.sum();
+        (!self.is_empty()).then(|| sum / self.len() as f64)
+    }
+
+    /// Returns the first smallest element, or `None` if there are none. `NaN`s are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_scalar_vec] or [TypeErasedVec::new_scalar],
+    /// or if `self` is leaked.
+    #[track_caller]
+    pub fn min(&self) -> Option<ErasedRef<'_>> {
+        self.extreme(Ordering::Less)
+    }
+
+    /// Returns the first largest element, or `None` if there are none. `NaN`s are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` was not constructed with [TypeErasedVec::from_scalar_vec] or [TypeErasedVec::new_scalar],
+    /// or if `self` is leaked.
+    #[track_caller]
+    pub fn max(&self) -> Option<ErasedRef<'_>> {
+        self.extreme(Ordering::Greater)
+    }
+
+    #[track_caller]
+    fn reduced_scalar_type(&self) -> ScalarType {
+        match self.scalar_type() {
+            Some(scalar_type) => scalar_type,
+            None => missing_capability(self.vtable, "from_scalar_vec or new_scalar"),
+        }
+    }
+
+    /// Returns the first element every other compares `ordering` to or equal.
+    #[track_caller]
+    fn extreme(&self, ordering: Ordering) -> Option<ErasedRef<'_>> {
+        // `dispatch!` only calls this with the element type.
+        let index = dispatch!(self.reduced_scalar_type(), |T| position_of_extreme(
+            unsafe { self.get::<T>() },
+            ordering
+        ));
+        self.element(index?)
+    }
+}
+
+/// Returns the index of the first element every other compares `ordering` to or equal, skipping incomparable ones like `NaN`.
+fn position_of_extreme<T: PartialOrd>(elements: &[T], ordering: Ordering) -> Option<usize> {
+    let mut extreme: Option<usize> = None;
+    for (index, element) in elements.iter().enumerate() {
+        if element.partial_cmp(element).is_none() {
+            continue;
+        }
+        if extreme.is_none_or(|extreme| element.partial_cmp(&elements[extreme]) == Some(ordering)) {
+            extreme = Some(index);
+        }
+    }
+    extreme
+}
@@ -0,0 +1,84 @@
+//! Runtime lookup of element vtables.
+
+use std::{any::TypeId, collections::BTreeMap, sync::RwLock};
+
+use crate::{ElementVTable, Erasable, ErasedElement, TypedVTable};
+
+#[derive(Debug, Default)]
+/// A collection of element vtables, looked up by [TypeId] or type name.
+///
+/// Plugins can register their element types so deserializers and editors can work on buffers they don't know statically.
+/// A process wide registry is available with [TypeRegistry::global].
+///
+/// ```
+/// use type_erased_vec::{TypeRegistry, TypedVTable};
+///
+/// static VTABLE: TypedVTable<String> = TypedVTable::new().with_clone().with_debug();
+///
+/// let mut registry = TypeRegistry::new();
+/// registry.register(&VTABLE);
+/// let vtable = registry.get_by_name(std::any::type_name::<String>()).unwrap();
+/// assert!(vtable.is_clone());
+/// ```
+pub struct TypeRegistry {
+    by_id: BTreeMap<TypeId, &'static ElementVTable>,
+    by_name: BTreeMap<&'static str, &'static ElementVTable>,
+}
+
+/// The registry returned by [TypeRegistry::global].
+static GLOBAL: RwLock<TypeRegistry> = RwLock::new(TypeRegistry::new());
+
+impl TypeRegistry {
+    /// Constructs an empty registry.
+    pub const fn new() -> Self {
+        TypeRegistry {
+            by_id: BTreeMap::new(),
+            by_name: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the process wide registry.
+    pub fn global() -> &'static RwLock<TypeRegistry> {
+        &GLOBAL
+    }
+
+    /// Registers `vtable` for `T`, returning the vtable it replaces.
+    pub fn register<T: Erasable>(
+        &mut self,
+        vtable: &'static TypedVTable<T>,
+    ) -> Option<&'static ElementVTable> {
+        let vtable = vtable.erased();
+        self.by_name.insert(vtable.type_name(), vtable);
+        self.by_id.insert(vtable.type_id(), vtable)
+    }
+
+    /// Registers the vtable of the [ErasedElement] implementation of `T`, returning the vtable it replaces.
+    pub fn register_element<T: ErasedElement>(&mut self) -> Option<&'static ElementVTable> {
+        self.register(&T::VTABLE)
+    }
+
+    /// Returns the vtable registered for `type_id`.
+    pub fn get(&self, type_id: TypeId) -> Option<&'static ElementVTable> {
+        self.by_id.get(&type_id).copied()
+    }
+
+    /// Returns the vtable registered for the type named `type_name`. See [std::any::type_name].
+    pub fn get_by_name(&self, type_name: &str) -> Option<&'static ElementVTable> {
+        self.by_name.get(type_name).copied()
+    }
+
+    /// Iterates over all registered vtables.
+    pub fn iter(&self) -> impl Iterator<Item = &'static ElementVTable> + '_ {
+        self.by_id.values().copied()
+    }
+
+    /// Returns the number of registered types.
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    /// Returns if no type is registered.
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
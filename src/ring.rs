@@ -0,0 +1,176 @@
+//! A ring buffer for transient per-frame data.
+
+use std::{collections::VecDeque, ops::Range};
+
+use crate::{ElementVTable, Erasable, TypeErasedVec, TypedVTable};
+
+/// A fixed capacity buffer of plain-old-data elements that is appended to in contiguous regions, wrapping around
+/// to the start when the end is reached, e.g. for per-frame vertex or uniform data.
+///
+/// Regions are freed a frame at a time with [RingErasedBuffer::retire], once the consumer (e.g. the GPU) is done with them.
+///
+/// ```
+/// use type_erased_vec::RingErasedBuffer;
+///
+/// let mut ring = RingErasedBuffer::new::<u32>(8);
+/// assert_eq!(ring.push(&[1u32, 2, 3]), Some(0..3));
+/// ring.end_frame();
+/// assert_eq!(ring.push(&[4u32, 5, 6]), Some(3..6));
+/// ring.end_frame();
+/// // Fits neither in the 2 elements left at the end, nor before the first frame.
+/// assert_eq!(ring.push(&[7u32, 8, 9]), None);
+/// ring.retire();
+/// // Wraps around to the start, freed by the first frame.
+/// assert_eq!(ring.push(&[7u32, 8, 9]), Some(0..3));
+/// assert_eq!(unsafe { ring.as_erased().get::<u32>() }, [7, 8, 9, 4, 5, 6, 0, 0]);
+/// ```
+#[derive(Debug)]
+pub struct RingErasedBuffer {
+    /// `capacity` elements.
+    vec: TypeErasedVec,
+    /// Where the next region starts, up to the capacity.
+    head: usize,
+    /// Where the oldest region in use starts.
+    tail: usize,
+    /// The number of elements in use, including the ones skipped when wrapping around.
+    used: usize,
+    /// The number of elements used by the frames not retired yet, oldest first.
+    frames: VecDeque<usize>,
+    /// The number of elements used by the current frame.
+    current_frame: usize,
+}
+
+impl RingErasedBuffer {
+    /// Constructs a ring of `capacity` zeroed `T`s.
+    pub fn new<T: bytemuck::Pod + Erasable>(capacity: usize) -> Self {
+        Self::new_from_vtable(&TypedVTable::<T>::POD.vtable, capacity)
+    }
+
+    /// Constructs a ring of `capacity` zeroed elements described by `vtable`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data.
+    #[track_caller]
+    pub fn new_from_vtable(vtable: &'static ElementVTable, capacity: usize) -> Self {
+        let mut vec = TypeErasedVec::new_from_vtable(vtable);
+        assert!(
+            vtable.pod,
+            "RingErasedBuffer elements must be plain-old-data"
+        );
+        vec.resize_zeroed(capacity);
+        RingErasedBuffer {
+            vec,
+            head: 0,
+            tail: 0,
+            used: 0,
+            frames: VecDeque::new(),
+            current_frame: 0,
+        }
+    }
+
+    /// Gets the backing buffer, e.g. to upload or bind it. Regions returned by the push methods index into it.
+    pub fn as_erased(&self) -> &TypeErasedVec {
+        &self.vec
+    }
+
+    /// Returns the number of elements the ring holds.
+    pub fn capacity(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns the number of elements in use, including the ones skipped when wrapping around.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    /// Returns the number of frames ended but not retired yet.
+    pub fn frames_in_flight(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Copies elements given as bytes into a contiguous region, returning its element range,
+    /// or `None` if there is no room for them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` is not a multiple of the element size.
+    #[track_caller]
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Option<Range<usize>> {
+        let size = self.vec.element_layout().size();
+        let count = if size == 0 {
+            0
+        } else {
+            assert!(
+                bytes.len().is_multiple_of(size),
+                "byte length is not a multiple of element size"
+            );
+            bytes.len() / size
+        };
+        let start = self.allocate(count)?;
+        self.vec.as_bytes_mut().unwrap()[start * size..][..bytes.len()].copy_from_slice(bytes);
+        Some(start..start + count)
+    }
+
+    /// Copies `elements` into a contiguous region, returning its element range, or `None` if there is no room for them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of `elements` is not a multiple of the element size.
+    #[track_caller]
+    pub fn push<T: bytemuck::Pod>(&mut self, elements: &[T]) -> Option<Range<usize>> {
+        self.push_bytes(bytemuck::cast_slice(elements))
+    }
+
+    /// Ends the current frame. Its regions will be freed by the matching [RingErasedBuffer::retire].
+    pub fn end_frame(&mut self) {
+        self.frames.push_back(self.current_frame);
+        self.current_frame = 0;
+    }
+
+    /// Frees the regions of the oldest ended frame, returning `false` if there's none.
+    pub fn retire(&mut self) -> bool {
+        let Some(frame) = self.frames.pop_front() else {
+            return false;
+        };
+        self.used -= frame;
+        if self.capacity() != 0 {
+            self.tail = (self.tail + frame) % self.capacity();
+        }
+        true
+    }
+
+    /// Reserves `count` contiguous elements, returning where they start.
+    fn allocate(&mut self, count: usize) -> Option<usize> {
+        let capacity = self.capacity();
+        if count > capacity - self.used {
+            return None;
+        }
+        if self.used == 0 {
+            // Only empty frames are in flight, so starting over doesn't free anything in use.
+            self.head = 0;
+            self.tail = 0;
+        }
+        let start = if self.head >= self.tail {
+            if capacity - self.head >= count {
+                self.head
+            } else if self.tail >= count {
+                // Skips the end, freeing it with the current frame.
+                let skipped = capacity - self.head;
+                self.used += skipped;
+                self.current_frame += skipped;
+                0
+            } else {
+                return None;
+            }
+        } else if self.tail - self.head >= count {
+            self.head
+        } else {
+            return None;
+        };
+        self.head = start + count;
+        self.used += count;
+        self.current_frame += count;
+        Some(start)
+    }
+}
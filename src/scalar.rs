@@ -0,0 +1,254 @@
+//! Runtime descriptors for primitive numeric element types.
+
+use std::{
+    alloc::Layout,
+    fmt::{self, Debug, Display},
+};
+
+use crate::{ElementVTable, Erasable, TypedVTable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A primitive numeric element type, chosen at runtime.
+///
+/// This is the kind of type descriptor GPU APIs and file formats use. See [TypeErasedVec::new_scalar](crate::TypeErasedVec::new_scalar).
+pub enum ScalarType {
+    /// [i8]
+    I8,
+    /// [i16]
+    I16,
+    /// [i32]
+    I32,
+    /// [i64]
+    I64,
+    /// [u8]
+    U8,
+    /// [u16]
+    U16,
+    /// [u32]
+    U32,
+    /// [u64]
+    U64,
+    /// [f32]
+    F32,
+    /// [f64]
+    F64,
+    #[cfg(feature = "half")]
+    /// [half::f16], the IEEE 754 half precision type.
+    F16,
+    #[cfg(feature = "half")]
+    /// [half::bf16], the `bfloat16` type machine learning uses.
+    BF16,
+}
+
+impl ScalarType {
+    /// All scalar types.
+    pub const ALL: [ScalarType; if cfg!(feature = "half") { 12 } else { 10 }] = [
+        ScalarType::I8,
+        ScalarType::I16,
+        ScalarType::I32,
+        ScalarType::I64,
+        ScalarType::U8,
+        ScalarType::U16,
+        ScalarType::U32,
+        ScalarType::U64,
+        ScalarType::F32,
+        ScalarType::F64,
+        #[cfg(feature = "half")]
+        ScalarType::F16,
+        #[cfg(feature = "half")]
+        ScalarType::BF16,
+    ];
+
+    /// Returns the size of the scalar type in bytes.
+    pub fn size(self) -> usize {
+        self.layout().size()
+    }
+
+    /// Returns the size and alignment of the scalar type.
+    pub fn layout(self) -> Layout {
+        self.vtable().layout
+    }
+
+    /// Returns if the scalar type is a floating point type.
+    pub fn is_float(self) -> bool {
+        match self {
+            ScalarType::F32 | ScalarType::F64 => true,
+            #[cfg(feature = "half")]
+            ScalarType::F16 | ScalarType::BF16 => true,
+            _ => false,
+        }
+    }
+
+    /// Returns if the scalar type is a signed integer or floating point type.
+    pub fn is_signed(self) -> bool {
+        !matches!(
+            self,
+            ScalarType::U8 | ScalarType::U16 | ScalarType::U32 | ScalarType::U64
+        )
+    }
+
+    /// Returns the Rust name of the scalar type, like `"f32"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "half")]
+            ScalarType::F16 => "f16",
+            #[cfg(feature = "half")]
+            ScalarType::BF16 => "bf16",
+            _ => self.vtable().type_name(),
+        }
+    }
+
+    /// Returns the vtable of the scalar type, which can clone, compare, format and view elements as bytes.
+    pub fn vtable(self) -> &'static ElementVTable {
+        match self {
+            ScalarType::I8 => &TypedVTable::<i8>::SCALAR.vtable,
+            ScalarType::I16 => &TypedVTable::<i16>::SCALAR.vtable,
+            ScalarType::I32 => &TypedVTable::<i32>::SCALAR.vtable,
+            ScalarType::I64 => &TypedVTable::<i64>::SCALAR.vtable,
+            ScalarType::U8 => &TypedVTable::<u8>::SCALAR.vtable,
+            ScalarType::U16 => &TypedVTable::<u16>::SCALAR.vtable,
+            ScalarType::U32 => &TypedVTable::<u32>::SCALAR.vtable,
+            ScalarType::U64 => &TypedVTable::<u64>::SCALAR.vtable,
+            ScalarType::F32 => &TypedVTable::<f32>::SCALAR.vtable,
+            ScalarType::F64 => &TypedVTable::<f64>::SCALAR.vtable,
+            #[cfg(feature = "half")]
+            ScalarType::F16 => &TypedVTable::<half::f16>::SCALAR.vtable,
+            #[cfg(feature = "half")]
+            ScalarType::BF16 => &TypedVTable::<half::bf16>::SCALAR.vtable,
+        }
+    }
+}
+
+impl Display for ScalarType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Calls generic code with the Rust type of a [ScalarType].
+///
+/// `dispatch!(scalar_type, |T| expr)` matches on `scalar_type` and evaluates `expr` with `T` naming the matching type.
+/// Every arm must evaluate to the same type. With the `half` feature, `T` can also be `half::f16` or `half::bf16`.
+///
+/// ```
+/// use type_erased_vec::{dispatch, Scalar, TypeErasedVec};
+///
+/// fn describe_first<T: Scalar>(vec: &TypeErasedVec) -> Option<String> {
+///     // `dispatch!` only calls this with the element type.
+///     unsafe { vec.get::<T>() }.first().map(|x| format!("{x:?}"))
+/// }
+///
+/// let vec = TypeErasedVec::from_scalar_vec(vec![1.5f32, 2.0]);
+/// let first = dispatch!(vec.scalar_type().unwrap(), |T| describe_first::<T>(&vec));
+/// assert_eq!(first.as_deref(), Some("1.5"));
+/// ```
+#[cfg(not(feature = "half"))]
+#[macro_export]
+macro_rules! dispatch {
+    ($scalar_type:expr, |$t:ident| $body:expr) => {
+        match $scalar_type {
+            $crate::ScalarType::I8 => $crate::dispatch!(@arm $t = i8, $body),
+            $crate::ScalarType::I16 => $crate::dispatch!(@arm $t = i16, $body),
+            $crate::ScalarType::I32 => $crate::dispatch!(@arm $t = i32, $body),
+            $crate::ScalarType::I64 => $crate::dispatch!(@arm $t = i64, $body),
+            $crate::ScalarType::U8 => $crate::dispatch!(@arm $t = u8, $body),
+            $crate::ScalarType::U16 => $crate::dispatch!(@arm $t = u16, $body),
+            $crate::ScalarType::U32 => $crate::dispatch!(@arm $t = u32, $body),
+            $crate::ScalarType::U64 => $crate::dispatch!(@arm $t = u64, $body),
+            $crate::ScalarType::F32 => $crate::dispatch!(@arm $t = f32, $body),
+            $crate::ScalarType::F64 => $crate::dispatch!(@arm $t = f64, $body),
+        }
+    };
+    // Only the primitive types, for code whose bounds the `half` types don't meet.
+    (@primitive $scalar_type:expr, |$t:ident| $body:expr) => {
+        $crate::dispatch!($scalar_type, |$t| $body)
+    };
+    (@arm $t:ident = $ty:ty, $body:expr) => {{
+        #[allow(non_camel_case_types, dead_code)]
+        type $t = $ty;
+        $body
+    }};
+}
+
+/// Calls generic code with the Rust type of a [ScalarType].
+///
+/// `dispatch!(scalar_type, |T| expr)` matches on `scalar_type` and evaluates `expr` with `T` naming the matching type.
+/// Every arm must evaluate to the same type. With the `half` feature, `T` can also be `half::f16` or `half::bf16`.
+///
+/// ```
+/// use type_erased_vec::{dispatch, Scalar, TypeErasedVec};
+///
+/// fn describe_first<T: Scalar>(vec: &TypeErasedVec) -> Option<String> {
+///     // `dispatch!` only calls this with the element type.
+///     unsafe { vec.get::<T>() }.first().map(|x| format!("{x:?}"))
+/// }
+///
+/// let vec = TypeErasedVec::from_scalar_vec(vec![1.5f32, 2.0]);
+/// let first = dispatch!(vec.scalar_type().unwrap(), |T| describe_first::<T>(&vec));
+/// assert_eq!(first.as_deref(), Some("1.5"));
+/// ```
+#[cfg(feature = "half")]
+#[macro_export]
+macro_rules! dispatch {
+    ($scalar_type:expr, |$t:ident| $body:expr) => {
+        match $scalar_type {
+            $crate::ScalarType::F16 => $crate::dispatch!(@arm $t = $crate::half::f16, $body),
+            $crate::ScalarType::BF16 => $crate::dispatch!(@arm $t = $crate::half::bf16, $body),
+            scalar_type => $crate::dispatch!(@primitive scalar_type, |$t| $body),
+        }
+    };
+    // Only the primitive types, for code whose bounds the `half` types don't meet. Panics for the `half` types.
+    (@primitive $scalar_type:expr, |$t:ident| $body:expr) => {
+        match $scalar_type {
+            $crate::ScalarType::I8 => $crate::dispatch!(@arm $t = i8, $body),
+            $crate::ScalarType::I16 => $crate::dispatch!(@arm $t = i16, $body),
+            $crate::ScalarType::I32 => $crate::dispatch!(@arm $t = i32, $body),
+            $crate::ScalarType::I64 => $crate::dispatch!(@arm $t = i64, $body),
+            $crate::ScalarType::U8 => $crate::dispatch!(@arm $t = u8, $body),
+            $crate::ScalarType::U16 => $crate::dispatch!(@arm $t = u16, $body),
+            $crate::ScalarType::U32 => $crate::dispatch!(@arm $t = u32, $body),
+            $crate::ScalarType::U64 => $crate::dispatch!(@arm $t = u64, $body),
+            $crate::ScalarType::F32 => $crate::dispatch!(@arm $t = f32, $body),
+            $crate::ScalarType::F64 => $crate::dispatch!(@arm $t = f64, $body),
+            scalar_type => unreachable!("{scalar_type} is not a primitive type"),
+        }
+    };
+    (@arm $t:ident = $ty:ty, $body:expr) => {{
+        #[allow(non_camel_case_types, dead_code)]
+        type $t = $ty;
+        $body
+    }};
+}
+
+/// Primitive numeric types that have a [ScalarType].
+///
+/// This trait is sealed and implemented for the types listed in [ScalarType].
+pub trait Scalar: Copy + Debug + PartialEq + Erasable + private::Sealed {
+    /// The runtime descriptor of `Self`.
+    const SCALAR_TYPE: ScalarType;
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty => $variant:ident),*) => {
+        $(
+            impl private::Sealed for $t {}
+
+            impl Scalar for $t {
+                const SCALAR_TYPE: ScalarType = ScalarType::$variant;
+            }
+        )*
+    };
+}
+
+impl_scalar!(
+    i8 => I8, i16 => I16, i32 => I32, i64 => I64,
+    u8 => U8, u16 => U16, u32 => U32, u64 => U64,
+    f32 => F32, f64 => F64
+);
+
+#[cfg(feature = "half")]
+impl_scalar!(half::f16 => F16, half::bf16 => BF16);
+
+mod private {
+    pub trait Sealed {}
+}
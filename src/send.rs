@@ -0,0 +1,164 @@
+//! Wrappers that opt type erased vecs in to [Send] and [Sync].
+
+use std::{
+    fmt::{self, Debug},
+    ops::Deref,
+};
+
+use crate::{Allocator, Erasable, FrozenTypeErasedVec, Global, TypeErasedVec, VecMut};
+
+/// A [TypeErasedVec] whose element type is known to be [Send], so it can be moved to another thread.
+/// See [TypeErasedVec::from_vec_send].
+///
+/// ```
+/// use type_erased_vec::TypeErasedVec;
+///
+/// let vec = TypeErasedVec::from_vec_send(vec!["loaded".to_string()]);
+/// let vec = std::thread::spawn(move || vec).join().unwrap();
+/// assert_eq!(unsafe { vec.get::<String>() }, ["loaded"]);
+/// ```
+pub struct SendTypeErasedVec<A: Allocator = Global> {
+    vec: TypeErasedVec<A>,
+}
+
+// Safety: the element type is `Send`, and so is the allocator.
+unsafe impl<A: Allocator + Send> Send for SendTypeErasedVec<A> {}
+
+/// A [TypeErasedVec] whose element type is known to be [Send] and [Sync], so it can be moved to and shared with other threads.
+/// See [TypeErasedVec::from_vec_sync].
+///
+/// Freezing it makes a [FrozenTypeErasedVec] that is [Send] and [Sync] too.
+///
+/// ```
+/// use type_erased_vec::TypeErasedVec;
+///
+/// let vertices = TypeErasedVec::from_vec_sync(vec![[0.0f32; 3]; 64]).freeze();
+/// let for_upload = vertices.clone();
+/// std::thread::spawn(move || assert_eq!(for_upload.len(), 64)).join().unwrap();
+/// ```
+pub struct SendSyncTypeErasedVec<A: Allocator = Global> {
+    vec: TypeErasedVec<A>,
+}
+
+// Safety: the element type is `Send` and `Sync`, and so is the allocator.
+unsafe impl<A: Allocator + Send> Send for SendSyncTypeErasedVec<A> {}
+unsafe impl<A: Allocator + Sync> Sync for SendSyncTypeErasedVec<A> {}
+
+#[cfg(feature = "allocator-api")]
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Erases the type of `vec`, remembering that it can be sent to another thread.
+    pub fn from_vec_send<T: Send + Erasable>(vec: Vec<T, A>) -> SendTypeErasedVec<A> {
+        SendTypeErasedVec {
+            vec: Self::from_vec(vec),
+        }
+    }
+
+    /// Erases the type of `vec`, remembering that it can be sent to and shared with other threads.
+    pub fn from_vec_sync<T: Send + Sync + Erasable>(vec: Vec<T, A>) -> SendSyncTypeErasedVec<A> {
+        SendSyncTypeErasedVec {
+            vec: Self::from_vec(vec),
+        }
+    }
+}
+
+#[cfg(not(feature = "allocator-api"))]
+impl TypeErasedVec<Global> {
+    /// Erases the type of `vec`, remembering that it can be sent to another thread.
+    pub fn from_vec_send<T: Send + Erasable>(vec: Vec<T>) -> SendTypeErasedVec {
+        SendTypeErasedVec {
+            vec: Self::from_vec(vec),
+        }
+    }
+
+    /// Erases the type of `vec`, remembering that it can be sent to and shared with other threads.
+    pub fn from_vec_sync<T: Send + Sync + Erasable>(vec: Vec<T>) -> SendSyncTypeErasedVec {
+        SendSyncTypeErasedVec {
+            vec: Self::from_vec(vec),
+        }
+    }
+}
+
+/// Implements the methods shared by the wrappers.
+macro_rules! impl_wrapper {
+    ($wrapper:ident, $bounds:literal) => {
+        impl<A: Allocator> $wrapper<A> {
+            #[doc = concat!("Wraps `vec`.\n\n# Safety\n\nThe element type of `vec` must be ", $bounds, ".")]
+            pub unsafe fn from_erased_unchecked(vec: TypeErasedVec<A>) -> Self {
+                $wrapper { vec }
+            }
+
+            /// Unwraps the underlying [TypeErasedVec].
+            pub fn into_erased(self) -> TypeErasedVec<A> {
+                self.vec
+            }
+
+            /// Gets the underlying [TypeErasedVec].
+            pub fn as_erased(&self) -> &TypeErasedVec<A> {
+                &self.vec
+            }
+
+            #[doc = concat!("Gets the underlying [TypeErasedVec] mutably.\n\n# Safety\n\nThe element type must stay ", $bounds, ", e.g. it must not be reset to another type.")]
+            pub unsafe fn as_erased_mut(&mut self) -> &mut TypeErasedVec<A> {
+                &mut self.vec
+            }
+
+            /// Gets a smart pointer to `mut Vec<T>`. See [TypeErasedVec::get_mut].
+            ///
+            /// # Safety
+            ///
+            /// `T` must be the element type.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `self` is leaked.
+            #[track_caller]
+            pub unsafe fn get_mut<T>(&mut self) -> VecMut<'_, T, A> {
+                self.vec.get_mut()
+            }
+
+            /// Moves the elements into a [FrozenTypeErasedVec]. See [TypeErasedVec::freeze].
+            ///
+            /// # Panics
+            ///
+            /// Panics if `self` is leaked.
+            #[track_caller]
+            pub fn freeze(self) -> FrozenTypeErasedVec<Self> {
+                raw!(self.vec);
+                FrozenTypeErasedVec::new(self)
+            }
+        }
+
+        impl<A: Allocator> Deref for $wrapper<A> {
+            type Target = TypeErasedVec<A>;
+
+            fn deref(&self) -> &TypeErasedVec<A> {
+                &self.vec
+            }
+        }
+
+        impl<A: Allocator> Deref for FrozenTypeErasedVec<$wrapper<A>> {
+            type Target = TypeErasedVec<A>;
+
+            fn deref(&self) -> &TypeErasedVec<A> {
+                self.inner()
+            }
+        }
+
+        impl<A: Allocator + Clone> Clone for $wrapper<A> {
+            fn clone(&self) -> Self {
+                $wrapper {
+                    vec: self.vec.clone(),
+                }
+            }
+        }
+
+        impl<A: Allocator + Debug> Debug for $wrapper<A> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($wrapper)).field(&self.vec).finish()
+            }
+        }
+    };
+}
+
+impl_wrapper!(SendTypeErasedVec, "[Send]");
+impl_wrapper!(SendSyncTypeErasedVec, "[Send] and [Sync]");
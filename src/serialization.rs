@@ -0,0 +1,145 @@
+//! [serde] support.
+
+use std::fmt::{self, Debug};
+
+use serde::{
+    de::{self, DeserializeSeed, Deserializer},
+    ser::{self, Serialize, Serializer},
+};
+
+#[cfg(feature = "serde-pod-bytes")]
+use crate::{raw::RawVec, Global};
+use crate::{Allocator, ElementVTable, TypeErasedVec};
+
+impl<A: Allocator> Serialize for TypeErasedVec<A> {
+    /// Serializes the elements as a sequence, the same way as [Vec].
+    ///
+    /// With the `serde-pod-bytes` feature, vecs constructed with [TypeErasedVec::from_pod_vec] are serialized
+    /// as a single block of native endian bytes instead.
+    ///
+    /// Fails if `self` was not constructed with [TypeErasedVec::from_vec_serialize].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "serde-pod-bytes")]
+        if is_pod_bytes(self.vtable) {
+            return serializer.serialize_bytes(self.as_bytes().unwrap());
+        }
+        let serialize = self.vtable.serialize.ok_or_else(|| {
+            <S::Error as ser::Error>::custom(
+                "TypeErasedVec was not constructed with from_vec_serialize",
+            )
+        })?;
+        let raw = raw!(self);
+        let mut serializer = Some(serializer);
+        let mut result = None;
+        unsafe {
+            serialize(raw.ptr(), raw.len(), &mut |elements| {
+                result = Some(erased_serde::serialize(
+                    elements,
+                    serializer.take().unwrap(),
+                ));
+            });
+        }
+        result.unwrap()
+    }
+}
+
+/// Deserializes a [TypeErasedVec] whose element type is only known at runtime.
+///
+/// The vtable must be built with [TypedVTable::with_deserialize](crate::TypedVTable::with_deserialize),
+/// and it becomes the vtable of the deserialized vec.
+///
+/// ```
+/// use serde::de::DeserializeSeed;
+/// use type_erased_vec::{ErasedVecSeed, TypedVTable};
+///
+/// static VTABLE: TypedVTable<i32> = TypedVTable::new().with_serialize().with_deserialize();
+///
+/// let seed = ErasedVecSeed::new(VTABLE.erased());
+/// let mut deserializer = serde_json::Deserializer::from_str("[1, 2, 3]");
+/// let vec = seed.deserialize(&mut deserializer).unwrap();
+/// assert_eq!(unsafe { vec.get::<i32>() }, [1, 2, 3]);
+/// ```
+#[derive(Clone, Copy)]
+pub struct ErasedVecSeed {
+    vtable: &'static ElementVTable,
+}
+
+impl ErasedVecSeed {
+    /// Constructs a seed that deserializes elements described by `vtable`.
+    pub fn new(vtable: &'static ElementVTable) -> Self {
+        ErasedVecSeed { vtable }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ErasedVecSeed {
+    type Value = TypeErasedVec;
+
+    /// Fails if the vtable can't deserialize elements.
+    ///
+    /// With the `serde-pod-bytes` feature, plain-old-data elements are read from a block of bytes instead,
+    /// as written by the `Serialize` implementation.
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<TypeErasedVec, D::Error> {
+        #[cfg(feature = "serde-pod-bytes")]
+        if is_pod_bytes(self.vtable) {
+            return deserializer.deserialize_bytes(PodBytesVisitor(self.vtable));
+        }
+        let deserialize = self.vtable.deserialize.ok_or_else(|| {
+            <D::Error as de::Error>::custom("vtable was not built with with_deserialize")
+        })?;
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+        deserialize(&mut deserializer, self.vtable).map_err(<D::Error as de::Error>::custom)
+    }
+}
+
+impl Debug for ErasedVecSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedVecSeed")
+            .field("vtable", self.vtable)
+            .finish()
+    }
+}
+
+#[cfg(feature = "serde-pod-bytes")]
+/// Returns if elements described by `vtable` go through the byte block fast path.
+///
+/// Zero sized elements don't, because their count can't be recovered from bytes.
+fn is_pod_bytes(vtable: &ElementVTable) -> bool {
+    vtable.pod && vtable.layout.size() != 0
+}
+
+#[cfg(feature = "serde-pod-bytes")]
+/// Reads plain-old-data elements from a block of bytes.
+struct PodBytesVisitor(&'static ElementVTable);
+
+#[cfg(feature = "serde-pod-bytes")]
+impl<'de> de::Visitor<'de> for PodBytesVisitor {
+    type Value = TypeErasedVec;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bytes of {} elements", self.0.type_name())
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<TypeErasedVec, E> {
+        let size = self.0.layout.size();
+        if !bytes.len().is_multiple_of(size) {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+        let raw = RawVec::with_capacity_in(self.0.layout, bytes.len() / size, Global);
+        let mut vec = unsafe { TypeErasedVec::from_raw(raw, self.0) };
+        vec.extend_from_byte_slice(bytes);
+        Ok(vec)
+    }
+
+    /// Self describing formats may write bytes as a sequence.
+    fn visit_seq<S: de::SeqAccess<'de>>(self, mut seq: S) -> Result<TypeErasedVec, S::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
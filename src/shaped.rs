@@ -0,0 +1,164 @@
+//! n-dimensional views with `ndarray`.
+
+use std::slice;
+
+use ndarray::{Array, ArrayViewD, ArrayViewMutD, Dimension, IxDyn};
+
+use crate::{Allocator, Erasable, Global, Scalar, TypeErasedVec};
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Views the elements as an n-dimensional array of `shape` in row-major order.
+    ///
+    /// # Safety
+    ///
+    /// See [TypeErasedVec::into_vec].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the product of `shape` is not the length, or if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn as_array_view<T>(&self, shape: &[usize]) -> ArrayViewD<'_, T> {
+        let elements = self.get::<T>();
+        check_shape(shape, elements.len());
+        ArrayViewD::from_shape(IxDyn(shape), elements).unwrap()
+    }
+
+    /// Views the elements as a mutable n-dimensional array of `shape` in row-major order.
+    ///
+    /// # Safety
+    ///
+    /// See [TypeErasedVec::into_vec].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the product of `shape` is not the length, or if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn as_array_view_mut<T>(&mut self, shape: &[usize]) -> ArrayViewMutD<'_, T> {
+        let raw = raw!(mut self);
+        check_shape(shape, raw.len());
+        let elements = slice::from_raw_parts_mut(raw.ptr().cast::<T>(), raw.len());
+        ArrayViewMutD::from_shape(IxDyn(shape), elements).unwrap()
+    }
+}
+
+#[track_caller]
+fn check_shape(shape: &[usize], len: usize) {
+    assert_eq!(
+        shape.iter().product::<usize>(),
+        len,
+        "shape {shape:?} doesn't match length {len}"
+    );
+}
+
+/// A [TypeErasedVec] with the shape of the n-dimensional array it holds in row-major order.
+///
+/// ```
+/// use ndarray::array;
+/// use type_erased_vec::ShapedTypeErasedVec;
+///
+/// let mut image = ShapedTypeErasedVec::from_scalar_array(array![[1u8, 2, 3], [4, 5, 6]]);
+/// assert_eq!(image.shape(), [2, 3]);
+/// image.reshape(vec![3, 2]);
+/// assert_eq!(unsafe { image.view::<u8>() }[[2, 0]], 5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShapedTypeErasedVec<A: Allocator = Global> {
+    vec: TypeErasedVec<A>,
+    shape: Vec<usize>,
+}
+
+impl<A: Allocator> ShapedTypeErasedVec<A> {
+    /// Attaches `shape` to `vec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the product of `shape` is not the length of `vec`, or if `vec` is leaked.
+    #[track_caller]
+    pub fn new(vec: TypeErasedVec<A>, shape: Vec<usize>) -> Self {
+        check_shape(&shape, vec.len());
+        ShapedTypeErasedVec { vec, shape }
+    }
+
+    /// Returns the dimension lengths.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Changes the dimension lengths, keeping the elements in the same order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the product of `shape` is not the length.
+    #[track_caller]
+    pub fn reshape(&mut self, shape: Vec<usize>) {
+        check_shape(&shape, self.vec.len());
+        self.shape = shape;
+    }
+
+    /// Gets the vec. It can't be borrowed mutably, as changing its length would invalidate the shape.
+    pub fn as_erased(&self) -> &TypeErasedVec<A> {
+        &self.vec
+    }
+
+    /// Unwraps the vec and the shape.
+    pub fn into_inner(self) -> (TypeErasedVec<A>, Vec<usize>) {
+        (self.vec, self.shape)
+    }
+
+    /// Views the elements as an n-dimensional array.
+    ///
+    /// # Safety
+    ///
+    /// See [TypeErasedVec::into_vec].
+    pub unsafe fn view<T>(&self) -> ArrayViewD<'_, T> {
+        self.vec.as_array_view(&self.shape)
+    }
+
+    /// Views the elements as a mutable n-dimensional array.
+    ///
+    /// # Safety
+    ///
+    /// See [TypeErasedVec::into_vec].
+    pub unsafe fn view_mut<T>(&mut self) -> ArrayViewMutD<'_, T> {
+        self.vec.as_array_view_mut(&self.shape)
+    }
+}
+
+impl ShapedTypeErasedVec {
+    /// Converts from an array, with a vec constructed by [TypeErasedVec::from_vec].
+    ///
+    /// The elements are only copied if they are not in row-major order, or not at the start of the array's buffer.
+    pub fn from_array<T: Erasable + Clone, D: Dimension>(array: Array<T, D>) -> Self {
+        let (vec, shape) = into_standard_vec(array);
+        ShapedTypeErasedVec {
+            vec: TypeErasedVec::from_vec(vec),
+            shape,
+        }
+    }
+
+    /// Converts from an array, with a vec constructed by [TypeErasedVec::from_scalar_vec]. See [ShapedTypeErasedVec::from_array].
+    pub fn from_scalar_array<T: Scalar, D: Dimension>(array: Array<T, D>) -> Self {
+        let (vec, shape) = into_standard_vec(array);
+        ShapedTypeErasedVec {
+            vec: TypeErasedVec::from_scalar_vec(vec),
+            shape,
+        }
+    }
+}
+
+/// Returns the elements of `array` in row-major order, and its shape.
+fn into_standard_vec<T: Clone, D: Dimension>(array: Array<T, D>) -> (Vec<T>, Vec<usize>) {
+    let shape = array.shape().to_vec();
+    let len = array.len();
+    let array = if array.is_standard_layout() {
+        array
+    } else {
+        array.as_standard_layout().into_owned()
+    };
+    let (mut vec, offset) = array.into_raw_vec_and_offset();
+    // The elements are contiguous from `offset`.
+    let start = offset.unwrap_or(0);
+    vec.truncate(start + len);
+    vec.drain(..start);
+    (vec, shape)
+}
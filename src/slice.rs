@@ -0,0 +1,413 @@
+//! Borrowed views of type erased elements.
+
+#[cfg(feature = "type-id")]
+use std::any::TypeId;
+use std::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+    ptr, slice,
+};
+
+use crate::{ElementBytes, ElementVTable, Erasable, ErasedMut, ErasedRef, Error, TypedVTable};
+
+/// A type erased `&'a [T]`, for APIs that read erased elements without taking ownership of a [TypeErasedVec](crate::TypeErasedVec).
+///
+/// ```
+/// use type_erased_vec::{TypeErasedSlice, TypeErasedVec};
+///
+/// fn total_size(slice: TypeErasedSlice<'_>) -> usize {
+///     slice.len() * slice.vtable().layout().size()
+/// }
+///
+/// let vec = TypeErasedVec::from_vec(vec![1u32, 2, 3]);
+/// assert_eq!(total_size(vec.as_erased_slice()), 12);
+/// assert_eq!(total_size(TypeErasedSlice::from_slice(&[1u8, 2])), 2);
+/// ```
+#[derive(Clone, Copy)]
+pub struct TypeErasedSlice<'a> {
+    ptr: *const u8,
+    len: usize,
+    vtable: &'static ElementVTable,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> TypeErasedSlice<'a> {
+    /// Erases the type of `slice`.
+    pub fn from_slice<T: Erasable>(slice: &'a [T]) -> Self {
+        unsafe {
+            Self::from_raw_parts(
+                slice.as_ptr().cast(),
+                slice.len(),
+                &TypedVTable::<T>::NEW.vtable,
+            )
+        }
+    }
+
+    #[cfg(feature = "bytemuck")]
+    /// Erases the type of `slice`, remembering that `T` is plain-old-data so it can be accessed with [TypeErasedSlice::as_bytes].
+    pub fn from_pod_slice<T: bytemuck::Pod + Erasable>(slice: &'a [T]) -> Self {
+        unsafe {
+            Self::from_raw_parts(
+                slice.as_ptr().cast(),
+                slice.len(),
+                &TypedVTable::<T>::POD.vtable,
+            )
+        }
+    }
+
+    /// Erases the type of `slice`, using `vtable` for the operations that don't know the element type.
+    pub fn from_slice_with_vtable<T>(slice: &'a [T], vtable: &'static TypedVTable<T>) -> Self {
+        unsafe { Self::from_raw_parts(slice.as_ptr().cast(), slice.len(), vtable.erased()) }
+    }
+
+    /// Views `bytes` as elements described by `vtable`, without copying, e.g. ones received from the network or a file.
+    ///
+    /// ```
+    /// use type_erased_vec::{ScalarType, TypeErasedSlice};
+    ///
+    /// let bytes = 7u32.to_ne_bytes();
+    /// match TypeErasedSlice::try_from_bytes(&bytes, ScalarType::U32.vtable()) {
+    ///     Ok(slice) => assert_eq!(unsafe { slice.get::<u32>() }, [7]),
+    ///     // `bytes` may not be aligned for `u32`.
+    ///     Err(e) => println!("{e}"),
+    /// }
+    /// ```
+    ///
+    /// Fails if the element type is not known to be plain-old-data, as any bytes may be in `bytes`, if it's zero sized,
+    /// if the length of `bytes` is not a multiple of the element size, or if `bytes` is not aligned to the element alignment.
+    pub fn try_from_bytes(bytes: &'a [u8], vtable: &'static ElementVTable) -> Result<Self, Error> {
+        if !vtable.pod {
+            return Err(Error::NotPod);
+        }
+        let layout = vtable.layout;
+        if layout.size() == 0 {
+            return Err(Error::Unsupported("element type is zero sized"));
+        }
+        if !bytes.len().is_multiple_of(layout.size()) {
+            return Err(Error::LengthNotMultipleOfElement {
+                len: bytes.len(),
+                element_size: layout.size(),
+            });
+        }
+        if !bytes.as_ptr().addr().is_multiple_of(layout.align()) {
+            return Err(Error::Misaligned {
+                align: layout.align(),
+            });
+        }
+        // Any bytes are valid plain-old-data.
+        Ok(unsafe { Self::from_raw_parts(bytes.as_ptr(), bytes.len() / layout.size(), vtable) })
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must point to `len` contiguous elements described by `vtable`, valid for reads during `'a`.
+    pub(crate) unsafe fn from_raw_parts(
+        ptr: *const u8,
+        len: usize,
+        vtable: &'static ElementVTable,
+    ) -> Self {
+        TypeErasedSlice {
+            ptr,
+            len,
+            vtable,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the vtable of the element type.
+    pub fn vtable(&self) -> &'static ElementVTable {
+        self.vtable
+    }
+
+    /// Returns a pointer to the first element.
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+
+    /// Returns the number of elements. See [slice::len].
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns if there are no elements. See [slice::is_empty].
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Returns if the element type is `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.vtable.type_id() == TypeId::of::<T>()
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Gets the elements as `&[T]`, or `None` if `T` is not the element type.
+    pub fn downcast<T: 'static>(self) -> Option<&'a [T]> {
+        if self.is::<T>() {
+            Some(unsafe { self.get() })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the elements as `&[T]`.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the element type.
+    pub unsafe fn get<T>(self) -> &'a [T] {
+        slice::from_raw_parts(self.ptr.cast(), self.len)
+    }
+
+    /// Gets a handle to the element at `index`, or `None` if out of bounds. See [slice::get].
+    pub fn element(self, index: usize) -> Option<ErasedRef<'a>> {
+        if index < self.len {
+            let ptr = unsafe { self.ptr.add(index * self.vtable.layout.size()) };
+            Some(unsafe { ErasedRef::new(ptr, self.vtable) })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the elements as bytes, or `None` if the element type is not known to be plain-old-data.
+    pub fn as_bytes(self) -> Option<&'a [u8]> {
+        if self.vtable.pod {
+            Some(unsafe { slice::from_raw_parts(self.ptr, self.len * self.vtable.layout.size()) })
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over the byte representation of each element, or returns `None` if the element type is not known to be plain-old-data.
+    pub fn iter_element_bytes(self) -> Option<ElementBytes<'a>> {
+        let bytes = self.as_bytes()?;
+        Some(ElementBytes::new(
+            bytes,
+            self.vtable.layout.size(),
+            self.len,
+        ))
+    }
+}
+
+impl TypeErasedSlice<'static> {
+    /// Erases the type of `'static` data, such as a baked-in lookup table, without copying it into a [Vec].
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedSlice;
+    ///
+    /// static GAMMA: [u8; 4] = [0, 21, 99, 255];
+    ///
+    /// let table = TypeErasedSlice::from_static(&GAMMA);
+    /// assert_eq!(table.len(), 4);
+    /// assert_eq!(unsafe { table.get::<u8>() }, GAMMA);
+    /// ```
+    pub fn from_static<T: Erasable>(slice: &'static [T]) -> Self {
+        Self::from_slice(slice)
+    }
+
+    #[cfg(feature = "bytemuck")]
+    /// Erases the type of `'static` plain-old-data, such as embedded asset data, without copying it. See [TypeErasedSlice::from_pod_slice].
+    pub fn from_static_pod<T: bytemuck::Pod + Erasable>(slice: &'static [T]) -> Self {
+        Self::from_pod_slice(slice)
+    }
+}
+
+impl Debug for TypeErasedSlice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_slice(self.ptr, self.len, self.vtable, "TypeErasedSlice", f)
+    }
+}
+
+/// A type erased `&'a mut [T]`. Elements can be mutated in place, but not added or removed.
+///
+/// Unlike [TypeErasedVec::get_mut](crate::TypeErasedVec::get_mut), there's no guard to leak.
+///
+/// ```
+/// use type_erased_vec::{TypeErasedSliceMut, TypeErasedVec};
+///
+/// fn reverse(mut slice: TypeErasedSliceMut<'_>) {
+///     let len = slice.len();
+///     for i in 0..len / 2 {
+///         slice.swap(i, len - 1 - i);
+///     }
+/// }
+///
+/// let mut vec = TypeErasedVec::from_vec(vec![1, 2, 3]);
+/// reverse(vec.as_erased_slice_mut());
+/// assert_eq!(unsafe { vec.get::<i32>() }, [3, 2, 1]);
+///
+/// let mut strings = ["a".to_string(), "b".to_string()];
+/// reverse(TypeErasedSliceMut::from_mut_slice(&mut strings));
+/// assert_eq!(strings, ["b", "a"]);
+/// ```
+pub struct TypeErasedSliceMut<'a> {
+    ptr: *mut u8,
+    len: usize,
+    vtable: &'static ElementVTable,
+    _marker: PhantomData<&'a mut ()>,
+}
+
+impl<'a> TypeErasedSliceMut<'a> {
+    /// Erases the type of `slice`.
+    pub fn from_mut_slice<T: Erasable>(slice: &'a mut [T]) -> Self {
+        unsafe {
+            Self::from_raw_parts(
+                slice.as_mut_ptr().cast(),
+                slice.len(),
+                &TypedVTable::<T>::NEW.vtable,
+            )
+        }
+    }
+
+    /// Erases the type of `slice`, using `vtable` for the operations that don't know the element type.
+    pub fn from_mut_slice_with_vtable<T>(
+        slice: &'a mut [T],
+        vtable: &'static TypedVTable<T>,
+    ) -> Self {
+        unsafe { Self::from_raw_parts(slice.as_mut_ptr().cast(), slice.len(), vtable.erased()) }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must point to `len` contiguous elements described by `vtable`, valid for reads and writes and not aliased during `'a`.
+    pub(crate) unsafe fn from_raw_parts(
+        ptr: *mut u8,
+        len: usize,
+        vtable: &'static ElementVTable,
+    ) -> Self {
+        TypeErasedSliceMut {
+            ptr,
+            len,
+            vtable,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the vtable of the element type.
+    pub fn vtable(&self) -> &'static ElementVTable {
+        self.vtable
+    }
+
+    /// Returns a pointer to the first element.
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Returns the number of elements. See [slice::len].
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns if there are no elements. See [slice::is_empty].
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reborrows as a [TypeErasedSlice].
+    pub fn as_slice(&self) -> TypeErasedSlice<'_> {
+        unsafe { TypeErasedSlice::from_raw_parts(self.ptr, self.len, self.vtable) }
+    }
+
+    /// Reborrows for a shorter lifetime.
+    pub fn reborrow(&mut self) -> TypeErasedSliceMut<'_> {
+        unsafe { TypeErasedSliceMut::from_raw_parts(self.ptr, self.len, self.vtable) }
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Returns if the element type is `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.as_slice().is::<T>()
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Gets the elements as `&mut [T]`, or `None` if `T` is not the element type.
+    pub fn downcast<T: 'static>(self) -> Option<&'a mut [T]> {
+        if self.is::<T>() {
+            Some(unsafe { self.get() })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the elements as `&mut [T]`.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the element type.
+    pub unsafe fn get<T>(self) -> &'a mut [T] {
+        slice::from_raw_parts_mut(self.ptr.cast(), self.len)
+    }
+
+    /// Gets a handle to the element at `index`, or `None` if out of bounds. See [slice::get].
+    pub fn element(&self, index: usize) -> Option<ErasedRef<'_>> {
+        self.as_slice().element(index)
+    }
+
+    /// Gets a mutable handle to the element at `index`, or `None` if out of bounds. See [slice::get_mut].
+    pub fn element_mut(&mut self, index: usize) -> Option<ErasedMut<'_>> {
+        if index < self.len {
+            let ptr = unsafe { self.ptr.add(index * self.vtable.layout.size()) };
+            Some(unsafe { ErasedMut::new(ptr, self.vtable) })
+        } else {
+            None
+        }
+    }
+
+    /// Swaps the elements at `a` and `b`. See [slice::swap].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is out of bounds.
+    #[track_caller]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        assert!(a < self.len && b < self.len, "index out of bounds");
+        if a != b {
+            let size = self.vtable.layout.size();
+            unsafe {
+                ptr::swap_nonoverlapping(self.ptr.add(a * size), self.ptr.add(b * size), size)
+            }
+        }
+    }
+
+    /// Gets the elements as mutable bytes, or `None` if the element type is not known to be plain-old-data.
+    pub fn as_bytes_mut(self) -> Option<&'a mut [u8]> {
+        if self.vtable.pod {
+            Some(unsafe {
+                slice::from_raw_parts_mut(self.ptr, self.len * self.vtable.layout.size())
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl Debug for TypeErasedSliceMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_slice(self.ptr, self.len, self.vtable, "TypeErasedSliceMut", f)
+    }
+}
+
+/// Formats the elements with the vtable's debug function if there's one, or only their type name and count otherwise.
+fn debug_slice(
+    ptr: *const u8,
+    len: usize,
+    vtable: &'static ElementVTable,
+    name: &str,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    struct Elements(*const u8, usize, &'static ElementVTable);
+
+    impl Debug for Elements {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let debug = self.2.debug.unwrap();
+            unsafe { debug(self.0, self.1, f) }
+        }
+    }
+
+    let mut s = f.debug_struct(name);
+    s.field("type_name", &vtable.type_name()).field("len", &len);
+    match vtable.debug {
+        Some(_) => s.field("elements", &Elements(ptr, len, vtable)).finish(),
+        None => s.finish_non_exhaustive(),
+    }
+}
@@ -0,0 +1,210 @@
+//! A type erased vec behind a lock.
+
+use std::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::{Allocator, Global, SendSyncTypeErasedVec, VecMut};
+
+/// A [SendSyncTypeErasedVec] behind a [RwLock], for sharing a mutable buffer between threads,
+/// e.g. in a multithreaded asset cache.
+///
+/// With the `type-id` feature, [SyncTypeErasedVec::read] and [SyncTypeErasedVec::write] check the element type.
+///
+/// A panic while the lock is held doesn't make later accesses fail, as the vec is restored while unwinding.
+///
+/// ```
+/// use std::sync::Arc;
+/// use type_erased_vec::{SyncTypeErasedVec, TypeErasedVec};
+///
+/// let cache = Arc::new(SyncTypeErasedVec::new(TypeErasedVec::from_vec_sync(Vec::<String>::new())));
+/// let loader = cache.clone();
+/// std::thread::spawn(move || unsafe { loader.write_unchecked::<String>() }.push("mesh.obj".to_string()))
+///     .join()
+///     .unwrap();
+/// assert_eq!(*unsafe { cache.read_unchecked::<String>() }, ["mesh.obj"]);
+/// ```
+pub struct SyncTypeErasedVec<A: Allocator = Global> {
+    lock: RwLock<SendSyncTypeErasedVec<A>>,
+}
+
+impl<A: Allocator> SyncTypeErasedVec<A> {
+    /// Puts `vec` behind a lock.
+    pub fn new(vec: SendSyncTypeErasedVec<A>) -> Self {
+        SyncTypeErasedVec {
+            lock: RwLock::new(vec),
+        }
+    }
+
+    /// Unwraps the underlying vec.
+    pub fn into_inner(self) -> SendSyncTypeErasedVec<A> {
+        self.lock
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Gets the underlying vec mutably, without locking as `self` is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut SendSyncTypeErasedVec<A> {
+        self.lock.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks for shared access to the elements without knowing their type.
+    pub fn read_erased(&self) -> RwLockReadGuard<'_, SendSyncTypeErasedVec<A>> {
+        self.lock.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks for exclusive access to the elements without knowing their type.
+    pub fn write_erased(&self) -> RwLockWriteGuard<'_, SendSyncTypeErasedVec<A>> {
+        self.lock.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Locks for shared access to the elements as `&[T]`, or returns `None` if `T` is not the element type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vec is leaked.
+    #[track_caller]
+    pub fn read<T: 'static>(&self) -> Option<ReadGuard<'_, T, A>> {
+        let guard = self.read_erased();
+        if guard.is::<T>() {
+            Some(ReadGuard::new(guard))
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Locks for exclusive access to the elements as `Vec<T>`, or returns `None` if `T` is not the element type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vec is leaked.
+    #[track_caller]
+    pub fn write<T: 'static>(&self) -> Option<WriteGuard<'_, T, A>> {
+        let guard = self.write_erased();
+        if guard.is::<T>() {
+            Some(unsafe { WriteGuard::new(guard) })
+        } else {
+            None
+        }
+    }
+
+    /// Locks for shared access to the elements as `&[T]`.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the element type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vec is leaked.
+    #[track_caller]
+    pub unsafe fn read_unchecked<T>(&self) -> ReadGuard<'_, T, A> {
+        ReadGuard::new(self.read_erased())
+    }
+
+    /// Locks for exclusive access to the elements as `Vec<T>`.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the element type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vec is leaked.
+    #[track_caller]
+    pub unsafe fn write_unchecked<T>(&self) -> WriteGuard<'_, T, A> {
+        WriteGuard::new(self.write_erased())
+    }
+}
+
+impl<A: Allocator> From<SendSyncTypeErasedVec<A>> for SyncTypeErasedVec<A> {
+    fn from(vec: SendSyncTypeErasedVec<A>) -> Self {
+        Self::new(vec)
+    }
+}
+
+impl<A: Allocator + Debug> Debug for SyncTypeErasedVec<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncTypeErasedVec")
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+/// Shared access to the elements of a [SyncTypeErasedVec] as `&[T]`. See [SyncTypeErasedVec::read].
+pub struct ReadGuard<'a, T, A: Allocator = Global> {
+    guard: RwLockReadGuard<'a, SendSyncTypeErasedVec<A>>,
+    _marker: PhantomData<&'a [T]>,
+}
+
+impl<'a, T, A: Allocator> ReadGuard<'a, T, A> {
+    #[track_caller]
+    fn new(guard: RwLockReadGuard<'a, SendSyncTypeErasedVec<A>>) -> Self {
+        raw!(guard.as_erased());
+        ReadGuard {
+            guard,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, A: Allocator> Deref for ReadGuard<'_, T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { self.guard.get() }
+    }
+}
+
+impl<T: Debug, A: Allocator> Debug for ReadGuard<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ReadGuard").field(&&**self).finish()
+    }
+}
+
+/// Exclusive access to the elements of a [SyncTypeErasedVec] as `Vec<T>`. See [SyncTypeErasedVec::write].
+pub struct WriteGuard<'a, T, A: Allocator = Global> {
+    /// Borrows from `guard`, so it's declared first to be dropped, restoring the vec, before unlocking.
+    vec: VecMut<'a, T, A>,
+    _guard: RwLockWriteGuard<'a, SendSyncTypeErasedVec<A>>,
+}
+
+impl<'a, T, A: Allocator> WriteGuard<'a, T, A> {
+    /// # Safety
+    ///
+    /// `T` must be the element type.
+    #[track_caller]
+    unsafe fn new(mut guard: RwLockWriteGuard<'a, SendSyncTypeErasedVec<A>>) -> Self {
+        // The vec stays in the lock, so the pointer outlives moving the guard.
+        let vec: *mut SendSyncTypeErasedVec<A> = &mut *guard;
+        WriteGuard {
+            vec: (*vec).get_mut(),
+            _guard: guard,
+        }
+    }
+}
+
+impl<'a, T, A: Allocator> Deref for WriteGuard<'a, T, A> {
+    type Target = <VecMut<'a, T, A> as Deref>::Target;
+
+    fn deref(&self) -> &Self::Target {
+        &self.vec
+    }
+}
+
+impl<T, A: Allocator> DerefMut for WriteGuard<'_, T, A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.vec
+    }
+}
+
+impl<T: Debug, A: Allocator> Debug for WriteGuard<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WriteGuard").field(&&**self).finish()
+    }
+}
@@ -0,0 +1,177 @@
+//! Mutation with dirty range tracking.
+
+use std::{
+    fmt::{self, Debug},
+    ops::{Deref, Range, RangeBounds},
+};
+
+use crate::{resolve_range, Allocator, TypeErasedVec, VecMut};
+
+/// Like [VecMut], but only mutates through methods that record which bytes were touched,
+/// e.g. to upload only the changed parts of a GPU buffer. See [TypeErasedVec::get_mut_tracked].
+///
+/// ```
+/// use type_erased_vec::TypeErasedVec;
+///
+/// let mut vertices = TypeErasedVec::from_vec(vec![[0.0f32; 2]; 100]);
+/// let mut tracked = unsafe { vertices.get_mut_tracked::<[f32; 2]>() };
+/// tracked.set(10, [1.0, 1.0]);
+/// tracked.range_mut(11..13).fill([2.0, 2.0]);
+/// tracked.set(50, [3.0, 3.0]);
+/// assert_eq!(tracked.into_dirty_ranges(), [80..104, 400..408]);
+/// ```
+pub struct TrackedVecMut<'a, T, A: Allocator> {
+    vec: VecMut<'a, T, A>,
+    /// Sorted, non overlapping and non adjacent byte ranges.
+    dirty: Vec<Range<usize>>,
+}
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Gets a smart pointer to `mut Vec<T>` recording the byte ranges mutated through it.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the element type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub unsafe fn get_mut_tracked<T>(&mut self) -> TrackedVecMut<'_, T, A> {
+        TrackedVecMut {
+            vec: self.get_mut(),
+            dirty: Vec::new(),
+        }
+    }
+}
+
+impl<T, A: Allocator> TrackedVecMut<'_, T, A> {
+    /// Returns the sorted and merged byte ranges touched so far.
+    pub fn dirty_ranges(&self) -> &[Range<usize>] {
+        &self.dirty
+    }
+
+    /// Releases the vec, returning the touched byte ranges.
+    pub fn into_dirty_ranges(self) -> Vec<Range<usize>> {
+        self.dirty
+    }
+
+    /// Forgets the touched ranges, e.g. after uploading them.
+    pub fn clear_dirty(&mut self) {
+        self.dirty.clear();
+    }
+
+    /// Marks the elements in `range` as touched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    #[track_caller]
+    pub fn mark_dirty(&mut self, range: impl RangeBounds<usize>) {
+        let range = resolve_range(range, self.vec.len());
+        self.mark(range);
+    }
+
+    /// Gets a mutable reference to the element at `index`, marking it as touched, or `None` if out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.vec.len() {
+            self.mark(index..index + 1);
+            self.vec.get_mut(index)
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn set(&mut self, index: usize, value: T) {
+        self.range_mut(index..=index)[0] = value;
+    }
+
+    /// Gets the elements in `range` mutably, marking them as touched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    #[track_caller]
+    pub fn range_mut(&mut self, range: impl RangeBounds<usize>) -> &mut [T] {
+        let range = resolve_range(range, self.vec.len());
+        self.mark(range.clone());
+        &mut self.vec[range]
+    }
+
+    /// Swaps the elements at `a` and `b`, marking both as touched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is out of bounds.
+    #[track_caller]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.vec.swap(a, b);
+        self.mark(a..a + 1);
+        self.mark(b..b + 1);
+    }
+
+    /// Appends `value`, marking it as touched.
+    pub fn push(&mut self, value: T) {
+        let len = self.vec.len();
+        self.vec.push(value);
+        self.mark(len..len + 1);
+    }
+
+    /// Appends the elements of `iter`, marking them as touched.
+    pub fn extend(&mut self, iter: impl IntoIterator<Item = T>) {
+        let len = self.vec.len();
+        self.vec.extend(iter);
+        let new_len = self.vec.len();
+        self.mark(len..new_len);
+    }
+
+    /// Removes the last element. Nothing is marked as touched, as the remaining bytes are unchanged.
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    /// Shortens to `len` elements. Nothing is marked as touched, as the remaining bytes are unchanged.
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len);
+    }
+
+    /// Records the bytes of the elements in `range`, merging with overlapping or adjacent ranges.
+    fn mark(&mut self, range: Range<usize>) {
+        let size = size_of::<T>();
+        let range = range.start * size..range.end * size;
+        if range.is_empty() {
+            return;
+        }
+        let first = self.dirty.partition_point(|dirty| dirty.end < range.start);
+        let last = self.dirty.partition_point(|dirty| dirty.start <= range.end);
+        let merged = if first < last {
+            range.start.min(self.dirty[first].start)..range.end.max(self.dirty[last - 1].end)
+        } else {
+            range
+        };
+        self.dirty.splice(first..last, [merged]);
+    }
+}
+
+impl<T, A: Allocator> Deref for TrackedVecMut<'_, T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T: Debug, A: Allocator> Debug for TrackedVecMut<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrackedVecMut")
+            .field("elements", &&**self)
+            .field("dirty", &self.dirty)
+            .finish()
+    }
+}
@@ -0,0 +1,155 @@
+//! Ownership of many type erased vecs behind stable handles.
+
+use crate::{Allocator, Global, TypeErasedVec};
+
+/// A handle to a vec in an [ErasedVecRegistry].
+///
+/// It stays valid until the vec is removed, and is never mistaken for a handle to a vec inserted afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BufferId {
+    index: u32,
+    generation: u32,
+}
+
+/// Owns [TypeErasedVec]s of any element types and hands out [BufferId]s for them,
+/// e.g. for a renderer or an ECS world to keep runtime typed buffers in one place.
+///
+/// ```
+/// use type_erased_vec::{ErasedVecRegistry, TypeErasedVec};
+///
+/// let mut buffers = ErasedVecRegistry::new();
+/// let positions = buffers.insert(TypeErasedVec::from_vec(vec![[0.0f32; 3]; 8]));
+/// let indices = buffers.insert(TypeErasedVec::from_vec(vec![0u16, 1, 2]));
+/// assert_eq!(buffers.get(indices).unwrap().len(), 3);
+///
+/// buffers.remove(positions);
+/// assert!(buffers.get(positions).is_none());
+/// ```
+#[derive(Debug)]
+pub struct ErasedVecRegistry<A: Allocator = Global> {
+    slots: Vec<Slot<A>>,
+    /// Indices of the empty slots.
+    free: Vec<u32>,
+    /// The number of empty slots whose generation can't be bumped anymore, which are never reused.
+    retired: usize,
+}
+
+#[derive(Debug)]
+struct Slot<A: Allocator> {
+    /// Bumped whenever the vec is removed, invalidating its ids.
+    generation: u32,
+    vec: Option<TypeErasedVec<A>>,
+}
+
+impl<A: Allocator> ErasedVecRegistry<A> {
+    /// Constructs an empty registry.
+    pub const fn new() -> Self {
+        ErasedVecRegistry {
+            slots: Vec::new(),
+            free: Vec::new(),
+            retired: 0,
+        }
+    }
+
+    /// Takes ownership of `vec`, returning its id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `u32::MAX` vecs.
+    pub fn insert(&mut self, vec: TypeErasedVec<A>) -> BufferId {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("too many vecs");
+                self.slots.push(Slot {
+                    generation: 0,
+                    vec: None,
+                });
+                index
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.vec = Some(vec);
+        BufferId {
+            index,
+            generation: slot.generation,
+        }
+    }
+
+    /// Gets the vec of `id`, or `None` if it was removed.
+    pub fn get(&self, id: BufferId) -> Option<&TypeErasedVec<A>> {
+        match self.slots.get(id.index as usize) {
+            Some(slot) if slot.generation == id.generation => slot.vec.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Gets the vec of `id` mutably, or `None` if it was removed.
+    pub fn get_mut(&mut self, id: BufferId) -> Option<&mut TypeErasedVec<A>> {
+        match self.slots.get_mut(id.index as usize) {
+            Some(slot) if slot.generation == id.generation => slot.vec.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// Returns if `id` refers to a vec in the registry.
+    pub fn contains(&self, id: BufferId) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Removes and returns the vec of `id`, or `None` if it was already removed.
+    pub fn remove(&mut self, id: BufferId) -> Option<TypeErasedVec<A>> {
+        let slot = self.slots.get_mut(id.index as usize)?;
+        if slot.generation != id.generation {
+            return None;
+        }
+        let vec = slot.vec.take()?;
+        // Reusing the slot once its generation wraps would let old ids refer to new vecs.
+        match slot.generation.checked_add(1) {
+            Some(generation) => {
+                slot.generation = generation;
+                self.free.push(id.index);
+            }
+            None => self.retired += 1,
+        }
+        Some(vec)
+    }
+
+    /// Returns the number of vecs.
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len() - self.retired
+    }
+
+    /// Returns if there are no vecs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the vecs and their ids.
+    pub fn iter(&self) -> impl Iterator<Item = (BufferId, &TypeErasedVec<A>)> + '_ {
+        self.slots.iter().zip(0..).filter_map(|(slot, index)| {
+            let id = BufferId {
+                index,
+                generation: slot.generation,
+            };
+            Some((id, slot.vec.as_ref()?))
+        })
+    }
+
+    /// Iterates over the vecs mutably and their ids.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (BufferId, &mut TypeErasedVec<A>)> + '_ {
+        self.slots.iter_mut().zip(0..).filter_map(|(slot, index)| {
+            let id = BufferId {
+                index,
+                generation: slot.generation,
+            };
+            Some((id, slot.vec.as_mut()?))
+        })
+    }
+}
+
+impl<A: Allocator> Default for ErasedVecRegistry<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
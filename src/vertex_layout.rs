@@ -0,0 +1,118 @@
+//! Descriptors of vertex buffer layouts.
+
+use std::ops::Range;
+
+use crate::ElementVTable;
+
+/// Where the elements of one attribute are in a vertex buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct AttributeLayout {
+    /// The offset of the first element in bytes.
+    pub offset: usize,
+    /// The distance between consecutive elements in bytes.
+    pub stride: usize,
+    /// The element type.
+    pub format: &'static ElementVTable,
+}
+
+impl AttributeLayout {
+    /// Returns the size of an element in bytes.
+    pub fn size(&self) -> usize {
+        self.format.layout().size()
+    }
+
+    /// Returns the byte range of the element of vertex `index`.
+    pub fn range(&self, index: usize) -> Range<usize> {
+        let start = self.offset + index * self.stride;
+        start..start + self.size()
+    }
+}
+
+/// The layout of an interleaved vertex buffer: its stride and the attributes in each vertex.
+///
+/// ```
+/// use type_erased_vec::{BufferLayout, ScalarType, TypeErasedVec};
+///
+/// let layout = BufferLayout::packed(&[ScalarType::F64.vtable(), ScalarType::U8.vtable()]);
+/// assert_eq!(layout.stride(), 16);
+/// assert_eq!(layout.attributes()[1].offset, 8);
+///
+/// let weights = TypeErasedVec::from_scalar_vec(vec![0.5f64]);
+/// let ids = TypeErasedVec::from_scalar_vec(vec![1u8]);
+/// let vertices = TypeErasedVec::interleave(&[weights.as_erased_slice(), ids.as_erased_slice()], &layout);
+/// let columns = TypeErasedVec::deinterleave(vertices.as_bytes().unwrap(), &layout);
+/// assert_eq!(unsafe { columns[1].get::<u8>() }, [1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BufferLayout {
+    stride: usize,
+    attributes: Vec<AttributeLayout>,
+}
+
+impl BufferLayout {
+    /// Constructs a layout of `stride` bytes per vertex without attributes.
+    pub fn new(stride: usize) -> Self {
+        BufferLayout {
+            stride,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Constructs a layout of attributes of `formats` in order, each at the next offset aligned for it,
+    /// with the stride rounded up to the largest alignment, the same as a `#[repr(C)]` struct.
+    pub fn packed(formats: &[&'static ElementVTable]) -> Self {
+        let mut offsets = Vec::with_capacity(formats.len());
+        let mut end = 0usize;
+        let mut align = 1;
+        for format in formats {
+            let layout = format.layout();
+            let offset = end.next_multiple_of(layout.align());
+            offsets.push(offset);
+            end = offset + layout.size();
+            align = align.max(layout.align());
+        }
+        let mut layout = BufferLayout::new(end.next_multiple_of(align));
+        for (&format, offset) in formats.iter().zip(offsets) {
+            layout = layout.with_attribute(offset, format);
+        }
+        layout
+    }
+
+    /// Adds an attribute of `format` at `offset` in each vertex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element doesn't fit in the stride at `offset`.
+    #[track_caller]
+    pub fn with_attribute(mut self, offset: usize, format: &'static ElementVTable) -> Self {
+        let size = format.layout().size();
+        assert!(
+            offset
+                .checked_add(size)
+                .is_some_and(|end| end <= self.stride),
+            "element of {size} bytes at offset {offset} exceeds stride {}",
+            self.stride
+        );
+        self.attributes.push(AttributeLayout {
+            offset,
+            stride: self.stride,
+            format,
+        });
+        self
+    }
+
+    /// Returns the size of a vertex in bytes.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the attributes, in the order they were added.
+    pub fn attributes(&self) -> &[AttributeLayout] {
+        &self.attributes
+    }
+
+    /// Returns the number of whole vertices in `len` bytes.
+    pub fn vertex_count(&self, len: usize) -> usize {
+        len.checked_div(self.stride).unwrap_or(0)
+    }
+}
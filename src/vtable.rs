@@ -0,0 +1,507 @@
+//! Per element type operations, shared by all [TypeErasedVec](crate::TypeErasedVec)s of that type.
+
+#[cfg(feature = "type-id")]
+use std::any::TypeId;
+use std::{
+    alloc::Layout,
+    any::type_name,
+    cmp::Ordering,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::forget,
+    ops::Deref,
+    ptr::{drop_in_place, slice_from_raw_parts_mut},
+    slice,
+};
+
+#[cfg(feature = "serde")]
+use crate::{raw::RawVec, TypeErasedVec};
+use crate::{Erasable, Scalar, ScalarType};
+
+/// Drops a buffer of `len` elements.
+pub(crate) type DropFn = unsafe fn(*mut u8, usize);
+
+/// Clones a buffer of `len` elements into uninitialized memory.
+pub(crate) type CloneFn = unsafe fn(*const u8, *mut u8, usize);
+
+#[cfg(feature = "type-id")]
+/// Compares two buffers of `len` elements.
+pub(crate) type EqFn = unsafe fn(*const u8, *const u8, usize) -> bool;
+
+/// Compares two elements.
+pub(crate) type CmpFn = unsafe fn(*const u8, *const u8) -> Ordering;
+
+/// Hashes a buffer of `len` elements.
+pub(crate) type HashFn = unsafe fn(*const u8, usize, &mut dyn Hasher);
+
+/// Formats a buffer of `len` elements.
+pub(crate) type DebugFn = unsafe fn(*const u8, usize, &mut fmt::Formatter<'_>) -> fmt::Result;
+
+#[cfg(feature = "serde")]
+/// Passes a buffer of `len` elements to the callback as a serializable slice.
+pub(crate) type SerializeFn =
+    unsafe fn(*const u8, usize, &mut dyn FnMut(&dyn erased_serde::Serialize));
+
+#[cfg(feature = "serde")]
+/// Deserializes a sequence of elements into a vec described by the given vtable.
+pub(crate) type DeserializeFn = for<'de> fn(
+    &mut dyn erased_serde::Deserializer<'de>,
+    &'static ElementVTable,
+) -> Result<TypeErasedVec, erased_serde::Error>;
+
+#[derive(Clone, Copy)]
+/// Describes an element type and the operations a [TypeErasedVec](crate::TypeErasedVec) can perform on it without knowing the type.
+///
+/// An `ElementVTable` is always built from a [TypedVTable].
+pub struct ElementVTable {
+    pub(crate) layout: Layout,
+    pub(crate) type_name: fn() -> &'static str,
+    #[cfg(feature = "type-id")]
+    pub(crate) type_id: fn() -> TypeId,
+    pub(crate) drop: DropFn,
+    pub(crate) clone: Option<CloneFn>,
+    #[cfg(feature = "type-id")]
+    pub(crate) eq: Option<EqFn>,
+    pub(crate) cmp: Option<CmpFn>,
+    pub(crate) hash: Option<HashFn>,
+    pub(crate) debug: Option<DebugFn>,
+    #[cfg(feature = "serde")]
+    pub(crate) serialize: Option<SerializeFn>,
+    #[cfg(feature = "serde")]
+    pub(crate) deserialize: Option<DeserializeFn>,
+    /// If the element type is plain-old-data, so the buffer can be viewed as bytes.
+    pub(crate) pod: bool,
+    /// If the all zero bit pattern is a valid element.
+    pub(crate) zeroable: bool,
+    pub(crate) scalar_type: Option<ScalarType>,
+}
+
+impl ElementVTable {
+    /// Returns the size and alignment of the element type.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Drops `len` elements starting at `ptr`, e.g. ones from [TypeErasedVec::into_raw_parts](crate::TypeErasedVec::into_raw_parts).
+    ///
+    /// # Safety
+    ///
+    /// See [std::ptr::drop_in_place]. `ptr` must point to `len` initialized elements of the type the vtable describes.
+    pub unsafe fn drop_in_place(&self, ptr: *mut u8, len: usize) {
+        (self.drop)(ptr, len)
+    }
+
+    /// Returns the name of the element type. See [std::any::type_name].
+    pub fn type_name(&self) -> &'static str {
+        (self.type_name)()
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Returns the [TypeId] of the element type.
+    pub fn type_id(&self) -> TypeId {
+        (self.type_id)()
+    }
+
+    /// Returns if elements can be cloned.
+    pub fn is_clone(&self) -> bool {
+        self.clone.is_some()
+    }
+
+    #[cfg(feature = "type-id")]
+    /// Returns if elements can be compared.
+    pub fn is_eq(&self) -> bool {
+        self.eq.is_some()
+    }
+
+    /// Returns if elements can be ordered.
+    pub fn is_ord(&self) -> bool {
+        self.cmp.is_some()
+    }
+
+    /// Returns if elements can be hashed.
+    pub fn is_hash(&self) -> bool {
+        self.hash.is_some()
+    }
+
+    /// Returns if elements can be formatted.
+    pub fn is_debug(&self) -> bool {
+        self.debug.is_some()
+    }
+
+    #[cfg(feature = "serde")]
+    /// Returns if elements can be serialized.
+    pub fn is_serialize(&self) -> bool {
+        self.serialize.is_some()
+    }
+
+    #[cfg(feature = "serde")]
+    /// Returns if elements can be deserialized. See [ErasedVecSeed](crate::ErasedVecSeed).
+    pub fn is_deserialize(&self) -> bool {
+        self.deserialize.is_some()
+    }
+
+    /// Returns if the element type is plain-old-data.
+    pub fn is_pod(&self) -> bool {
+        self.pod
+    }
+
+    /// Returns if the all zero bit pattern is a valid element.
+    pub fn is_zeroable(&self) -> bool {
+        self.zeroable
+    }
+
+    /// Returns the scalar type of the elements, or `None` if they're not known to be a [Scalar].
+    pub fn scalar_type(&self) -> Option<ScalarType> {
+        self.scalar_type
+    }
+}
+
+impl Debug for ElementVTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("ElementVTable");
+        s.field("type_name", &self.type_name())
+            .field("layout", &self.layout)
+            .field("clone", &self.is_clone())
+            .field("ord", &self.is_ord())
+            .field("hash", &self.is_hash())
+            .field("debug", &self.is_debug());
+        #[cfg(feature = "serde")]
+        s.field("serialize", &self.is_serialize())
+            .field("deserialize", &self.is_deserialize());
+        s.field("pod", &self.pod)
+            .field("zeroable", &self.zeroable)
+            .field("scalar_type", &self.scalar_type)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An [ElementVTable] known to describe `T`.
+///
+/// Start from [TypedVTable::new] and add capabilities with the `with_*` methods. As these are `const`, a vtable can be built into a `static`:
+///
+/// ```
+/// use type_erased_vec::{TypeErasedVec, TypedVTable};
+///
+/// static VTABLE: TypedVTable<String> = TypedVTable::new().with_clone().with_debug();
+///
+/// let vec = TypeErasedVec::from_vec_with_vtable(vec![String::from("a")], &VTABLE);
+/// assert_eq!(unsafe { vec.clone().get::<String>() }, ["a"]);
+/// ```
+pub struct TypedVTable<T> {
+    pub(crate) vtable: ElementVTable,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Element types that carry their own vtable, so they can be erased with all their capabilities.
+///
+/// Implement it with `#[derive(ErasedElement)]`, which requires the `derive` feature, or by hand:
+///
+/// ```
+/// use type_erased_vec::{ErasedElement, TypeErasedVec, TypedVTable};
+///
+/// #[derive(Clone, Debug)]
+/// struct Name(String);
+///
+/// impl ErasedElement for Name {
+///     const VTABLE: TypedVTable<Self> = TypedVTable::new().with_clone().with_debug();
+/// }
+///
+/// let vec = TypeErasedVec::from_element_vec(vec![Name("a".into())]);
+/// assert!(format!("{:?}", vec.clone()).contains("Name(\"a\")"));
+/// ```
+pub trait ErasedElement: Erasable + Sized {
+    /// The vtable used by [TypeErasedVec::from_element_vec](crate::TypeErasedVec::from_element_vec) and friends.
+    const VTABLE: TypedVTable<Self>;
+}
+
+impl<T: Erasable> TypedVTable<T> {
+    /// A vtable that only knows how to drop `T`.
+    pub(crate) const NEW: Self = Self::new();
+
+    /// Constructs a vtable that only knows how to drop `T`.
+    pub const fn new() -> Self {
+        TypedVTable {
+            vtable: ElementVTable {
+                layout: Layout::new::<T>(),
+                type_name: type_name::<T>,
+                #[cfg(feature = "type-id")]
+                type_id: TypeId::of::<T>,
+                drop: drop_elements::<T>,
+                clone: None,
+                #[cfg(feature = "type-id")]
+                eq: None,
+                cmp: None,
+                hash: None,
+                debug: None,
+                #[cfg(feature = "serde")]
+                serialize: None,
+                #[cfg(feature = "serde")]
+                deserialize: None,
+                pod: false,
+                zeroable: false,
+                scalar_type: None,
+            },
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Erasable> Default for TypedVTable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TypedVTable<T> {
+    /// Gets the type erased vtable.
+    pub const fn erased(&self) -> &ElementVTable {
+        &self.vtable
+    }
+}
+
+impl<T: Clone + Erasable> TypedVTable<T> {
+    /// A vtable that knows how to clone `T`.
+    pub(crate) const CLONE: Self = Self::new().with_clone();
+
+    /// Adds cloning.
+    pub const fn with_clone(mut self) -> Self {
+        self.vtable.clone = Some(clone_elements::<T>);
+        self
+    }
+}
+
+#[cfg(feature = "type-id")]
+impl<T: PartialEq + Erasable> TypedVTable<T> {
+    /// A vtable that knows how to compare `T`.
+    pub(crate) const EQ: Self = Self::new().with_eq();
+
+    /// Adds equality comparison.
+    pub const fn with_eq(mut self) -> Self {
+        self.vtable.eq = Some(eq_elements::<T>);
+        self
+    }
+}
+
+impl<T: Ord + Erasable> TypedVTable<T> {
+    /// A vtable that knows how to order `T`.
+    pub(crate) const ORD: Self = Self::new().with_ord();
+
+    /// Adds total ordering.
+    pub const fn with_ord(mut self) -> Self {
+        self.vtable.cmp = Some(cmp_elements::<T>);
+        self
+    }
+}
+
+impl<T: Hash + Erasable> TypedVTable<T> {
+    /// A vtable that knows how to hash `T`.
+    pub(crate) const HASH: Self = Self::new().with_hash();
+
+    /// Adds hashing.
+    pub const fn with_hash(mut self) -> Self {
+        self.vtable.hash = Some(hash_elements::<T>);
+        self
+    }
+}
+
+impl<T: Debug + Erasable> TypedVTable<T> {
+    /// A vtable that knows how to format `T`.
+    pub(crate) const DEBUG: Self = Self::new().with_debug();
+
+    /// Adds formatting.
+    pub const fn with_debug(mut self) -> Self {
+        self.vtable.debug = Some(debug_elements::<T>);
+        self
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + Erasable> TypedVTable<T> {
+    /// A vtable that knows how to serialize `T`.
+    pub(crate) const SERIALIZE: Self = Self::new().with_serialize();
+
+    /// Adds serialization.
+    pub const fn with_serialize(mut self) -> Self {
+        self.vtable.serialize = Some(serialize_elements::<T>);
+        self
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::de::DeserializeOwned + Erasable> TypedVTable<T> {
+    /// Adds deserialization. See [ErasedVecSeed](crate::ErasedVecSeed).
+    pub const fn with_deserialize(mut self) -> Self {
+        self.vtable.deserialize = Some(deserialize_elements::<T>);
+        self
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T: bytemuck::Pod + Erasable> TypedVTable<T> {
+    /// A vtable that knows `T` is plain-old-data.
+    pub(crate) const POD: Self = Self::new().with_pod();
+
+    /// Marks `T` as plain-old-data, which implies zeroable.
+    pub const fn with_pod(mut self) -> Self {
+        self.vtable.pod = true;
+        self.vtable.zeroable = true;
+        self
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T: bytemuck::Zeroable + Erasable> TypedVTable<T> {
+    /// A vtable that knows the all zero bit pattern is a valid `T`.
+    pub(crate) const ZEROABLE: Self = Self::new().with_zeroable();
+
+    /// Marks the all zero bit pattern as a valid `T`.
+    pub const fn with_zeroable(mut self) -> Self {
+        self.vtable.zeroable = true;
+        self
+    }
+}
+
+impl<T: Scalar> TypedVTable<T> {
+    /// A vtable that knows everything [Scalar] provides about `T`.
+    pub(crate) const SCALAR: Self = {
+        let vtable = Self::new().with_clone().with_debug().with_scalar();
+        #[cfg(feature = "type-id")]
+        let vtable = vtable.with_eq();
+        vtable
+    };
+
+    /// Records the [ScalarType] of `T`, which also marks it as plain-old-data.
+    pub const fn with_scalar(mut self) -> Self {
+        self.vtable.scalar_type = Some(T::SCALAR_TYPE);
+        self.vtable.pod = true;
+        self.vtable.zeroable = true;
+        self
+    }
+}
+
+impl<T> Deref for TypedVTable<T> {
+    type Target = ElementVTable;
+
+    fn deref(&self) -> &ElementVTable {
+        &self.vtable
+    }
+}
+
+impl<T> Clone for TypedVTable<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypedVTable<T> {}
+
+impl<T> Debug for TypedVTable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.vtable.fmt(f)
+    }
+}
+
+/// Drops `len` elements of type `T` starting at `ptr`.
+///
+/// # Safety
+///
+/// See [std::ptr::drop_in_place].
+unsafe fn drop_elements<T>(ptr: *mut u8, len: usize) {
+    drop_in_place(slice_from_raw_parts_mut(ptr.cast::<T>(), len));
+}
+
+/// # Safety
+///
+/// `src` must point to `len` elements of type `T`, and `dst` must be valid for writing `len` elements of type `T`.
+unsafe fn clone_elements<T: Clone>(src: *const u8, dst: *mut u8, len: usize) {
+    /// Drops the elements cloned so far if a `clone` panics.
+    struct Guard<T> {
+        dst: *mut T,
+        initialized: usize,
+    }
+
+    impl<T> Drop for Guard<T> {
+        fn drop(&mut self) {
+            unsafe { drop_in_place(slice_from_raw_parts_mut(self.dst, self.initialized)) }
+        }
+    }
+
+    let src = slice::from_raw_parts(src.cast::<T>(), len);
+    let mut guard = Guard {
+        dst: dst.cast::<T>(),
+        initialized: 0,
+    };
+    for element in src {
+        guard.dst.add(guard.initialized).write(element.clone());
+        guard.initialized += 1;
+    }
+    forget(guard);
+}
+
+#[cfg(feature = "type-id")]
+/// # Safety
+///
+/// `a` and `b` must both point to `len` elements of type `T`.
+unsafe fn eq_elements<T: PartialEq>(a: *const u8, b: *const u8, len: usize) -> bool {
+    let a = slice::from_raw_parts(a.cast::<T>(), len);
+    let b = slice::from_raw_parts(b.cast::<T>(), len);
+    a == b
+}
+
+/// # Safety
+///
+/// `a` and `b` must both point to an element of type `T`.
+unsafe fn cmp_elements<T: Ord>(a: *const u8, b: *const u8) -> Ordering {
+    (*a.cast::<T>()).cmp(&*b.cast::<T>())
+}
+
+/// # Safety
+///
+/// `ptr` must point to `len` elements of type `T`.
+unsafe fn hash_elements<T: Hash>(ptr: *const u8, len: usize, mut state: &mut dyn Hasher) {
+    slice::from_raw_parts(ptr.cast::<T>(), len).hash(&mut state);
+}
+
+/// Maximum number of elements [debug_elements] prints.
+const DEBUG_LIMIT: usize = 32;
+
+/// # Safety
+///
+/// `ptr` must point to `len` elements of type `T`.
+unsafe fn debug_elements<T: Debug>(
+    ptr: *const u8,
+    len: usize,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    let slice = slice::from_raw_parts(ptr.cast::<T>(), len);
+    let mut list = f.debug_list();
+    list.entries(slice.iter().take(DEBUG_LIMIT));
+    if len > DEBUG_LIMIT {
+        list.finish_non_exhaustive()
+    } else {
+        list.finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+/// # Safety
+///
+/// `ptr` must point to `len` elements of type `T`.
+unsafe fn serialize_elements<T: serde::Serialize>(
+    ptr: *const u8,
+    len: usize,
+    f: &mut dyn FnMut(&dyn erased_serde::Serialize),
+) {
+    f(&slice::from_raw_parts(ptr.cast::<T>(), len));
+}
+
+#[cfg(feature = "serde")]
+/// `vtable` must describe `T`.
+fn deserialize_elements<T: serde::de::DeserializeOwned>(
+    deserializer: &mut dyn erased_serde::Deserializer<'_>,
+    vtable: &'static ElementVTable,
+) -> Result<TypeErasedVec, erased_serde::Error> {
+    let vec = erased_serde::deserialize::<Vec<T>>(deserializer)?;
+    Ok(unsafe { TypeErasedVec::from_raw(RawVec::from_vec(vec), vtable) })
+}
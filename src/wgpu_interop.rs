@@ -0,0 +1,114 @@
+//! Uploading to and reading back from `wgpu` buffers.
+
+use wgpu::{BufferAddress, BufferDescriptor, BufferUsages, COPY_BUFFER_ALIGNMENT};
+
+use crate::{missing_capability, Allocator, Error, Global, ScalarType, TypeErasedVec};
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Returns the size of the buffer [TypeErasedVec::create_wgpu_buffer] creates, which is the byte length
+    /// rounded up to [wgpu::COPY_BUFFER_ALIGNMENT].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn wgpu_buffer_size(&self) -> BufferAddress {
+        let len = raw!(self).len() * self.vtable.layout.size();
+        padded_size(len as BufferAddress)
+    }
+
+    /// Creates a buffer holding the elements, with its size padded to [wgpu::COPY_BUFFER_ALIGNMENT] with zeros.
+    ///
+    /// ```
+    /// use type_erased_vec::TypeErasedVec;
+    ///
+    /// let (device, _queue) = wgpu::Device::noop(&Default::default());
+    /// let indices = TypeErasedVec::from_pod_vec(vec![0u16, 1, 2]);
+    /// let buffer = indices.create_wgpu_buffer(&device, Some("indices"), wgpu::BufferUsages::INDEX);
+    /// assert_eq!(buffer.size(), 8);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data, or if `self` is leaked.
+    #[track_caller]
+    pub fn create_wgpu_buffer(
+        &self,
+        device: &wgpu::Device,
+        label: Option<&str>,
+        usage: BufferUsages,
+    ) -> wgpu::Buffer {
+        let Some(bytes) = self.as_bytes() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        let size = padded_size(bytes.len() as BufferAddress);
+        if bytes.is_empty() {
+            return device.create_buffer(&BufferDescriptor {
+                label,
+                size,
+                usage,
+                mapped_at_creation: false,
+            });
+        }
+        let buffer = device.create_buffer(&BufferDescriptor {
+            label,
+            size,
+            usage,
+            mapped_at_creation: true,
+        });
+        buffer
+            .get_mapped_range_mut(..)
+            .expect("buffer is mapped at creation")
+            .slice(..bytes.len())
+            .copy_from_slice(bytes);
+        buffer.unmap();
+        buffer
+    }
+
+    /// Schedules writing the elements to `buffer` at `offset`, padding them to [wgpu::COPY_BUFFER_ALIGNMENT] with zeros.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element type is not known to be plain-old-data, or if `self` is leaked.
+    #[track_caller]
+    pub fn write_wgpu_buffer(
+        &self,
+        queue: &wgpu::Queue,
+        buffer: &wgpu::Buffer,
+        offset: BufferAddress,
+    ) {
+        let Some(bytes) = self.as_bytes() else {
+            missing_capability(self.vtable, "from_pod_vec");
+        };
+        let size = padded_size(bytes.len() as BufferAddress) as usize;
+        if size == bytes.len() {
+            queue.write_buffer(buffer, offset, bytes);
+        } else {
+            let mut padded = vec![0; size];
+            padded[..bytes.len()].copy_from_slice(bytes);
+            queue.write_buffer(buffer, offset, &padded);
+        }
+    }
+}
+
+impl TypeErasedVec<Global> {
+    /// Copies the elements out of a mapped buffer, e.g. one written by a compute shader and mapped with
+    /// [wgpu::BufferSlice::map_async].
+    ///
+    /// The padding of the buffer is read as elements too, use [TypeErasedVec::truncate] to drop it.
+    ///
+    /// Fails if the mapped length is not a multiple of the size of `scalar_type`.
+    pub fn from_wgpu_buffer_view(
+        view: &wgpu::BufferView,
+        scalar_type: ScalarType,
+    ) -> Result<Self, Error> {
+        let mut vec = Self::new_scalar(scalar_type);
+        vec.try_extend_from_byte_slice(view)?;
+        Ok(vec)
+    }
+}
+
+/// Rounds `len` up to [COPY_BUFFER_ALIGNMENT].
+fn padded_size(len: BufferAddress) -> BufferAddress {
+    len.next_multiple_of(COPY_BUFFER_ALIGNMENT)
+}
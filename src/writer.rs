@@ -0,0 +1,67 @@
+//! Writing bytes into vecs of bytes.
+
+use std::{
+    alloc::Layout,
+    io::{self, Write},
+};
+
+use crate::{Allocator, Global, TypeErasedVec};
+
+/// A [Write] appending to a [TypeErasedVec] of byte elements, like `u8`, created by [TypeErasedVec::byte_writer].
+///
+/// Lets encoders and compressors write straight into the buffer, like they would into a `Vec<u8>`.
+///
+/// ```
+/// use std::io::Write;
+///
+/// use type_erased_vec::TypeErasedVec;
+///
+/// let mut vec = TypeErasedVec::from_scalar_vec(b"ab".to_vec());
+/// write!(vec.byte_writer().unwrap(), "{}", 12).unwrap();
+/// assert_eq!(unsafe { vec.get::<u8>() }, b"ab12");
+/// ```
+#[derive(Debug)]
+pub struct ByteWriter<'a, A: Allocator = Global> {
+    vec: &'a mut TypeErasedVec<A>,
+}
+
+impl<A: Allocator> TypeErasedVec<A> {
+    /// Gets a [ByteWriter] appending to `self`, or `None` if the elements are not plain-old-data bytes,
+    /// i.e. the vec was not constructed with [TypeErasedVec::from_pod_vec] or [TypeErasedVec::from_scalar_vec]
+    /// with one byte elements like `u8` or `i8`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is leaked.
+    #[track_caller]
+    pub fn byte_writer(&mut self) -> Option<ByteWriter<'_, A>> {
+        raw!(self);
+        (self.vtable.pod && self.vtable.layout == Layout::new::<u8>())
+            .then_some(ByteWriter { vec: self })
+    }
+}
+
+impl<A: Allocator> ByteWriter<'_, A> {
+    /// Gets the vec written to.
+    pub fn as_erased(&self) -> &TypeErasedVec<A> {
+        self.vec
+    }
+}
+
+impl<A: Allocator> Write for ByteWriter<'_, A> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        // Every byte is a whole element, so only the allocation can fail.
+        self.vec
+            .try_extend_from_byte_slice(buf)
+            .map_err(|_| io::Error::from(io::ErrorKind::OutOfMemory))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+//! Derive macro for [type_erased_vec](https://docs.rs/type_erased_vec) element vtables.
+//!
+//! Use it through the `derive` feature of `type_erased_vec`, which re-exports [ErasedElement](macro@ErasedElement).
+
+#![deny(missing_docs)]
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, DeriveInput, Ident};
+
+/// Capabilities that can be listed in `#[erased_element(...)]`, and the `TypedVTable` builder each one calls.
+const CAPABILITIES: &[(&str, &str)] = &[
+    ("clone", "with_clone"),
+    ("eq", "with_eq"),
+    ("ord", "with_ord"),
+    ("hash", "with_hash"),
+    ("debug", "with_debug"),
+    ("pod", "with_pod"),
+    ("zeroable", "with_zeroable"),
+    ("serialize", "with_serialize"),
+    ("deserialize", "with_deserialize"),
+];
+
+/// Implements `ErasedElement`, building the element vtable from the capabilities listed in `#[erased_element(...)]`.
+///
+/// The vtable always knows how to drop elements. Each listed capability requires the matching trait:
+///
+/// | Capability    | Requires                           |
+/// |---------------|------------------------------------|
+/// | `clone`       | `Clone`                            |
+/// | `eq`          | `PartialEq`, and the `type-id` feature |
+/// | `ord`         | `Ord`                              |
+/// | `hash`        | `Hash`                             |
+/// | `debug`       | `Debug`                            |
+/// | `pod`         | `bytemuck::Pod`, and the `bytemuck` feature |
+/// | `zeroable`    | `bytemuck::Zeroable`, and the `bytemuck` feature |
+/// | `serialize`   | `serde::Serialize`, and the `serde` feature |
+/// | `deserialize` | `serde::de::DeserializeOwned`, and the `serde` feature |
+#[proc_macro_derive(ErasedElement, attributes(erased_element))]
+pub fn derive_erased_element(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut builders = Vec::new();
+    for attr in &input.attrs {
+        if !attr.path().is_ident("erased_element") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            let builder = CAPABILITIES
+                .iter()
+                .find(|(name, _)| meta.path.is_ident(name))
+                .ok_or_else(|| meta.error("unknown capability"))?
+                .1;
+            builders.push(Ident::new(builder, meta.path.span()));
+            Ok(())
+        })?;
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::type_erased_vec::ErasedElement for #name #ty_generics #where_clause {
+            const VTABLE: ::type_erased_vec::TypedVTable<Self> =
+                ::type_erased_vec::TypedVTable::new() #(.#builders())*;
+        }
+    })
+}